@@ -60,4 +60,6 @@ fn get_version(impl_commit: &str) -> String {
 
 fn main() {
     generate_cargo_keys();
+    // sqlx::migrate! 在編譯時讀取 migrations/
+    println!("cargo:rerun-if-changed=migrations");
 }
\ No newline at end of file
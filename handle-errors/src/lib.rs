@@ -25,6 +25,7 @@ pub enum Error {
     TokenNotFound,
     CacheError(RedisError),
     StdFileErroor(stdIoError),
+    DatasetNotFound(String),
 }
 
 impl std::fmt::Display for Error {
@@ -60,6 +61,9 @@ impl std::fmt::Display for Error {
             Error::StdFileErroor(ref err) => {
                 write!(f, "std file 錯誤: {}", err)
             }
+            Error::DatasetNotFound(ref name) => {
+                write!(f, "沒有資料集 {}", name)
+            }
         }
     }
 }
@@ -72,10 +76,13 @@ const DUPLICATE_KET: u32 = 23505;
 pub async fn return_error(r: Rejection) -> Result<impl Reply, Rejection> {
     if let Some(error) = r.find::<Error>() {
         event!(Level::ERROR, "{}", error);
-        Ok(warp::reply::with_status(
-            error.to_string(),
-            StatusCode::UNPROCESSABLE_ENTITY,
-        ))
+        // 未登入或無權限的請求回 401，讓前端可以導向登入頁
+        let status = match error {
+            Error::Unauthorized => StatusCode::UNAUTHORIZED,
+            Error::DatasetNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Ok(warp::reply::with_status(error.to_string(), status))
     } else if let Some(error) = r.find::<RedisError>() {
         event!(Level::ERROR, "{}", error);
         Ok(warp::reply::with_status(
@@ -0,0 +1,242 @@
+-- 完整的資料表結構；既有資料庫上重跑不影響資料，缺少的欄位以 ADD COLUMN 補上
+
+CREATE TABLE IF NOT EXISTS accounts (
+    email TEXT PRIMARY KEY,
+    user_name TEXT NOT NULL UNIQUE,
+    password TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS directory (
+    id TEXT PRIMARY KEY, -- user_name-directory
+    user_name TEXT NOT NULL,
+    directory TEXT NOT NULL,
+    public BOOLEAN NOT NULL DEFAULT FALSE,
+    description TEXT NOT NULL DEFAULT '',
+    note_order TEXT[] NOT NULL DEFAULT '{}'
+);
+
+CREATE TABLE IF NOT EXISTS note (
+    id TEXT PRIMARY KEY, -- user_name-directory-file_name
+    user_name TEXT NOT NULL,
+    directory TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content JSONB,
+    footer TEXT,
+    public BOOLEAN NOT NULL DEFAULT FALSE,
+    date TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS file (
+    id TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    css TEXT NOT NULL,
+    user_name TEXT NOT NULL,
+    directory TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content_nav TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS note_reference (
+    note_id TEXT NOT NULL,
+    ref_type TEXT NOT NULL,
+    ref_id TEXT NOT NULL,
+    PRIMARY KEY (note_id, ref_type, ref_id)
+);
+
+CREATE TABLE IF NOT EXISTS note_stale_card (
+    note_id TEXT NOT NULL,
+    block_index INTEGER NOT NULL,
+    law_id TEXT NOT NULL,
+    card_lines JSONB NOT NULL,
+    current_lines JSONB NOT NULL,
+    checked_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS note_revision (
+    id TEXT PRIMARY KEY,
+    note_id TEXT NOT NULL,
+    content JSONB,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS library (
+    id TEXT PRIMARY KEY,
+    library_name TEXT NOT NULL,
+    user_name TEXT NOT NULL,
+    public BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE TABLE IF NOT EXISTS library_item (
+    id TEXT PRIMARY KEY,
+    item_library TEXT NOT NULL,
+    item_type TEXT NOT NULL,
+    item_name TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    ordering SMALLINT NOT NULL DEFAULT 1 -- favorite 為 99
+);
+
+CREATE TABLE IF NOT EXISTS dictionary (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    user_name TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS vocab_item (
+    id TEXT PRIMARY KEY,
+    user_name TEXT NOT NULL,
+    term TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    dictionary TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS vocabitem_law (
+    vocabitem_id TEXT NOT NULL,
+    law_id TEXT NOT NULL,
+    PRIMARY KEY (vocabitem_id, law_id)
+);
+
+CREATE TABLE IF NOT EXISTS law_alias (
+    alias TEXT PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+-- 以下由 otherlawresource 與 new_law 的爬蟲寫入
+
+CREATE TABLE IF NOT EXISTS newlaw (
+    id TEXT PRIMARY KEY, -- 法規名稱-條號
+    href TEXT NOT NULL,
+    chapter TEXT[] NOT NULL,
+    num TEXT NOT NULL,
+    lines JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+ALTER TABLE newlaw ADD COLUMN IF NOT EXISTS content_hash TEXT;
+ALTER TABLE newlaw ADD COLUMN IF NOT EXISTS source_url TEXT;
+ALTER TABLE newlaw ADD COLUMN IF NOT EXISTS fetched_at TIMESTAMPTZ;
+ALTER TABLE newlaw ADD COLUMN IF NOT EXISTS upstream_date TEXT;
+
+CREATE TABLE IF NOT EXISTS lawinformation (
+    originalid TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    update_date TEXT NOT NULL,
+    release_date TEXT NOT NULL
+);
+ALTER TABLE lawinformation ADD COLUMN IF NOT EXISTS content_hash TEXT;
+
+CREATE TABLE IF NOT EXISTS history_law (
+    id TEXT PRIMARY KEY,
+    lawid TEXT NOT NULL, -- 法規名稱-條號
+    date TEXT NOT NULL,
+    content TEXT NOT NULL,
+    no SMALLINT NOT NULL
+);
+ALTER TABLE history_law ADD COLUMN IF NOT EXISTS source TEXT;
+
+CREATE TABLE IF NOT EXISTS newinters (
+    id TEXT PRIMARY KEY,
+    casename TEXT NOT NULL,
+    name TEXT NOT NULL,
+    casesummary TEXT,
+    date TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    maincontent TEXT[] NOT NULL,
+    related_law TEXT,
+    source TEXT NOT NULL,
+    year SMALLINT NOT NULL,
+    number SMALLINT NOT NULL
+);
+ALTER TABLE newinters ADD COLUMN IF NOT EXISTS reflaws TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE TABLE IF NOT EXISTS newinterpretations (
+    id TEXT PRIMARY KEY,
+    no TEXT NOT NULL,
+    name TEXT NOT NULL,
+    date TEXT NOT NULL,
+    reason TEXT,
+    content TEXT,
+    related_law TEXT,
+    source TEXT NOT NULL,
+    year SMALLINT NOT NULL,
+    number SMALLINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS oldinters (
+    id TEXT PRIMARY KEY,
+    date TEXT NOT NULL,
+    reasoning TEXT,
+    content TEXT,
+    trouble TEXT,
+    related_law TEXT,
+    source TEXT NOT NULL
+);
+ALTER TABLE oldinters ADD COLUMN IF NOT EXISTS reflaws TEXT[];
+ALTER TABLE oldinters ADD COLUMN IF NOT EXISTS reflawid TEXT[];
+ALTER TABLE oldinters ADD COLUMN IF NOT EXISTS refinter TEXT[];
+
+CREATE TABLE IF NOT EXISTS precedents (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    holding TEXT NOT NULL,
+    source TEXT NOT NULL,
+    year SMALLINT NOT NULL,
+    num SMALLINT NOT NULL,
+    specific TEXT NOT NULL
+);
+ALTER TABLE precedents ADD COLUMN IF NOT EXISTS court TEXT;
+ALTER TABLE precedents ADD COLUMN IF NOT EXISTS case_type TEXT;
+ALTER TABLE precedents ADD COLUMN IF NOT EXISTS reflawid TEXT[];
+ALTER TABLE precedents ADD COLUMN IF NOT EXISTS refinter TEXT[];
+ALTER TABLE precedents ADD COLUMN IF NOT EXISTS refprecedent TEXT[];
+
+CREATE TABLE IF NOT EXISTS resolution (
+    id TEXT PRIMARY KEY,
+    lawtype TEXT NOT NULL,
+    related_law TEXT NOT NULL,
+    name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    source TEXT NOT NULL,
+    year SMALLINT NOT NULL,
+    time SMALLINT NOT NULL
+);
+ALTER TABLE resolution ADD COLUMN IF NOT EXISTS reflawid TEXT[];
+ALTER TABLE resolution ADD COLUMN IF NOT EXISTS refinter TEXT[];
+ALTER TABLE resolution ADD COLUMN IF NOT EXISTS refprecedent TEXT[];
+
+-- 出處：擷取時間、內容雜湊與來源公布日期
+ALTER TABLE history_law ADD COLUMN IF NOT EXISTS fetched_at TIMESTAMPTZ;
+ALTER TABLE history_law ADD COLUMN IF NOT EXISTS content_hash TEXT;
+ALTER TABLE history_law ADD COLUMN IF NOT EXISTS upstream_date TEXT;
+ALTER TABLE newinters ADD COLUMN IF NOT EXISTS fetched_at TIMESTAMPTZ;
+ALTER TABLE newinters ADD COLUMN IF NOT EXISTS content_hash TEXT;
+ALTER TABLE newinters ADD COLUMN IF NOT EXISTS upstream_date TEXT;
+ALTER TABLE newinterpretations ADD COLUMN IF NOT EXISTS fetched_at TIMESTAMPTZ;
+ALTER TABLE newinterpretations ADD COLUMN IF NOT EXISTS content_hash TEXT;
+ALTER TABLE newinterpretations ADD COLUMN IF NOT EXISTS upstream_date TEXT;
+ALTER TABLE oldinters ADD COLUMN IF NOT EXISTS fetched_at TIMESTAMPTZ;
+ALTER TABLE oldinters ADD COLUMN IF NOT EXISTS content_hash TEXT;
+ALTER TABLE oldinters ADD COLUMN IF NOT EXISTS upstream_date TEXT;
+ALTER TABLE precedents ADD COLUMN IF NOT EXISTS fetched_at TIMESTAMPTZ;
+ALTER TABLE precedents ADD COLUMN IF NOT EXISTS content_hash TEXT;
+ALTER TABLE precedents ADD COLUMN IF NOT EXISTS upstream_date TEXT;
+ALTER TABLE resolution ADD COLUMN IF NOT EXISTS fetched_at TIMESTAMPTZ;
+ALTER TABLE resolution ADD COLUMN IF NOT EXISTS content_hash TEXT;
+ALTER TABLE resolution ADD COLUMN IF NOT EXISTS upstream_date TEXT;
+
+CREATE TABLE IF NOT EXISTS sync_run (
+    id BIGSERIAL PRIMARY KEY,
+    source TEXT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    finished_at TIMESTAMPTZ,
+    inserted TEXT[] NOT NULL DEFAULT '{}',
+    updated TEXT[] NOT NULL DEFAULT '{}',
+    unchanged TEXT[] NOT NULL DEFAULT '{}',
+    failed TEXT[] NOT NULL DEFAULT '{}'
+);
+
+CREATE TABLE IF NOT EXISTS sync_checkpoint (
+    source TEXT NOT NULL,
+    key TEXT NOT NULL,
+    done_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (source, key)
+);
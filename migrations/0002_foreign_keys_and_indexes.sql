@@ -0,0 +1,65 @@
+-- 筆記屬於目錄、目錄屬於帳號；既有資料庫可能已有不符的資料，外鍵以 NOT VALID 加上，只檢查之後寫入的資料
+
+CREATE UNIQUE INDEX IF NOT EXISTS directory_user_name_directory ON directory (user_name, directory);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'directory_user_name_fkey') THEN
+        ALTER TABLE directory ADD CONSTRAINT directory_user_name_fkey
+            FOREIGN KEY (user_name) REFERENCES accounts (user_name)
+            ON UPDATE CASCADE ON DELETE CASCADE NOT VALID;
+    END IF;
+
+    -- 刪除目錄時一併刪除其中的筆記
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'note_directory_fkey') THEN
+        ALTER TABLE note ADD CONSTRAINT note_directory_fkey
+            FOREIGN KEY (user_name, directory) REFERENCES directory (user_name, directory)
+            ON UPDATE CASCADE ON DELETE CASCADE NOT VALID;
+    END IF;
+
+    -- 筆記改名會改變 id，附屬資料跟著更新
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'note_reference_note_id_fkey') THEN
+        ALTER TABLE note_reference ADD CONSTRAINT note_reference_note_id_fkey
+            FOREIGN KEY (note_id) REFERENCES note (id)
+            ON UPDATE CASCADE ON DELETE CASCADE NOT VALID;
+    END IF;
+
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'note_stale_card_note_id_fkey') THEN
+        ALTER TABLE note_stale_card ADD CONSTRAINT note_stale_card_note_id_fkey
+            FOREIGN KEY (note_id) REFERENCES note (id)
+            ON UPDATE CASCADE ON DELETE CASCADE NOT VALID;
+    END IF;
+
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'note_revision_note_id_fkey') THEN
+        ALTER TABLE note_revision ADD CONSTRAINT note_revision_note_id_fkey
+            FOREIGN KEY (note_id) REFERENCES note (id)
+            ON UPDATE CASCADE ON DELETE CASCADE NOT VALID;
+    END IF;
+
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'library_item_item_library_fkey') THEN
+        ALTER TABLE library_item ADD CONSTRAINT library_item_item_library_fkey
+            FOREIGN KEY (item_library) REFERENCES library (id)
+            ON DELETE CASCADE NOT VALID;
+    END IF;
+
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'vocabitem_law_vocabitem_id_fkey') THEN
+        ALTER TABLE vocabitem_law ADD CONSTRAINT vocabitem_law_vocabitem_id_fkey
+            FOREIGN KEY (vocabitem_id) REFERENCES vocab_item (id)
+            ON DELETE CASCADE NOT VALID;
+    END IF;
+END
+$$;
+
+CREATE INDEX IF NOT EXISTS note_user_name_directory ON note (user_name, directory);
+CREATE INDEX IF NOT EXISTS file_user_name_directory ON file (user_name, directory);
+CREATE INDEX IF NOT EXISTS library_user_name ON library (user_name);
+CREATE INDEX IF NOT EXISTS library_item_item_library ON library_item (item_library);
+CREATE INDEX IF NOT EXISTS dictionary_user_name ON dictionary (user_name);
+CREATE INDEX IF NOT EXISTS vocab_item_user_name_dictionary ON vocab_item (user_name, dictionary);
+CREATE INDEX IF NOT EXISTS vocabitem_law_law_id ON vocabitem_law (law_id);
+CREATE INDEX IF NOT EXISTS note_reference_ref ON note_reference (ref_type, ref_id);
+CREATE INDEX IF NOT EXISTS note_stale_card_note_id ON note_stale_card (note_id);
+CREATE INDEX IF NOT EXISTS note_revision_note_id ON note_revision (note_id);
+CREATE INDEX IF NOT EXISTS history_law_lawid ON history_law (lawid);
+CREATE INDEX IF NOT EXISTS newlaw_created_at ON newlaw (created_at);
+CREATE INDEX IF NOT EXISTS sync_run_source ON sync_run (source);
@@ -0,0 +1,60 @@
+-- 筆記與目錄的 id 改為 uuid，舊的複合 id（user_name-directory[-file_name]）保留為唯一的 slug，
+-- 名稱含有「-」時不再需要拆解 id；舊網址以 slug 查詢仍可使用
+
+CREATE EXTENSION IF NOT EXISTS pgcrypto; -- gen_random_uuid()，PostgreSQL 13 以前需要
+
+ALTER TABLE directory ADD COLUMN IF NOT EXISTS slug TEXT;
+ALTER TABLE note ADD COLUMN IF NOT EXISTS slug TEXT;
+ALTER TABLE note ADD COLUMN IF NOT EXISTS directory_id TEXT;
+
+-- note_reference、note_stale_card、note_revision 的 note_id 由 0002 的 ON UPDATE CASCADE 跟著更新
+UPDATE directory SET slug = id, id = gen_random_uuid()::text WHERE slug IS NULL;
+UPDATE note SET slug = id, id = gen_random_uuid()::text WHERE slug IS NULL;
+
+-- 收藏中的筆記與目錄存的是舊 id，換成新的 id；舊 id 同時是筆記與目錄時以筆記為準
+UPDATE library_item SET item_id = note.id FROM note WHERE library_item.item_id = note.slug;
+UPDATE library_item SET item_id = directory.id FROM directory WHERE library_item.item_id = directory.slug;
+
+-- 沒有對應目錄的筆記補上目錄，slug 與 Directory::make_slug 相同；帳號已不存在的筆記刪除
+INSERT INTO directory (id, slug, user_name, directory)
+SELECT gen_random_uuid()::text,
+    replace(replace(missing.user_name, '%', '%25'), '-', '%2D') || '-'
+        || replace(replace(missing.directory, '%', '%25'), '-', '%2D'),
+    missing.user_name,
+    missing.directory
+FROM (
+    SELECT DISTINCT note.user_name, note.directory
+    FROM note
+    JOIN accounts ON accounts.user_name = note.user_name
+    WHERE NOT EXISTS (
+        SELECT 1 FROM directory
+        WHERE directory.user_name = note.user_name
+        AND directory.directory = note.directory
+    )
+) missing;
+
+UPDATE note
+SET directory_id = directory.id
+FROM directory
+WHERE note.directory_id IS NULL
+AND note.user_name = directory.user_name
+AND note.directory = directory.directory;
+
+DELETE FROM note WHERE directory_id IS NULL;
+
+ALTER TABLE directory ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE note ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE note ALTER COLUMN directory_id SET NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS directory_slug ON directory (slug);
+CREATE UNIQUE INDEX IF NOT EXISTS note_slug ON note (slug);
+CREATE INDEX IF NOT EXISTS note_directory_id ON note (directory_id);
+
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'note_directory_id_fkey') THEN
+        ALTER TABLE note ADD CONSTRAINT note_directory_id_fkey
+            FOREIGN KEY (directory_id) REFERENCES directory (id)
+            ON DELETE CASCADE;
+    END IF;
+END
+$$;
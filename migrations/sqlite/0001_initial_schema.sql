@@ -0,0 +1,237 @@
+-- 與 migrations/ 中 PostgreSQL 相同的資料表，陣列與 jsonb 欄位改存 JSON 文字，時間存 RFC 3339 文字
+
+CREATE TABLE IF NOT EXISTS accounts (
+    user_name TEXT NOT NULL UNIQUE,
+    email TEXT PRIMARY KEY,
+    password TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS file (
+    id TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    css TEXT NOT NULL,
+    user_name TEXT NOT NULL,
+    directory TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content_nav TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS directory (
+    id TEXT PRIMARY KEY,
+    user_name TEXT NOT NULL,
+    directory TEXT NOT NULL,
+    public BOOLEAN NOT NULL DEFAULT FALSE,
+    description TEXT NOT NULL DEFAULT '',
+    note_order TEXT NOT NULL DEFAULT '[]'
+);
+
+CREATE TABLE IF NOT EXISTS note (
+    id TEXT PRIMARY KEY,
+    user_name TEXT NOT NULL,
+    directory TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content TEXT,
+    footer TEXT,
+    public BOOLEAN NOT NULL DEFAULT FALSE,
+    date TEXT
+);
+
+CREATE TABLE IF NOT EXISTS note_reference (
+    note_id TEXT NOT NULL,
+    ref_type TEXT NOT NULL,
+    ref_id TEXT NOT NULL,
+    PRIMARY KEY (note_id, ref_type, ref_id)
+);
+
+CREATE TABLE IF NOT EXISTS note_stale_card (
+    note_id TEXT NOT NULL,
+    block_index INTEGER NOT NULL,
+    law_id TEXT NOT NULL,
+    card_lines TEXT NOT NULL,
+    current_lines TEXT NOT NULL,
+    checked_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS note_revision (
+    id TEXT PRIMARY KEY,
+    note_id TEXT NOT NULL,
+    content TEXT,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS library (
+    id TEXT PRIMARY KEY,
+    library_name TEXT NOT NULL,
+    user_name TEXT NOT NULL,
+    public BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE TABLE IF NOT EXISTS library_item (
+    id TEXT PRIMARY KEY,
+    item_library TEXT NOT NULL,
+    item_type TEXT NOT NULL,
+    item_name TEXT NOT NULL,
+    item_id TEXT NOT NULL,
+    ordering INTEGER NOT NULL DEFAULT 1
+);
+
+CREATE TABLE IF NOT EXISTS dictionary (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    user_name TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS vocab_item (
+    id TEXT PRIMARY KEY,
+    user_name TEXT NOT NULL,
+    term TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    dictionary TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS vocabitem_law (
+    vocabitem_id TEXT NOT NULL,
+    law_id TEXT NOT NULL,
+    PRIMARY KEY (vocabitem_id, law_id)
+);
+
+CREATE TABLE IF NOT EXISTS law_alias (
+    alias TEXT PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS newlaw (
+    id TEXT PRIMARY KEY,
+    href TEXT NOT NULL,
+    chapter TEXT NOT NULL,
+    num TEXT NOT NULL,
+    lines TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    content_hash TEXT,
+    source_url TEXT,
+    fetched_at TEXT,
+    upstream_date TEXT
+);
+
+CREATE TABLE IF NOT EXISTS lawinformation (
+    originalid TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    update_date TEXT NOT NULL,
+    release_date TEXT NOT NULL,
+    content_hash TEXT
+);
+
+CREATE TABLE IF NOT EXISTS history_law (
+    id TEXT PRIMARY KEY,
+    lawid TEXT NOT NULL,
+    date TEXT NOT NULL,
+    content TEXT NOT NULL,
+    no INTEGER NOT NULL,
+    source TEXT,
+    fetched_at TEXT,
+    content_hash TEXT,
+    upstream_date TEXT
+);
+
+CREATE TABLE IF NOT EXISTS newinters (
+    id TEXT PRIMARY KEY,
+    casename TEXT NOT NULL,
+    name TEXT NOT NULL,
+    casesummary TEXT,
+    date TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    maincontent TEXT NOT NULL,
+    related_law TEXT,
+    source TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    number INTEGER NOT NULL,
+    reflaws TEXT NOT NULL DEFAULT '[]',
+    fetched_at TEXT,
+    content_hash TEXT,
+    upstream_date TEXT
+);
+
+CREATE TABLE IF NOT EXISTS newinterpretations (
+    id TEXT PRIMARY KEY,
+    no TEXT NOT NULL,
+    name TEXT NOT NULL,
+    date TEXT NOT NULL,
+    reason TEXT,
+    content TEXT,
+    related_law TEXT,
+    source TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    number INTEGER NOT NULL,
+    fetched_at TEXT,
+    content_hash TEXT,
+    upstream_date TEXT
+);
+
+CREATE TABLE IF NOT EXISTS oldinters (
+    id TEXT PRIMARY KEY,
+    date TEXT NOT NULL,
+    reasoning TEXT,
+    content TEXT,
+    trouble TEXT,
+    related_law TEXT,
+    source TEXT NOT NULL,
+    reflaws TEXT,
+    reflawid TEXT,
+    refinter TEXT,
+    fetched_at TEXT,
+    content_hash TEXT,
+    upstream_date TEXT
+);
+
+CREATE TABLE IF NOT EXISTS precedents (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    holding TEXT NOT NULL,
+    source TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    num INTEGER NOT NULL,
+    specific TEXT NOT NULL,
+    court TEXT,
+    case_type TEXT,
+    reflawid TEXT,
+    refinter TEXT,
+    refprecedent TEXT,
+    fetched_at TEXT,
+    content_hash TEXT,
+    upstream_date TEXT
+);
+
+CREATE TABLE IF NOT EXISTS resolution (
+    id TEXT PRIMARY KEY,
+    lawtype TEXT NOT NULL,
+    related_law TEXT NOT NULL,
+    name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    source TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    time INTEGER NOT NULL,
+    reflawid TEXT,
+    refinter TEXT,
+    refprecedent TEXT,
+    fetched_at TEXT,
+    content_hash TEXT,
+    upstream_date TEXT
+);
+
+CREATE TABLE IF NOT EXISTS sync_run (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL,
+    started_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TEXT,
+    inserted TEXT NOT NULL DEFAULT '[]',
+    updated TEXT NOT NULL DEFAULT '[]',
+    unchanged TEXT NOT NULL DEFAULT '[]',
+    failed TEXT NOT NULL DEFAULT '[]'
+);
+
+CREATE INDEX IF NOT EXISTS history_law_lawid ON history_law (lawid);
+
+CREATE UNIQUE INDEX IF NOT EXISTS directory_user_name_directory ON directory (user_name, directory);
+
+CREATE INDEX IF NOT EXISTS note_user_name_directory ON note (user_name, directory);
@@ -0,0 +1,116 @@
+-- 與 PostgreSQL 的 0003 相同：筆記與目錄的 id 改為 uuid，舊的 id（user_name-directory[-file_name]）保留為 slug，
+-- 舊網址以 slug 查詢仍可使用。SQLite 無法替既有欄位加上外鍵，note 與引用筆記的資料表以新結構重建
+
+ALTER TABLE directory ADD COLUMN slug TEXT;
+ALTER TABLE note ADD COLUMN slug TEXT;
+
+UPDATE directory
+SET slug = id,
+    id = lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4'
+        || substr(lower(hex(randomblob(2))), 2) || '-'
+        || substr('89ab', 1 + abs(random()) % 4, 1) || substr(lower(hex(randomblob(2))), 2) || '-'
+        || lower(hex(randomblob(6)))
+WHERE slug IS NULL;
+
+UPDATE note
+SET slug = id,
+    id = lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4'
+        || substr(lower(hex(randomblob(2))), 2) || '-'
+        || substr('89ab', 1 + abs(random()) % 4, 1) || substr(lower(hex(randomblob(2))), 2) || '-'
+        || lower(hex(randomblob(6)))
+WHERE slug IS NULL;
+
+-- 收藏中的筆記與目錄存的是舊 id，換成新的 id；舊 id 同時是筆記與目錄時以筆記為準
+UPDATE library_item
+SET item_id = (SELECT note.id FROM note WHERE note.slug = library_item.item_id)
+WHERE item_id IN (SELECT slug FROM note);
+UPDATE library_item
+SET item_id = (SELECT directory.id FROM directory WHERE directory.slug = library_item.item_id)
+WHERE item_id IN (SELECT slug FROM directory);
+
+-- 沒有對應目錄的筆記補上目錄，slug 與 Directory::make_slug 相同
+INSERT INTO directory (id, slug, user_name, directory)
+SELECT lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4'
+        || substr(lower(hex(randomblob(2))), 2) || '-'
+        || substr('89ab', 1 + abs(random()) % 4, 1) || substr(lower(hex(randomblob(2))), 2) || '-'
+        || lower(hex(randomblob(6))),
+    replace(replace(user_name, '%', '%25'), '-', '%2D') || '-'
+        || replace(replace(directory, '%', '%25'), '-', '%2D'),
+    user_name,
+    directory
+FROM (
+    SELECT DISTINCT note.user_name, note.directory
+    FROM note
+    WHERE NOT EXISTS (
+        SELECT 1 FROM directory
+        WHERE directory.user_name = note.user_name
+        AND directory.directory = note.directory
+    )
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS directory_slug ON directory (slug);
+
+-- SQLite 無法替既有欄位加上 NOT NULL 與外鍵，note 以新結構重建，directory_id 依名稱找出目錄
+CREATE TABLE note_new (
+    id TEXT PRIMARY KEY,
+    slug TEXT NOT NULL,
+    directory_id TEXT NOT NULL REFERENCES directory (id) ON DELETE CASCADE,
+    user_name TEXT NOT NULL,
+    directory TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    content TEXT,
+    footer TEXT,
+    public BOOLEAN NOT NULL DEFAULT FALSE,
+    date TEXT
+);
+INSERT INTO note_new (id, slug, directory_id, user_name, directory, file_name, content, footer, public, date)
+SELECT note.id, note.slug, directory.id, note.user_name, note.directory, note.file_name,
+    note.content, note.footer, note.public, note.date
+FROM note
+JOIN directory ON directory.user_name = note.user_name AND directory.directory = note.directory;
+DROP TABLE note;
+ALTER TABLE note_new RENAME TO note;
+
+CREATE INDEX IF NOT EXISTS note_user_name_directory ON note (user_name, directory);
+CREATE UNIQUE INDEX IF NOT EXISTS note_slug ON note (slug);
+CREATE INDEX IF NOT EXISTS note_directory_id ON note (directory_id);
+
+-- 舊資料的 note_id 是現在的 slug，換成新的 id；已不存在的筆記不再保留
+CREATE TABLE note_reference_new (
+    note_id TEXT NOT NULL REFERENCES note (id) ON DELETE CASCADE,
+    ref_type TEXT NOT NULL,
+    ref_id TEXT NOT NULL,
+    PRIMARY KEY (note_id, ref_type, ref_id)
+);
+INSERT INTO note_reference_new (note_id, ref_type, ref_id)
+SELECT note.id, note_reference.ref_type, note_reference.ref_id
+FROM note_reference JOIN note ON note.slug = note_reference.note_id;
+DROP TABLE note_reference;
+ALTER TABLE note_reference_new RENAME TO note_reference;
+
+CREATE TABLE note_stale_card_new (
+    note_id TEXT NOT NULL REFERENCES note (id) ON DELETE CASCADE,
+    block_index INTEGER NOT NULL,
+    law_id TEXT NOT NULL,
+    card_lines TEXT NOT NULL,
+    current_lines TEXT NOT NULL,
+    checked_at TEXT NOT NULL
+);
+INSERT INTO note_stale_card_new (note_id, block_index, law_id, card_lines, current_lines, checked_at)
+SELECT note.id, s.block_index, s.law_id, s.card_lines, s.current_lines, s.checked_at
+FROM note_stale_card s JOIN note ON note.slug = s.note_id;
+DROP TABLE note_stale_card;
+ALTER TABLE note_stale_card_new RENAME TO note_stale_card;
+
+CREATE TABLE note_revision_new (
+    id TEXT PRIMARY KEY,
+    note_id TEXT NOT NULL REFERENCES note (id) ON DELETE CASCADE,
+    content TEXT,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+INSERT INTO note_revision_new (id, note_id, content, reason, created_at)
+SELECT r.id, note.id, r.content, r.reason, r.created_at
+FROM note_revision r JOIN note ON note.slug = r.note_id;
+DROP TABLE note_revision;
+ALTER TABLE note_revision_new RENAME TO note_revision;
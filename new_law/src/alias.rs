@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::Row;
+use std::collections::HashMap;
+
+// 預設的法規簡稱，資料庫中的同名簡稱會覆蓋這些設定
+const BUILTIN_ALIASES: [(&str, &str); 16] = [
+    ("刑法", "中華民國刑法"),
+    ("憲法", "中華民國憲法"),
+    ("憲增", "中華民國憲法增修條文"),
+    ("民訴", "民事訴訟法"),
+    ("刑訴", "刑事訴訟法"),
+    ("行訴", "行政訴訟法"),
+    ("行程法", "行政程序法"),
+    ("行執法", "行政執行法"),
+    ("憲訴法", "憲法訴訟法"),
+    ("強執法", "強制執行法"),
+    ("家事法", "家事事件法"),
+    ("國賠法", "國家賠償法"),
+    ("公平法", "公平交易法"),
+    ("消保法", "消費者保護法"),
+    ("個資法", "個人資料保護法"),
+    ("勞基法", "勞動基準法"),
+];
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct LawAlias {
+    pub alias: String, // 簡稱，例如「民訴」
+    pub name: String,  // 完整法規名稱，例如「民事訴訟法」
+}
+
+/// 法規簡稱對照表，把使用者習慣的簡稱轉成 `NewLaws::categories(0)` 的法規名稱
+#[derive(Clone, Debug)]
+pub struct LawAliases {
+    map: HashMap<String, String>,
+}
+
+impl Default for LawAliases {
+    fn default() -> Self {
+        LawAliases::builtin()
+    }
+}
+
+impl LawAliases {
+    pub fn builtin() -> Self {
+        let map = BUILTIN_ALIASES
+            .iter()
+            .map(|(alias, name)| (alias.to_string(), name.to_string()))
+            .collect();
+        LawAliases { map }
+    }
+
+    /// 以預設簡稱為基礎，再套上資料庫中的設定
+    pub fn from_list(list: Vec<LawAlias>) -> Self {
+        let mut aliases = LawAliases::builtin();
+        for item in list {
+            aliases.insert(item);
+        }
+        aliases
+    }
+
+    pub fn insert(&mut self, item: LawAlias) {
+        self.map.insert(item.alias, item.name);
+    }
+
+    pub fn remove(&mut self, alias: &str) -> Option<String> {
+        self.map.remove(alias)
+    }
+
+    /// 找不到簡稱時原樣回傳，讓完整法規名稱也能直接使用
+    pub fn resolve<'a>(&'a self, name: &'a str) -> &'a str {
+        let trimmed = name.trim();
+        self.map.get(trimmed).map(|s| s.as_str()).unwrap_or(trimmed)
+    }
+
+    /// 將「刑法-2」這類以簡稱開頭的法條 id 轉為「中華民國刑法-2」
+    pub fn resolve_law_id(&self, id: &str) -> String {
+        match crate::law_id::split(id) {
+            Some((name, num)) => format!("{}-{}", self.resolve(name), num),
+            None => id.to_string(),
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.map.keys().cloned().collect()
+    }
+
+    pub fn list(&self) -> Vec<LawAlias> {
+        let mut list: Vec<LawAlias> = self
+            .map
+            .iter()
+            .map(|(alias, name)| LawAlias {
+                alias: alias.clone(),
+                name: name.clone(),
+            })
+            .collect();
+        list.sort_by(|a, b| (&a.name, &a.alias).cmp(&(&b.name, &b.alias)));
+        list
+    }
+}
+
+pub async fn get_law_aliases(pool: &PgPool) -> Result<Vec<LawAlias>, sqlx::Error> {
+    sqlx::query("SELECT alias, name FROM law_alias")
+        .map(|row: PgRow| LawAlias {
+            alias: row.get("alias"),
+            name: row.get("name"),
+        })
+        .fetch_all(pool)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_builtin_and_custom_aliases() {
+        let aliases = LawAliases::from_list(vec![LawAlias {
+            alias: "民訴".to_string(),
+            name: "民事訴訟法".to_string(),
+        }]);
+        assert_eq!(aliases.resolve("刑法"), "中華民國刑法");
+        assert_eq!(aliases.resolve("民訴"), "民事訴訟法");
+        assert_eq!(aliases.resolve("民法"), "民法");
+        assert_eq!(aliases.resolve_law_id("刑法-2"), "中華民國刑法-2");
+        assert_eq!(aliases.resolve_law_id("民法-184"), "民法-184");
+        assert_eq!(aliases.resolve_law_id("刑法-185-3"), "中華民國刑法-185-3");
+    }
+}
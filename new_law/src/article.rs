@@ -0,0 +1,134 @@
+use crate::NewLaw;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// 條號，例如「15」、「15-1」（第十五條之一），parts 為「之」後的各段
+/// 排序依序比較 main 與 parts，因此 15 < 15-1 < 15-2 < 15-10 < 16
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArticleNumber {
+    pub main: u32,
+    pub parts: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseArticleError(pub String);
+
+impl fmt::Display for ParseArticleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "無法解析條號：{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseArticleError {}
+
+fn chinese_digit(c: char) -> Option<u32> {
+    match c {
+        '〇' | '○' | '零' => Some(0),
+        '一' => Some(1),
+        '二' | '兩' => Some(2),
+        '三' => Some(3),
+        '四' => Some(4),
+        '五' => Some(5),
+        '六' => Some(6),
+        '七' => Some(7),
+        '八' => Some(8),
+        '九' => Some(9),
+        _ => None,
+    }
+}
+
+/// 解析阿拉伯數字或「一百八十四」這類中文數字
+pub fn parse_number(text: &str) -> Option<u32> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        return text.parse().ok();
+    }
+
+    let mut total: u32 = 0;
+    let mut current: Option<u32> = None;
+    for c in text.chars() {
+        let unit = match c {
+            '十' => 10,
+            '百' => 100,
+            '千' => 1000,
+            _ => {
+                // 「一〇五」這種逐位寫法也接受
+                let digit = chinese_digit(c)?;
+                current = Some(current.unwrap_or(0).checked_mul(10)?.checked_add(digit)?);
+                continue;
+            }
+        };
+        // 「十五」省略了前面的「一」
+        total = total.checked_add(current.take().unwrap_or(1).checked_mul(unit)?)?;
+    }
+    total.checked_add(current.unwrap_or(0))
+}
+
+impl FromStr for ArticleNumber {
+    type Err = ParseArticleError;
+
+    /// 接受「15-1」、「第十五條之一」、「第15條之1」
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseArticleError(s.to_string());
+        let text = s.trim().trim_start_matches('第').replace('條', "");
+        let mut numbers = text
+            .split(['-', '之'])
+            .map(|part| parse_number(part).ok_or_else(err));
+        let main = numbers.next().ok_or_else(err)??;
+        let parts = numbers.collect::<Result<Vec<u32>, _>>()?;
+        Ok(ArticleNumber { main, parts })
+    }
+}
+
+impl fmt::Display for ArticleNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.main)?;
+        for part in &self.parts {
+            write!(f, "-{}", part)?;
+        }
+        Ok(())
+    }
+}
+
+/// 比較兩個條號字串，無法解析的排在最後並以原字串比較，不會 panic
+pub fn compare_nums(a: &str, b: &str) -> Ordering {
+    match (a.parse::<ArticleNumber>(), b.parse::<ArticleNumber>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+/// 依條號排序
+pub fn sort_laws(laws: &mut [NewLaw]) {
+    laws.sort_by(|a, b| compare_nums(&a.num, &b.num));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_orders_article_numbers() {
+        let n = |s: &str| s.parse::<ArticleNumber>().unwrap();
+        assert_eq!(n("15-1"), n("第十五條之一"));
+        assert_eq!(n("第15條之1").to_string(), "15-1");
+        assert_eq!(n("第一百八十四條").main, 184);
+        assert_eq!(n("二十").main, 20);
+        assert!(n("15-2") < n("15-10"));
+        assert!(n("15") < n("15-1"));
+        assert!(n("15-10") < n("16"));
+        assert!("15-x".parse::<ArticleNumber>().is_err());
+        assert_eq!(parse_number("一〇五"), Some(105));
+        assert_eq!(parse_number("九九九九九九九九九九九"), None);
+
+        let mut nums = vec!["16", "abc", "15-10", "15-2", "15"];
+        nums.sort_by(|a, b| compare_nums(a, b));
+        assert_eq!(nums, vec!["15", "15-2", "15-10", "16", "abc"]);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::alias::LawAliases;
+use crate::article::ArticleNumber;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+static ARTICLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"第\s*([0-9〇零一二兩三四五六七八九十百千]+)\s*條(?:之\s*([0-9〇零一二兩三四五六七八九十]+))?").unwrap()
+});
+static INTERPRETATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"釋字第\s*(\d+)\s*號(?:解釋)?").unwrap());
+// 判例字號，例如「49年台上字第929號」，id 與判例資料相同為「49-台上-929」
+static PRECEDENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d{2,3})\s*年度?\s*([^\s\d年度字第，。、（）()]{1,4}?)\s*字\s*第\s*(\d{1,5})\s*號").unwrap()
+});
+
+// 前一個引用與「第N條」之間只隔著這些字時，視為同一部法規的連續引用
+const CONTINUATION: [&str; 6] = ["、", "及", "與", "或", "和", "，"];
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationKind {
+    Law,               // id 例如「民法-184」、「民法-877-1」
+    OldInterpretation, // id 為釋字號碼，例如「748」
+    Precedent,         // id 例如「49-台上-929」
+}
+
+impl CitationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CitationKind::Law => "law",
+            CitationKind::OldInterpretation => "oldinterpretation",
+            CitationKind::Precedent => "precedent",
+        }
+    }
+}
+
+/// 文字中的一筆引用，start/end 為 byte 位置
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Citation {
+    pub kind: CitationKind,
+    pub id: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// 從一般文字中找出「民法第184條」、「釋字第748號」、「49年台上字第929號」這類引用
+pub struct CitationFinder<'a> {
+    names: HashSet<&'a str>,
+    aliases: &'a LawAliases,
+    max_chars: usize,
+}
+
+impl<'a> CitationFinder<'a> {
+    /// `law_names` 通常是 `NewLaws::categories(0)` 的 key
+    pub fn new<I>(law_names: I, aliases: &'a LawAliases) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let names: HashSet<&'a str> = law_names.into_iter().collect();
+        let max_chars = names
+            .iter()
+            .map(|name| name.chars().count())
+            .chain(aliases.names().iter().map(|name| name.chars().count()))
+            .max()
+            .unwrap_or(0);
+        CitationFinder {
+            names,
+            aliases,
+            max_chars,
+        }
+    }
+
+    // 在「第N條」前方找出最長的已知法規名稱或簡稱，回傳 (起始位置, 完整名稱)
+    fn law_before(&self, prefix: &str) -> Option<(usize, &str)> {
+        let starts: Vec<usize> = prefix
+            .char_indices()
+            .rev()
+            .take(self.max_chars)
+            .map(|(i, _)| i)
+            .collect();
+        for start in starts.into_iter().rev() {
+            let name = self.aliases.resolve(&prefix[start..]);
+            if let Some(name) = self.names.get(name) {
+                return Some((start, name));
+            }
+        }
+        None
+    }
+
+    pub fn find(&self, text: &str) -> Vec<Citation> {
+        let mut citations = Vec::new();
+
+        let mut last_law: Option<(&str, usize)> = None;
+        for caps in ARTICLE_RE.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            let prefix = &text[..whole.start()];
+
+            let found = if let Some((start, name)) = self.law_before(prefix) {
+                Some((start, name))
+            } else if let Some((name, end)) = last_law {
+                if prefix.ends_with("同法") {
+                    Some((whole.start() - "同法".len(), name))
+                } else if end <= whole.start()
+                    && CONTINUATION.contains(&text[end..whole.start()].trim())
+                {
+                    Some((whole.start(), name))
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            // 「第一百八十四條之一」統一轉成「184-1」
+            let num = match whole.as_str().parse::<ArticleNumber>() {
+                Ok(num) => num,
+                Err(_) => continue,
+            };
+            if let Some((start, name)) = found {
+                let id = format!("{}-{}", name, num);
+                citations.push(Citation {
+                    kind: CitationKind::Law,
+                    id,
+                    start,
+                    end: whole.end(),
+                });
+                last_law = Some((name, whole.end()));
+            }
+        }
+
+        for caps in INTERPRETATION_RE.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            citations.push(Citation {
+                kind: CitationKind::OldInterpretation,
+                id: caps[1].to_string(),
+                start: whole.start(),
+                end: whole.end(),
+            });
+        }
+
+        for caps in PRECEDENT_RE.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            citations.push(Citation {
+                kind: CitationKind::Precedent,
+                id: format!("{}-{}-{}", &caps[1], &caps[2], &caps[3]),
+                start: whole.start(),
+                end: whole.end(),
+            });
+        }
+
+        citations.sort_by_key(|c| c.start);
+        citations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_laws_aliases_and_interpretations() {
+        let aliases = LawAliases::builtin();
+        let finder = CitationFinder::new(["民法", "中華民國刑法"], &aliases);
+        let text = "依民法第184條、第一百八十五條及刑法第2條之1，參照釋字第748號及49年台上字第929號判例";
+        let ids: Vec<(&str, String)> = finder
+            .find(text)
+            .into_iter()
+            .map(|c| (&text[c.start..c.end], c.id))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                ("民法第184條", "民法-184".to_string()),
+                ("第一百八十五條", "民法-185".to_string()),
+                ("刑法第2條之1", "中華民國刑法-2-1".to_string()),
+                ("釋字第748號", "748".to_string()),
+                ("49年台上字第929號", "49-台上-929".to_string()),
+            ]
+        );
+        assert!(finder.find("本法第3條").is_empty());
+    }
+}
@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+
+/// 字元層級的差異片段
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal { text: String },
+    Insert { text: String },
+    Delete { text: String },
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LineChange {
+    Equal,
+    Insert,
+    Delete,
+    Change,
+}
+
+/// 一行的比對結果，Change 時 chars 為字元層級的差異
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct LineDiff {
+    pub change: LineChange,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    pub chars: Vec<DiffOp>,
+}
+
+// 中文一字一個單位，連續的英數字視為一個單位，避免「184」與「185」被拆得零碎
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, c) in text.char_indices() {
+        if c.is_ascii_alphanumeric() {
+            if start.is_none() {
+                start = Some(i);
+            }
+            continue;
+        }
+        if let Some(s) = start.take() {
+            tokens.push(&text[s..i]);
+        }
+        tokens.push(&text[i..i + c.len_utf8()]);
+    }
+    if let Some(s) = start {
+        tokens.push(&text[s..]);
+    }
+    tokens
+}
+
+enum Edit {
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+// 以最長共同子序列求出編輯步驟
+fn lcs_edits<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let (n, m) = (old.len(), new.len());
+    let mut table = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i][j] = if old[i] == new[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            edits.push(Edit::Equal(i));
+            i += 1;
+            j += 1;
+        } else if table[i + 1][j] >= table[i][j + 1] {
+            edits.push(Edit::Delete(i));
+            i += 1;
+        } else {
+            edits.push(Edit::Insert(j));
+            j += 1;
+        }
+    }
+    edits.extend((i..n).map(Edit::Delete));
+    edits.extend((j..m).map(Edit::Insert));
+    edits
+}
+
+fn push_op(ops: &mut Vec<DiffOp>, op: DiffOp) {
+    match (ops.last_mut(), op) {
+        (Some(DiffOp::Equal { text }), DiffOp::Equal { text: t })
+        | (Some(DiffOp::Insert { text }), DiffOp::Insert { text: t })
+        | (Some(DiffOp::Delete { text }), DiffOp::Delete { text: t }) => text.push_str(&t),
+        (_, op) => ops.push(op),
+    }
+}
+
+pub fn diff_chars(old: &str, new: &str) -> Vec<DiffOp> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    let mut ops = Vec::new();
+    for edit in lcs_edits(&old_tokens, &new_tokens) {
+        let op = match edit {
+            Edit::Equal(i) => DiffOp::Equal {
+                text: old_tokens[i].to_string(),
+            },
+            Edit::Delete(i) => DiffOp::Delete {
+                text: old_tokens[i].to_string(),
+            },
+            Edit::Insert(j) => DiffOp::Insert {
+                text: new_tokens[j].to_string(),
+            },
+        };
+        push_op(&mut ops, op);
+    }
+    ops
+}
+
+// 把累積的刪除、新增行輸出，能配對的部分視為修改
+fn flush_pending(
+    result: &mut Vec<LineDiff>,
+    deleted: &mut Vec<&String>,
+    inserted: &mut Vec<&String>,
+) {
+    let paired = deleted.len().min(inserted.len());
+    for (old, new) in deleted.iter().zip(inserted.iter()) {
+        result.push(LineDiff {
+            change: LineChange::Change,
+            old: Some(old.to_string()),
+            new: Some(new.to_string()),
+            chars: diff_chars(old, new),
+        });
+    }
+    for line in &deleted[paired..] {
+        result.push(LineDiff {
+            change: LineChange::Delete,
+            old: Some(line.to_string()),
+            new: None,
+            chars: vec![DiffOp::Delete {
+                text: line.to_string(),
+            }],
+        });
+    }
+    for line in &inserted[paired..] {
+        result.push(LineDiff {
+            change: LineChange::Insert,
+            old: None,
+            new: Some(line.to_string()),
+            chars: vec![DiffOp::Insert {
+                text: line.to_string(),
+            }],
+        });
+    }
+    deleted.clear();
+    inserted.clear();
+}
+
+/// 先以行對齊，相鄰的刪除與新增行再配對成 Change 做字元比對
+pub fn diff_lines(old: &[String], new: &[String]) -> Vec<LineDiff> {
+    let mut result = Vec::new();
+    let mut deleted: Vec<&String> = Vec::new();
+    let mut inserted: Vec<&String> = Vec::new();
+
+    for edit in lcs_edits(old, new) {
+        match edit {
+            Edit::Equal(i) => {
+                flush_pending(&mut result, &mut deleted, &mut inserted);
+                result.push(LineDiff {
+                    change: LineChange::Equal,
+                    old: Some(old[i].clone()),
+                    new: Some(old[i].clone()),
+                    chars: vec![DiffOp::Equal {
+                        text: old[i].clone(),
+                    }],
+                });
+            }
+            Edit::Delete(i) => deleted.push(&old[i]),
+            Edit::Insert(j) => inserted.push(&new[j]),
+        }
+    }
+    flush_pending(&mut result, &mut deleted, &mut inserted);
+    result
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 以 <ins>、<del> 標示差異，每行一個 <li>
+pub fn redline_html(diff: &[LineDiff]) -> String {
+    let mut html = String::from("<ul class='law-redline'>");
+    for line in diff {
+        let class = match line.change {
+            LineChange::Equal => "equal",
+            LineChange::Insert => "insert",
+            LineChange::Delete => "delete",
+            LineChange::Change => "change",
+        };
+        html.push_str(&format!("<li class='redline-{class}'>"));
+        for op in &line.chars {
+            match op {
+                DiffOp::Equal { text } => html.push_str(&escape_html(text)),
+                DiffOp::Insert { text } => {
+                    html.push_str(&format!("<ins>{}</ins>", escape_html(text)))
+                }
+                DiffOp::Delete { text } => {
+                    html.push_str(&format!("<del>{}</del>", escape_html(text)))
+                }
+            }
+        }
+        html.push_str("</li>");
+    }
+    html.push_str("</ul>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_lines_and_chars() {
+        let old = vec!["因故意或過失，不法侵害他人之權利者，負損害賠償責任。".to_string()];
+        let new = vec![
+            "因故意或過失，不法侵害他人之權利者，應負損害賠償責任。".to_string(),
+            "第184條準用之。".to_string(),
+        ];
+        let diff = diff_lines(&old, &new);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff[0].change, LineChange::Change);
+        assert!(diff[0].chars.contains(&DiffOp::Insert {
+            text: "應".to_string()
+        }));
+        assert_eq!(diff[1].change, LineChange::Insert);
+
+        assert_eq!(
+            diff_chars("第184條", "第185條"),
+            vec![
+                DiffOp::Equal {
+                    text: "第".to_string()
+                },
+                DiffOp::Delete {
+                    text: "184".to_string()
+                },
+                DiffOp::Insert {
+                    text: "185".to_string()
+                },
+                DiffOp::Equal {
+                    text: "條".to_string()
+                },
+            ]
+        );
+        assert!(redline_html(&diff).contains("<ins>應</ins>"));
+    }
+}
@@ -0,0 +1,23 @@
+// 法條 id 的格式為「法規名稱-條號」，條號可能帶有「之N」而寫成「877-1」，
+// 因此必須在第一個 `-` 切開，不能用 rsplit
+
+/// 將「民法-877-1」切成 ("民法", "877-1")
+pub fn split(id: &str) -> Option<(&str, &str)> {
+    match id.split_once('-') {
+        Some((law, num)) if !law.is_empty() && !num.is_empty() => Some((law, num)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_first_hyphen() {
+        assert_eq!(split("民法-184"), Some(("民法", "184")));
+        assert_eq!(split("民法-877-1"), Some(("民法", "877-1")));
+        assert_eq!(split("民法"), None);
+        assert_eq!(split("-184"), None);
+    }
+}
@@ -6,6 +6,17 @@ use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
 use sqlx::Row;
 use std::error::Error;
 use std::io::BufRead;
+use provenance::Provenance;
+use sync_run::{compare_hash, content_hash, WriteOutcome};
+
+pub mod alias;
+pub mod article;
+pub mod citation;
+pub mod diff;
+pub mod law_id;
+pub mod provenance;
+pub mod sync_run;
+pub mod toc;
 
 #[derive(Debug)]
 pub enum LawError {
@@ -38,38 +49,79 @@ fn format_lines(node: select::node::Node) -> Vec<Line> {
 }
 */
 
-#[derive(Deserialize, Serialize, Clone, Debug, sqlx::FromRow)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct NewLaw {
     pub id: String,
     pub href: String,
     pub chapter: Vec<String>,
     pub num: String,
     pub lines: Vec<Line>,
+    #[serde(default)]
+    pub provenance: Provenance,
+}
+
+// content_hash 只算條文本身，出處（例如擷取時間）不同不算變動
+#[derive(Serialize)]
+struct LawContent<'a> {
+    id: &'a str,
+    href: &'a str,
+    chapter: &'a [String],
+    num: &'a str,
+    lines: &'a [Line],
 }
 
 impl NewLaw {
-    pub async fn add_to_pool(&self, pool: &PgPool) {
+    /// 內容與上次寫入的 content_hash 相同時不寫入
+    pub async fn add_to_pool(&self, pool: &PgPool) -> Result<WriteOutcome, sqlx::Error> {
+        let hash = content_hash(&LawContent {
+            id: &self.id,
+            href: &self.href,
+            chapter: &self.chapter,
+            num: &self.num,
+            lines: &self.lines,
+        });
+        let stored = sqlx::query("SELECT content_hash FROM newlaw WHERE id = $1")
+            .bind(&self.id)
+            .map(|row: PgRow| row.get::<Option<String>, _>("content_hash"))
+            .fetch_optional(pool)
+            .await?;
+        let outcome = compare_hash(stored, &hash);
+        if outcome == WriteOutcome::Unchanged {
+            // 內容沒變仍記下這次確認的時間
+            sqlx::query("UPDATE newlaw SET fetched_at = COALESCE($2, now()) WHERE id = $1")
+                .bind(&self.id)
+                .bind(self.provenance.fetched_at)
+                .execute(pool)
+                .await?;
+            return Ok(outcome);
+        }
+
         let json_lines = serde_json::to_value(&self.lines).unwrap();
-        match sqlx::query(
-            "INSERT INTO newlaw (id, num, lines, href, chapter)
-             VALUES ($1, $2, $3, $4, $5)
+        sqlx::query(
+            "INSERT INTO newlaw (id, num, lines, href, chapter, content_hash, source_url, fetched_at, upstream_date)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, COALESCE($8, now()), $9)
              ON CONFLICT (id) DO UPDATE
              SET num = EXCLUDED.num,
                  lines = EXCLUDED.lines,
                  href = EXCLUDED.href,
-                 chapter = EXCLUDED.chapter",
+                 chapter = EXCLUDED.chapter,
+                 content_hash = EXCLUDED.content_hash,
+                 source_url = EXCLUDED.source_url,
+                 fetched_at = EXCLUDED.fetched_at,
+                 upstream_date = EXCLUDED.upstream_date",
         )
         .bind(&self.id)
         .bind(&self.num)
         .bind(&json_lines)
         .bind(&self.href)
         .bind(&self.chapter)
+        .bind(&hash)
+        .bind(&self.provenance.source_url)
+        .bind(self.provenance.fetched_at)
+        .bind(&self.provenance.upstream_date)
         .execute(pool)
-        .await
-        {
-            Ok(_) => println!("✅ Upserted: {}", self.id),
-            Err(e) => eprintln!("❌ Failed [{}]: {}", self.id, e),
-        }
+        .await?;
+        Ok(outcome)
     }
 }
 
@@ -136,7 +188,6 @@ impl NewLaws {
                 childChapters: Vec::new(),
             };
             let max = laws.count_chapter();
-            println!("{max}");
             if level < max - 1 {
                 // 遞迴建立子章節列表
                 chapter_ul.childChapters = laws.chapter_ul_list_create(level + 1);
@@ -244,6 +295,7 @@ impl NewLaws {
                 lines,
                 href: row.get("href"),
                 chapter: row.get("chapter"),
+                provenance: Provenance::from_row(&row, "source_url"),
             }
         })
         .fetch_all(&db_pool)
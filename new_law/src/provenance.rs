@@ -0,0 +1,79 @@
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{ColumnIndex, Decode, Row, Type};
+
+/// 資料的出處，API 一併回傳，引用時可註明「擷取自…」
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Provenance {
+    pub source_url: String,
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub content_hash: Option<String>,
+    pub upstream_date: Option<String>, // 上游的公布或修正日期，保留原文，例如「民國 110 年 01 月 20 日」
+}
+
+impl Provenance {
+    /// 剛從 source_url 取得的資料
+    pub fn fetched(source_url: impl Into<String>, upstream_date: Option<String>) -> Self {
+        Provenance {
+            source_url: source_url.into(),
+            fetched_at: Some(Utc::now()),
+            content_hash: None,
+            upstream_date,
+        }
+    }
+
+    /// 從資料列讀出，舊資料沒有這些欄位時留空；PostgreSQL 與 SQLite 的資料列皆可
+    pub fn from_row<'r, R>(row: &'r R, source_column: &str) -> Self
+    where
+        R: Row,
+        for<'c> &'c str: ColumnIndex<R>,
+        String: Decode<'r, R::Database> + Type<R::Database>,
+        DateTime<Utc>: Decode<'r, R::Database> + Type<R::Database>,
+    {
+        Provenance {
+            source_url: row
+                .try_get::<Option<String>, _>(source_column)
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            fetched_at: row.try_get("fetched_at").ok().flatten(),
+            content_hash: row.try_get("content_hash").ok().flatten(),
+            upstream_date: row.try_get("upstream_date").ok().flatten(),
+        }
+    }
+
+    /// 例如「擷取自 https://law.moj.gov.tw/...（2025年3月1日）」
+    pub fn retrieved_on(&self) -> String {
+        match self.fetched_at {
+            Some(at) => format!(
+                "擷取自 {}（{}年{}月{}日）",
+                self.source_url,
+                at.year(),
+                at.month(),
+                at.day()
+            ),
+            None => format!("擷取自 {}", self.source_url),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn formats_retrieved_on() {
+        let mut provenance = Provenance {
+            source_url: "https://law.moj.gov.tw/LawClass/LawSingle.aspx?pcode=B0000001&flno=184"
+                .to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            provenance.retrieved_on(),
+            "擷取自 https://law.moj.gov.tw/LawClass/LawSingle.aspx?pcode=B0000001&flno=184"
+        );
+        provenance.fetched_at = Some(Utc.with_ymd_and_hms(2025, 3, 1, 8, 0, 0).unwrap());
+        assert!(provenance.retrieved_on().ends_with("（2025年3月1日）"));
+    }
+}
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use sqlx::postgres::{PgPool, PgRow};
+use std::fmt::Display;
+
+/// 以 JSON 內容計算的 sha256，用來判斷上游資料是否變動
+pub fn content_hash<T: Serialize>(value: &T) -> String {
+    let json = serde_json::to_vec(value).unwrap();
+    sha256_hex(&json)
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum WriteOutcome {
+    Inserted,
+    Updated,
+    Unchanged,
+}
+
+/// 寫入前比對 content_hash，stored 為資料庫中的值，None 表示沒有這筆
+pub fn compare_hash(stored: Option<Option<String>>, hash: &str) -> WriteOutcome {
+    match stored {
+        None => WriteOutcome::Inserted,
+        Some(Some(stored)) if stored == hash => WriteOutcome::Unchanged,
+        Some(_) => WriteOutcome::Updated,
+    }
+}
+
+/// 一次同步的結果，開始時在 sync_run 新增一筆，結束時寫入各項 id
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct SyncReport {
+    pub run_id: Option<i64>, // sync_run 無法寫入時為 None，只印出結果
+    pub source: String,
+    pub inserted: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+impl SyncReport {
+    pub fn new(source: &str) -> Self {
+        SyncReport {
+            source: source.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub async fn start(pool: &PgPool, source: &str) -> Self {
+        let mut report = SyncReport::new(source);
+        match sqlx::query("INSERT INTO sync_run (source) VALUES ($1) RETURNING id")
+            .bind(source)
+            .map(|row: PgRow| row.get::<i64, _>("id"))
+            .fetch_one(pool)
+            .await
+        {
+            Ok(id) => report.run_id = Some(id),
+            Err(e) => eprintln!("新增 sync_run 失敗: {}", e),
+        }
+        report
+    }
+
+    pub fn record<E: Display>(&mut self, id: &str, result: Result<WriteOutcome, E>) {
+        match result {
+            Ok(WriteOutcome::Inserted) => self.inserted.push(id.to_string()),
+            Ok(WriteOutcome::Updated) => self.updated.push(id.to_string()),
+            Ok(WriteOutcome::Unchanged) => self.unchanged.push(id.to_string()),
+            Err(e) => self.fail(id, e),
+        }
+    }
+
+    pub fn fail(&mut self, id: &str, error: impl Display) {
+        eprintln!("[{}] {} 失敗: {}", self.source, id, error);
+        self.failed.push(id.to_string());
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "[{}] 新增 {}、更新 {}、未變動 {}、失敗 {}",
+            self.source,
+            self.inserted.len(),
+            self.updated.len(),
+            self.unchanged.len(),
+            self.failed.len()
+        )
+    }
+
+    pub async fn finish(&self, pool: &PgPool) {
+        println!("{}", self.summary());
+        let Some(id) = self.run_id else {
+            return;
+        };
+        match sqlx::query(
+            "UPDATE sync_run SET finished_at = now(), inserted = $2, updated = $3, unchanged = $4, failed = $5
+            WHERE id = $1",
+        )
+        .bind(id)
+        .bind(&self.inserted)
+        .bind(&self.updated)
+        .bind(&self.unchanged)
+        .bind(&self.failed)
+        .execute(pool)
+        .await
+        {
+            Ok(_) => (),
+            Err(e) => eprintln!("更新 sync_run {} 失敗: {}", id, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_changes() {
+        let hash = content_hash(&("民法", vec!["第一條"]));
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, content_hash(&("民法", vec!["第一條"])));
+        assert_ne!(hash, content_hash(&("民法", vec!["第二條"])));
+
+        assert_eq!(compare_hash(None, &hash), WriteOutcome::Inserted);
+        assert_eq!(compare_hash(Some(None), &hash), WriteOutcome::Updated);
+        assert_eq!(
+            compare_hash(Some(Some(hash.clone())), &hash),
+            WriteOutcome::Unchanged
+        );
+
+        let mut report = SyncReport::new("laws");
+        report.record("民法-1", Ok::<_, String>(WriteOutcome::Inserted));
+        report.record("民法-2", Ok::<_, String>(WriteOutcome::Unchanged));
+        report.record("民法-3", Err("timeout"));
+        assert_eq!(report.failed, vec!["民法-3"]);
+        assert_eq!(report.summary(), "[laws] 新增 1、更新 0、未變動 1、失敗 1");
+    }
+}
@@ -0,0 +1,170 @@
+use crate::article::compare_nums;
+use crate::{ChapterUl, NewLaw, NewLaws};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 目錄中的一個章節，id 為各層序號，例如「2.1」為第二編第一章
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct TocNode {
+    pub id: String,
+    pub title: String,
+    pub level: usize,
+    pub path: Vec<String>,
+    pub first: Option<String>, // 本章（含子章節）第一條的條號
+    pub last: Option<String>,
+    pub count: usize,
+    pub children: Vec<TocNode>,
+}
+
+impl From<&TocNode> for ChapterUl {
+    fn from(node: &TocNode) -> Self {
+        ChapterUl {
+            chapter: node.title.clone(),
+            level: node.level,
+            childChapters: node.children.iter().map(ChapterUl::from).collect(),
+        }
+    }
+}
+
+/// 一部法規的章節樹，載入時建立一次，之後以 node id 或章節路徑直接查到條文
+#[derive(Clone, Debug)]
+pub struct LawToc {
+    pub nodes: Vec<TocNode>,
+    // node id -> 該章節條文在 NewLaws.lines 中的位置，已依條號排序
+    laws_by_node: HashMap<String, Vec<usize>>,
+    // 去掉空白、以「/」串起的章節路徑 -> node id
+    node_by_path: HashMap<String, String>,
+}
+
+fn path_key(path: &[String]) -> String {
+    path.join("/").replace(' ', "")
+}
+
+impl LawToc {
+    /// laws 為 `NewLaws::categories(0)` 中某一部法規，chapter[0] 為法規名稱
+    pub fn build(laws: &NewLaws) -> LawToc {
+        let mut order: Vec<usize> = (0..laws.lines.len()).collect();
+        order.sort_by(|&a, &b| compare_nums(&laws.lines[a].num, &laws.lines[b].num));
+
+        let mut nodes: Vec<TocNode> = Vec::new();
+        let mut laws_by_node: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut node_by_path = HashMap::new();
+
+        for index in order {
+            let law = &laws.lines[index];
+            let mut siblings = &mut nodes;
+            let mut parent_id = String::new();
+            for level in 1..law.chapter.len() {
+                let title = &law.chapter[level];
+                let position = match siblings.iter().position(|node| &node.title == title) {
+                    Some(position) => position,
+                    None => {
+                        let id = if parent_id.is_empty() {
+                            format!("{}", siblings.len() + 1)
+                        } else {
+                            format!("{}.{}", parent_id, siblings.len() + 1)
+                        };
+                        let path = law.chapter[1..=level].to_vec();
+                        node_by_path.insert(path_key(&path), id.clone());
+                        siblings.push(TocNode {
+                            id,
+                            title: title.clone(),
+                            level,
+                            path,
+                            first: None,
+                            last: None,
+                            count: 0,
+                            children: Vec::new(),
+                        });
+                        siblings.len() - 1
+                    }
+                };
+                let node = &mut siblings[position];
+                if node.first.is_none() {
+                    node.first = Some(law.num.clone());
+                }
+                node.last = Some(law.num.clone());
+                node.count += 1;
+                laws_by_node.entry(node.id.clone()).or_default().push(index);
+                parent_id = node.id.clone();
+                siblings = &mut node.children;
+            }
+        }
+
+        LawToc {
+            nodes,
+            laws_by_node,
+            node_by_path,
+        }
+    }
+
+    /// 每部法規各建一份目錄
+    pub fn build_all(map: &indexmap::IndexMap<String, NewLaws>) -> HashMap<String, LawToc> {
+        map.iter()
+            .map(|(name, laws)| (name.clone(), LawToc::build(laws)))
+            .collect()
+    }
+
+    /// 以「第一編總則/第一章法例」這類路徑找 node id，空白會被忽略
+    pub fn node_id(&self, path: &str) -> Option<&str> {
+        self.node_by_path
+            .get(&path.replace(' ', ""))
+            .map(|id| id.as_str())
+    }
+
+    /// 章節（含子章節）內的條文，依條號排序
+    pub fn laws_in<'a>(&self, node_id: &str, laws: &'a NewLaws) -> Option<Vec<&'a NewLaw>> {
+        self.laws_by_node
+            .get(node_id)
+            .map(|indexes| indexes.iter().map(|&i| &laws.lines[i]).collect())
+    }
+
+    pub fn chapter_ul_list(&self) -> Vec<ChapterUl> {
+        self.nodes.iter().map(ChapterUl::from).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn law(num: &str, chapter: &[&str]) -> NewLaw {
+        NewLaw {
+            id: format!("民法-{num}"),
+            href: String::new(),
+            chapter: chapter.iter().map(|s| s.to_string()).collect(),
+            num: num.to_string(),
+            lines: Vec::new(),
+            provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn builds_tree_with_ranges() {
+        let laws = NewLaws {
+            lines: vec![
+                law("2", &["民法", "第一編 總則", "第一章 法例"]),
+                law("1", &["民法", "第一編 總則", "第一章 法例"]),
+                law("6", &["民法", "第一編 總則", "第二章 人"]),
+                law("1-1", &["民法", "第一編 總則", "第一章 法例"]),
+            ],
+        };
+        let toc = LawToc::build(&laws);
+        assert_eq!(toc.nodes.len(), 1);
+        let part = &toc.nodes[0];
+        assert_eq!(
+            (part.count, part.first.as_deref(), part.last.as_deref()),
+            (4, Some("1"), Some("6"))
+        );
+        assert_eq!(part.children[1].id, "1.2");
+
+        let id = toc.node_id("第一編總則/第一章 法例").unwrap();
+        let nums: Vec<&str> = toc
+            .laws_in(id, &laws)
+            .unwrap()
+            .iter()
+            .map(|law| law.num.as_str())
+            .collect();
+        assert_eq!(nums, vec!["1", "1-1", "2"]);
+    }
+}
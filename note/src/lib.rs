@@ -13,14 +13,14 @@ pub struct Note {
     // 可以加入其他元資料，例如標題、作者等
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Line {
     pub line_type: String,
     pub attributes: Option<Attributes>,
     pub children: Vec<InlineNode>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LawCard {
     pub chapter: String,
     pub num: String,
@@ -92,6 +92,22 @@ pub enum InlineNode {
         children: Vec<InlineNode>,
         attributes: Option<Attributes>,
     },
+    // 自動偵測到的法條或解釋引用，law_id 為「民法-184」或釋字號碼
+    LawLink {
+        text: String,
+        link_type: String,
+        law_id: String,
+        attributes: Option<Attributes>,
+    },
+}
+
+/// 文字中一段要轉成 LawLink 的範圍，start/end 為 byte 位置
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkSpan {
+    pub start: usize,
+    pub end: usize,
+    pub link_type: String,
+    pub law_id: String,
 }
 
 // 僅保留 chapter 與 num 的 law_card 資料結構
@@ -162,6 +178,15 @@ fn parse_inline_nodes(node: &Node) -> Vec<InlineNode> {
                         attributes: Some(attr),
                     });
                 }
+                "a" if child.attr("data-law-id").is_some() => {
+                    // 前端渲染過的 LawLink，直接還原，避免重複連結
+                    nodes.push(InlineNode::LawLink {
+                        text: child.text().trim().to_string(),
+                        link_type: child.attr("data-link-type").unwrap_or("law").to_string(),
+                        law_id: child.attr("data-law-id").unwrap_or_default().to_string(),
+                        attributes: None,
+                    });
+                }
                 "p" => {
                     let children = parse_inline_nodes(&child);
                     let attr = Attributes {
@@ -354,3 +379,337 @@ pub fn parse_note(html: &str) -> Vec<Block> {
     }
     blocks
 }
+
+fn link_inline_nodes<F>(nodes: Vec<InlineNode>, find: &F) -> Vec<InlineNode>
+where
+    F: Fn(&str) -> Vec<LinkSpan>,
+{
+    let mut result = Vec::new();
+    for node in nodes {
+        match node {
+            InlineNode::Text { text, attributes } => {
+                let mut cursor = 0;
+                for span in find(&text) {
+                    // 重疊或超出範圍的結果直接略過
+                    if span.start < cursor || span.end > text.len() || span.start >= span.end {
+                        continue;
+                    }
+                    if span.start > cursor {
+                        result.push(InlineNode::Text {
+                            text: text[cursor..span.start].to_string(),
+                            attributes: attributes.clone(),
+                        });
+                    }
+                    result.push(InlineNode::LawLink {
+                        text: text[span.start..span.end].to_string(),
+                        link_type: span.link_type,
+                        law_id: span.law_id,
+                        attributes: attributes.clone(),
+                    });
+                    cursor = span.end;
+                }
+                if cursor < text.len() {
+                    result.push(InlineNode::Text {
+                        text: text[cursor..].to_string(),
+                        attributes,
+                    });
+                }
+            }
+            InlineNode::Span {
+                children,
+                attributes,
+            } => result.push(InlineNode::Span {
+                children: link_inline_nodes(children, find),
+                attributes,
+            }),
+            InlineNode::Strong {
+                children,
+                attributes,
+            } => result.push(InlineNode::Strong {
+                children: link_inline_nodes(children, find),
+                attributes,
+            }),
+            InlineNode::P {
+                children,
+                attributes,
+            } => result.push(InlineNode::P {
+                children: link_inline_nodes(children, find),
+                attributes,
+            }),
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+/// 在 parse_note 之後執行，把文字中的法條引用包成 LawLink
+/// 已經是 LawLink 的節點與 law 卡片內容不會再處理，重複執行結果相同
+pub fn link_citations<F>(blocks: &mut [Block], find: F)
+where
+    F: Fn(&str) -> Vec<LinkSpan>,
+{
+    for block in blocks.iter_mut() {
+        match block {
+            Block::Paragraph { children, .. }
+            | Block::H2 { children, .. }
+            | Block::H3 { children, .. }
+            | Block::BlockQuote { children, .. }
+            | Block::Figure { children, .. }
+            | Block::Table { children, .. } => {
+                let nodes = std::mem::take(children);
+                *children = link_inline_nodes(nodes, &find);
+            }
+            Block::CustomCard { .. } => {}
+        }
+    }
+}
+
+/// 筆記引用的法條或解釋，ref_type 為 "law" 或 "oldinterpretation"
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct Reference {
+    pub ref_type: String,
+    pub ref_id: String,
+}
+
+fn collect_inline_references(nodes: &[InlineNode], refs: &mut Vec<Reference>) {
+    for node in nodes {
+        match node {
+            InlineNode::LawLink {
+                link_type, law_id, ..
+            } => refs.push(Reference {
+                ref_type: link_type.clone(),
+                ref_id: law_id.clone(),
+            }),
+            InlineNode::Span { children, .. }
+            | InlineNode::Strong { children, .. }
+            | InlineNode::P { children, .. } => collect_inline_references(children, refs),
+            _ => {}
+        }
+    }
+}
+
+/// 收集筆記中的 law 卡片與 LawLink，結果不重複
+pub fn collect_references(blocks: &[Block]) -> Vec<Reference> {
+    let mut refs = Vec::new();
+    for block in blocks {
+        match block {
+            Block::CustomCard { card_type, data } if card_type == "law" => {
+                let card = data
+                    .clone()
+                    .and_then(|value| serde_json::from_value::<LawCardData>(value).ok());
+                if let Some(card) = card {
+                    refs.push(Reference {
+                        ref_type: "law".to_string(),
+                        ref_id: format!("{}-{}", card.chapter.trim(), card.num.trim()),
+                    });
+                }
+            }
+            Block::CustomCard { .. } => {}
+            Block::Paragraph { children, .. }
+            | Block::H2 { children, .. }
+            | Block::H3 { children, .. }
+            | Block::BlockQuote { children, .. }
+            | Block::Figure { children, .. }
+            | Block::Table { children, .. } => collect_inline_references(children, &mut refs),
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    refs.retain(|r| seen.insert(r.clone()));
+    refs
+}
+
+/// 取出行內節點的純文字
+pub fn inline_text(nodes: &[InlineNode]) -> String {
+    let mut text = String::new();
+    for node in nodes {
+        match node {
+            InlineNode::Text { text: t, .. } | InlineNode::LawLink { text: t, .. } => {
+                text.push_str(t)
+            }
+            InlineNode::Span { children, .. }
+            | InlineNode::Strong { children, .. }
+            | InlineNode::P { children, .. } => text.push_str(&inline_text(children)),
+            InlineNode::Img { .. } => {}
+        }
+    }
+    text
+}
+
+impl LawCard {
+    /// 從 Block::CustomCard 的 data 還原 law 卡片
+    pub fn from_block(block: &Block) -> Option<LawCard> {
+        match block {
+            Block::CustomCard { card_type, data } if card_type == "law" => data
+                .clone()
+                .and_then(|value| serde_json::from_value(value).ok()),
+            _ => None,
+        }
+    }
+
+    pub fn to_block(&self) -> Block {
+        Block::CustomCard {
+            card_type: "law".to_string(),
+            data: Some(serde_json::to_value(self).unwrap()),
+        }
+    }
+
+    /// 每一行的純文字，用來和目前條文比對
+    pub fn text_lines(&self) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|line| inline_text(&line.children))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 測試用的搜尋函式，只認得「民法第184條」
+    fn find_184(text: &str) -> Vec<LinkSpan> {
+        let needle = "民法第184條";
+        text.match_indices(needle)
+            .map(|(start, _)| LinkSpan {
+                start,
+                end: start + needle.len(),
+                link_type: "law".to_string(),
+                law_id: "民法-184".to_string(),
+            })
+            .collect()
+    }
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            attributes: None,
+        }
+    }
+
+    fn paragraph(children: Vec<InlineNode>) -> Block {
+        Block::Paragraph {
+            attributes: None,
+            children,
+        }
+    }
+
+    fn law_card() -> LawCard {
+        LawCard {
+            chapter: "民法".to_string(),
+            num: "184".to_string(),
+            lines: vec![Line {
+                line_type: "normal".to_string(),
+                attributes: None,
+                children: vec![text("依民法第184條負損害賠償責任。")],
+            }],
+        }
+    }
+
+    fn to_json(blocks: &[Block]) -> serde_json::Value {
+        serde_json::to_value(blocks).unwrap()
+    }
+
+    #[test]
+    fn link_citations_wraps_matches_in_law_links() {
+        let mut blocks = vec![paragraph(vec![text("依民法第184條請求賠償")])];
+        link_citations(&mut blocks, find_184);
+
+        let Block::Paragraph { children, .. } = &blocks[0] else {
+            panic!("expected paragraph");
+        };
+        assert_eq!(children.len(), 3);
+        match &children[1] {
+            InlineNode::LawLink {
+                text,
+                link_type,
+                law_id,
+                ..
+            } => {
+                assert_eq!(text, "民法第184條");
+                assert_eq!(link_type, "law");
+                assert_eq!(law_id, "民法-184");
+            }
+            other => panic!("expected law link, got {:?}", other),
+        }
+        assert_eq!(inline_text(children), "依民法第184條請求賠償");
+    }
+
+    #[test]
+    fn link_citations_is_idempotent() {
+        let mut blocks = vec![paragraph(vec![InlineNode::Strong {
+            children: vec![text("民法第184條與民法第184條")],
+            attributes: None,
+        }])];
+        link_citations(&mut blocks, find_184);
+        let once = to_json(&blocks);
+        link_citations(&mut blocks, find_184);
+        assert_eq!(to_json(&blocks), once);
+    }
+
+    #[test]
+    fn link_citations_skips_law_cards_and_existing_links() {
+        let existing = InlineNode::LawLink {
+            text: "民法第184條".to_string(),
+            link_type: "law".to_string(),
+            law_id: "民法-184".to_string(),
+            attributes: None,
+        };
+        let mut blocks = vec![law_card().to_block(), paragraph(vec![existing])];
+        let before = to_json(&blocks);
+        link_citations(&mut blocks, find_184);
+        assert_eq!(to_json(&blocks), before);
+    }
+
+    #[test]
+    fn law_card_round_trips_through_block() {
+        let card = law_card();
+        let block = card.to_block();
+        let restored = LawCard::from_block(&block).unwrap();
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            serde_json::to_value(&card).unwrap()
+        );
+        assert_eq!(restored.text_lines(), vec!["依民法第184條負損害賠償責任。"]);
+        assert!(LawCard::from_block(&paragraph(vec![text("民法")])).is_none());
+    }
+
+    #[test]
+    fn collect_references_reads_cards_and_nested_links_once() {
+        let link = InlineNode::LawLink {
+            text: "民法第184條".to_string(),
+            link_type: "law".to_string(),
+            law_id: "民法-184".to_string(),
+            attributes: None,
+        };
+        let interpretation = InlineNode::LawLink {
+            text: "釋字第748號".to_string(),
+            link_type: "oldinterpretation".to_string(),
+            law_id: "748".to_string(),
+            attributes: None,
+        };
+        let blocks = vec![
+            law_card().to_block(),
+            Block::CustomCard {
+                card_type: "image".to_string(),
+                data: None,
+            },
+            paragraph(vec![InlineNode::Strong {
+                children: vec![link, interpretation],
+                attributes: None,
+            }]),
+        ];
+        assert_eq!(
+            collect_references(&blocks),
+            vec![
+                Reference {
+                    ref_type: "law".to_string(),
+                    ref_id: "民法-184".to_string(),
+                },
+                Reference {
+                    ref_type: "oldinterpretation".to_string(),
+                    ref_id: "748".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use chrono::{DateTime, Utc};
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use new_law::sync_run::{sha256_hex, to_hex};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::error::Error;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// 可匯出的資料集，名稱即資料表名稱，匯入時依此順序
+pub const DATASETS: [&str; 8] = [
+    "lawinformation",
+    "newlaw",
+    "history_law",
+    "newinters",
+    "newinterpretations",
+    "oldinters",
+    "precedents",
+    "resolution",
+];
+
+pub const MANIFEST: &str = "manifest.json";
+
+pub fn is_dataset(name: &str) -> bool {
+    DATASETS.contains(&name)
+}
+
+/// 匯出目錄中的 manifest.json，記錄各資料集的筆數與檔案 sha256
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Manifest {
+    pub created_at: DateTime<Utc>,
+    pub datasets: Vec<DatasetEntry>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct DatasetEntry {
+    pub name: String,
+    pub rows: usize,
+    pub jsonl_sha256: String,
+    pub csv_sha256: String,
+}
+
+impl DatasetEntry {
+    pub fn new(name: &str, rows: usize, jsonl: &str, csv: &str) -> Self {
+        DatasetEntry {
+            name: name.to_string(),
+            rows,
+            jsonl_sha256: sha256_hex(jsonl.as_bytes()),
+            csv_sha256: sha256_hex(csv.as_bytes()),
+        }
+    }
+
+    /// 匯入前確認 JSON Lines 檔與 manifest 相符
+    pub fn verify(&self, jsonl: &str) -> Result<(), String> {
+        if sha256_hex(jsonl.as_bytes()) != self.jsonl_sha256 {
+            return Err(format!("{}.jsonl 的 sha256 與 manifest 不符", self.name));
+        }
+        let rows = jsonl.lines().filter(|line| !line.is_empty()).count();
+        if rows != self.rows {
+            return Err(format!(
+                "{}.jsonl 有 {} 筆，manifest 記錄 {} 筆",
+                self.name, rows, self.rows
+            ));
+        }
+        Ok(())
+    }
+}
+
+// 資料表名稱無法 bind，每個資料集的查詢逐一寫出；lawinformation 的主鍵是 originalid
+fn select_query(dataset: &str) -> Option<&'static str> {
+    let query = match dataset {
+        "lawinformation" => {
+            "SELECT to_jsonb(t)::text AS row FROM lawinformation t ORDER BY originalid"
+        }
+        "newlaw" => "SELECT to_jsonb(t)::text AS row FROM newlaw t ORDER BY id",
+        "history_law" => "SELECT to_jsonb(t)::text AS row FROM history_law t ORDER BY id",
+        "newinters" => "SELECT to_jsonb(t)::text AS row FROM newinters t ORDER BY id",
+        "newinterpretations" => {
+            "SELECT to_jsonb(t)::text AS row FROM newinterpretations t ORDER BY id"
+        }
+        "oldinters" => "SELECT to_jsonb(t)::text AS row FROM oldinters t ORDER BY id",
+        "precedents" => "SELECT to_jsonb(t)::text AS row FROM precedents t ORDER BY id",
+        "resolution" => "SELECT to_jsonb(t)::text AS row FROM resolution t ORDER BY id",
+        _ => return None,
+    };
+    Some(query)
+}
+
+/// 整張表依主鍵排序逐筆讀出，每筆為一個 JSON 物件，欄位與資料表相同
+pub fn stream_rows<'a>(
+    pool: &'a PgPool,
+    dataset: &str,
+) -> BoxStream<'a, Result<Value, sqlx::Error>> {
+    let Some(query) = select_query(dataset) else {
+        let error = sqlx::Error::Protocol(format!("未知的資料集 {dataset}"));
+        return stream::once(async { Err(error) }).boxed();
+    };
+    sqlx::query(query)
+        .map(|row: PgRow| row.get::<String, _>("row"))
+        .fetch(pool)
+        .map(|row| {
+            row.and_then(|row| {
+                serde_json::from_str(&row).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+            })
+        })
+        .boxed()
+}
+
+pub fn to_jsonl(rows: &[Value]) -> String {
+    rows.iter().map(|row| format!("{row}\n")).collect()
+}
+
+/// 欄位順序取第一筆的欄位，字串陣列（例如 chapter）以「/」連接，其餘巢狀欄位存成 JSON
+pub fn to_csv(rows: &[Value]) -> Result<String, csv::Error> {
+    let Some(Value::Object(first)) = rows.first() else {
+        return Ok(String::new());
+    };
+    let header: Vec<&String> = first.keys().collect();
+    let mut wtr = csv::Writer::from_writer(Vec::new());
+    wtr.write_record(&header)?;
+    for row in rows {
+        wtr.write_record(header.iter().map(|key| csv_field(&row[key.as_str()])))?;
+    }
+    let bytes = wtr.into_inner().map_err(|e| e.into_error())?;
+    Ok(String::from_utf8(bytes).unwrap())
+}
+
+// 寫入的同時計算 sha256
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 逐筆寫出 JSON Lines 與 CSV，不需把整個資料集放在記憶體中；
+/// 輸出與 to_jsonl、to_csv 相同，只需要 manifest 時可寫入 std::io::sink()
+pub struct DatasetWriter<J: Write, C: Write> {
+    jsonl: HashingWriter<J>,
+    csv: csv::Writer<HashingWriter<C>>,
+    header: Option<Vec<String>>, // 第一筆的欄位，第一筆不是物件時不寫 CSV
+    rows: usize,
+}
+
+impl<J: Write, C: Write> DatasetWriter<J, C> {
+    pub fn new(jsonl: J, csv: C) -> Self {
+        DatasetWriter {
+            jsonl: HashingWriter {
+                inner: jsonl,
+                hasher: Sha256::new(),
+            },
+            csv: csv::Writer::from_writer(HashingWriter {
+                inner: csv,
+                hasher: Sha256::new(),
+            }),
+            header: None,
+            rows: 0,
+        }
+    }
+
+    pub fn push(&mut self, row: &Value) -> Result<(), csv::Error> {
+        writeln!(self.jsonl, "{row}")?;
+        if self.rows == 0
+            && let Value::Object(first) = row
+        {
+            let header: Vec<String> = first.keys().cloned().collect();
+            self.csv.write_record(&header)?;
+            self.header = Some(header);
+        }
+        if let Some(header) = &self.header {
+            self.csv
+                .write_record(header.iter().map(|key| csv_field(&row[key.as_str()])))?;
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self, name: &str) -> Result<DatasetEntry, csv::Error> {
+        self.jsonl.flush()?;
+        let mut csv = self.csv.into_inner().map_err(|e| e.into_error())?;
+        csv.flush()?;
+        Ok(DatasetEntry {
+            name: name.to_string(),
+            rows: self.rows,
+            jsonl_sha256: to_hex(&self.jsonl.hasher.finalize()),
+            csv_sha256: to_hex(&csv.hasher.finalize()),
+        })
+    }
+}
+
+fn csv_field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_string) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join("/"),
+        other => other.to_string(),
+    }
+}
+
+/// 每個資料集寫出 {name}.jsonl 與 {name}.csv，最後寫出 manifest.json
+pub async fn export_dir(
+    pool: &PgPool,
+    dir: &Path,
+    datasets: &[&str],
+) -> Result<Manifest, Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    let mut manifest = Manifest {
+        created_at: Utc::now(),
+        datasets: Vec::new(),
+    };
+    for dataset in datasets {
+        let jsonl = BufWriter::new(fs::File::create(dir.join(format!("{dataset}.jsonl")))?);
+        let csv = BufWriter::new(fs::File::create(dir.join(format!("{dataset}.csv")))?);
+        let mut writer = DatasetWriter::new(jsonl, csv);
+        let mut rows = stream_rows(pool, dataset);
+        while let Some(row) = rows.try_next().await? {
+            writer.push(&row)?;
+        }
+        let entry = writer.finish(dataset)?;
+        println!("匯出 {} {} 筆", dataset, entry.rows);
+        manifest.datasets.push(entry);
+    }
+    fs::write(dir.join(MANIFEST), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(manifest)
+}
+
+/// 從 export_dir 的輸出匯入，只讀 JSON Lines，已存在的 id 略過；datasets 為空時匯入全部
+pub async fn import_dir(
+    pool: &PgPool,
+    dir: &Path,
+    datasets: &[&str],
+) -> Result<Vec<(String, u64)>, Box<dyn Error>> {
+    let manifest: Manifest = serde_json::from_str(&fs::read_to_string(dir.join(MANIFEST))?)?;
+    let mut imported = Vec::new();
+    for entry in &manifest.datasets {
+        if !datasets.is_empty() && !datasets.contains(&entry.name.as_str()) {
+            continue;
+        }
+        let jsonl = fs::read_to_string(dir.join(format!("{}.jsonl", entry.name)))?;
+        entry.verify(&jsonl)?;
+        let inserted = import_rows(pool, &entry.name, &jsonl).await?;
+        println!(
+            "匯入 {} {} 筆，已存在略過 {} 筆",
+            entry.name,
+            inserted,
+            entry.rows as u64 - inserted
+        );
+        imported.push((entry.name.clone(), inserted));
+    }
+    Ok(imported)
+}
+
+/// 一個資料集在同一個交易中匯入，回傳新增的筆數
+pub async fn import_rows(pool: &PgPool, dataset: &str, jsonl: &str) -> Result<u64, sqlx::Error> {
+    if !is_dataset(dataset) {
+        return Err(sqlx::Error::Protocol(format!("未知的資料集 {dataset}")));
+    }
+    let query = format!(
+        "INSERT INTO {dataset} SELECT * FROM jsonb_populate_record(NULL::{dataset}, $1::jsonb)
+        ON CONFLICT DO NOTHING"
+    );
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+    for line in jsonl.lines().filter(|line| !line.is_empty()) {
+        inserted += sqlx::query(&query)
+            .bind(line)
+            .execute(&mut tx)
+            .await?
+            .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn writes_jsonl_csv_and_manifest() {
+        let rows = vec![
+            json!({
+                "id": "民法-1",
+                "chapter": ["民法", "第一編 總則"],
+                "lines": [{"line_type": "normal", "content": "民事，法律所未規定者，依習慣。"}],
+                "fetched_at": null
+            }),
+            json!({
+                "id": "民法-2",
+                "chapter": ["民法"],
+                "lines": [],
+                "fetched_at": "2025-03-01T08:00:00+00:00"
+            }),
+        ];
+        let jsonl = to_jsonl(&rows);
+        assert_eq!(jsonl.lines().count(), 2);
+        let back: Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+        assert_eq!(back, rows[0]);
+
+        let csv = to_csv(&rows).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("chapter,fetched_at,id,lines"));
+        assert_eq!(
+            lines.next(),
+            Some(
+                r#"民法/第一編 總則,,民法-1,"[{""content"":""民事，法律所未規定者，依習慣。"",""line_type"":""normal""}]""#
+            )
+        );
+        assert_eq!(
+            lines.next(),
+            Some("民法,2025-03-01T08:00:00+00:00,民法-2,[]")
+        );
+
+        let entry = DatasetEntry::new("newlaw", rows.len(), &jsonl, &csv);
+        assert_eq!(entry.verify(&jsonl), Ok(()));
+
+        let mut writer = DatasetWriter::new(Vec::new(), std::io::sink());
+        for row in &rows {
+            writer.push(row).unwrap();
+        }
+        assert_eq!(writer.finish("newlaw").unwrap(), entry);
+        assert!(entry.verify(&to_jsonl(&rows[..1])).is_err());
+        assert_eq!(to_csv(&[]).unwrap(), "");
+    }
+}
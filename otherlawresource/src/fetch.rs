@@ -0,0 +1,249 @@
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::Display;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::{Instant, sleep, sleep_until};
+
+pub type FetchError = Box<dyn Error + Send + Sync>;
+
+/// 取得網頁原始碼，爬蟲的解析與下載分開，測試時可改用存在磁碟上的網頁
+pub trait HttpFetcher {
+    fn fetch(&self, url: &str) -> impl Future<Output = Result<String, FetchError>> + Send;
+}
+
+static DEFAULT_FETCHER: LazyLock<PoliteFetcher> =
+    LazyLock::new(|| PoliteFetcher::new(HttpConfig::default()).unwrap());
+
+/// 沒有指定 fetcher 的 scrape_* 共用同一個連線與限速
+pub fn default_fetcher() -> &'static PoliteFetcher {
+    &DEFAULT_FETCHER
+}
+
+/// 爬蟲連線的設定，預設每個主機間隔 500 毫秒、失敗重試 3 次
+#[derive(Clone, Debug)]
+pub struct HttpConfig {
+    pub user_agent: String,
+    pub timeout: Duration,
+    pub interval: Duration, // 同一主機兩次請求的最短間隔
+    pub host_intervals: HashMap<String, Duration>, // 個別主機的間隔，優先於 interval
+    pub max_retries: u32,
+    pub backoff: Duration, // 第一次重試前的等待，之後每次加倍
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            user_agent: concat!("otherlawresource/", env!("CARGO_PKG_VERSION")).to_string(),
+            timeout: Duration::from_secs(30),
+            interval: Duration::from_millis(500),
+            host_intervals: HashMap::new(),
+            max_retries: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl HttpConfig {
+    pub fn interval_for(&self, host: &str) -> Duration {
+        self.host_intervals
+            .get(host)
+            .copied()
+            .unwrap_or(self.interval)
+    }
+
+    /// attempt 從 0 開始，最多等到 backoff 的 64 倍
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        self.backoff * 2u32.pow(attempt.min(6))
+    }
+}
+
+// 逾時、連線失敗、5xx 與 429 才重試，其他 4xx 重試也不會成功
+fn is_retryable(e: &reqwest::Error) -> bool {
+    e.is_timeout()
+        || e.is_connect()
+        || e.status().is_some_and(|status| {
+            status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        })
+}
+
+/// 共用的 reqwest client，依主機限速並在暫時性錯誤時以指數退避重試
+#[derive(Debug)]
+pub struct PoliteFetcher {
+    client: reqwest::Client,
+    config: HttpConfig,
+    next_slot: Mutex<HashMap<String, Instant>>, // 各主機下一次可以送出請求的時間
+}
+
+impl PoliteFetcher {
+    pub fn new(config: HttpConfig) -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.timeout)
+            .build()?;
+        Ok(PoliteFetcher {
+            client,
+            config,
+            next_slot: Mutex::new(HashMap::new()),
+        })
+    }
+
+    // 先預約時段再等待，同時送出的請求也會依序錯開
+    async fn wait_turn(&self, url: &str) {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_string()))
+            .unwrap_or_default();
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = next_slot
+                .get(&host)
+                .copied()
+                .map_or(Instant::now(), |slot| slot.max(Instant::now()));
+            next_slot.insert(host.clone(), slot + self.config.interval_for(&host));
+            slot
+        };
+        sleep_until(slot).await;
+    }
+}
+
+impl HttpFetcher for PoliteFetcher {
+    async fn fetch(&self, url: &str) -> Result<String, FetchError> {
+        let mut attempt = 0;
+        loop {
+            self.wait_turn(url).await;
+            let error = match self
+                .client
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+            {
+                Ok(response) => match response.text().await {
+                    Ok(text) => return Ok(text),
+                    Err(e) => e,
+                },
+                Err(e) => e,
+            };
+            if attempt >= self.config.max_retries || !is_retryable(&error) {
+                return Err(error.into());
+            }
+            let wait = self.config.backoff_for(attempt);
+            eprintln!("{url} 失敗（{error}），{wait:?} 後重試");
+            sleep(wait).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// 失敗的項目不直接丟掉，整輪跑完後等待 cooldown 再重試，最多 passes 輪
+#[derive(Debug)]
+pub struct RetryQueue {
+    pending: VecDeque<String>,
+    failed: Vec<(String, String)>, // key 與最後一次的錯誤
+    passes: u32,
+    cooldown: Duration,
+}
+
+impl RetryQueue {
+    pub fn new(keys: Vec<String>, passes: u32, cooldown: Duration) -> Self {
+        RetryQueue {
+            pending: keys.into(),
+            failed: Vec::new(),
+            passes,
+            cooldown,
+        }
+    }
+
+    pub async fn next(&mut self) -> Option<String> {
+        if self.pending.is_empty() && !self.failed.is_empty() && self.passes > 0 {
+            self.passes -= 1;
+            eprintln!("{} 項失敗，{:?} 後重試", self.failed.len(), self.cooldown);
+            sleep(self.cooldown).await;
+            self.pending = self.failed.drain(..).map(|(key, _)| key).collect();
+        }
+        self.pending.pop_front()
+    }
+
+    pub fn fail(&mut self, key: String, error: impl Display) {
+        eprintln!("{key} 失敗: {error}");
+        self.failed.push((key, error.to_string()));
+    }
+
+    /// 重試後仍失敗的項目，沒有記錄進度，下次執行會再處理
+    pub fn failed(&self) -> &[(String, String)] {
+        &self.failed
+    }
+}
+
+/// 以事先存下的網頁代替連線，url 對應到 dir 底下的檔案
+#[derive(Clone, Debug)]
+pub struct FixtureFetcher {
+    dir: PathBuf,
+    files: HashMap<String, String>,
+}
+
+impl FixtureFetcher {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FixtureFetcher {
+            dir: dir.into(),
+            files: HashMap::new(),
+        }
+    }
+
+    pub fn with(mut self, url: &str, file_name: &str) -> Self {
+        self.files.insert(url.to_string(), file_name.to_string());
+        self
+    }
+}
+
+impl HttpFetcher for FixtureFetcher {
+    async fn fetch(&self, url: &str) -> Result<String, FetchError> {
+        let file_name = self
+            .files
+            .get(url)
+            .ok_or_else(|| format!("沒有對應的測試網頁：{url}"))?;
+        Ok(std::fs::read_to_string(self.dir.join(file_name))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_per_host() {
+        let config = HttpConfig {
+            host_intervals: HashMap::from([("law.moj.gov.tw".to_string(), Duration::from_secs(2))]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.interval_for("law.moj.gov.tw"),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            config.interval_for("mojlaw.moj.gov.tw"),
+            Duration::from_millis(500)
+        );
+        assert_eq!(config.backoff_for(0), Duration::from_secs(1));
+        assert_eq!(config.backoff_for(3), Duration::from_secs(8));
+        assert_eq!(config.backoff_for(20), Duration::from_secs(64));
+    }
+
+    #[tokio::test]
+    async fn retries_failed_keys_once_more() {
+        let mut queue = RetryQueue::new(vec!["1".to_string(), "2".to_string()], 1, Duration::ZERO);
+        let mut seen = Vec::new();
+        while let Some(key) = queue.next().await {
+            seen.push(key.clone());
+            if key == "2" {
+                queue.fail(key, "503");
+            }
+        }
+        assert_eq!(seen, vec!["1", "2", "2"]);
+        assert_eq!(queue.failed(), &[("2".to_string(), "503".to_string())]);
+    }
+}
@@ -0,0 +1,360 @@
+use crate::references::References;
+use crate::{NewInter, OldInterpretation, Precedent, Resolution};
+use new_law::citation::CitationFinder;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// 鄰近範圍查詢最多往外幾層，避免一次回傳整張圖
+pub const MAX_DEPTH: usize = 3;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Law,               // id 例如「民法-184」
+    OldInterpretation, // 釋字，id 為號碼
+    NewInterpretation, // 憲法法庭判決
+    Precedent,
+    Resolution, // 民庭、刑庭決議
+}
+
+impl NodeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeKind::Law => "law",
+            NodeKind::OldInterpretation => "oldinterpretation",
+            NodeKind::NewInterpretation => "newinterpretation",
+            NodeKind::Precedent => "precedent",
+            NodeKind::Resolution => "resolution",
+        }
+    }
+
+    /// 與各資源的路由名稱相同，例如 `/graph/oldinterpretation/748/cites`
+    pub fn parse(text: &str) -> Option<NodeKind> {
+        match text {
+            "law" => Some(NodeKind::Law),
+            "oldinterpretation" => Some(NodeKind::OldInterpretation),
+            "newinterpretation" => Some(NodeKind::NewInterpretation),
+            "precedent" => Some(NodeKind::Precedent),
+            "resolution" => Some(NodeKind::Resolution),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct GraphNode {
+    pub key: String, // 「kind:id」，匯出時作為節點識別
+    pub kind: NodeKind,
+    pub id: String,
+    pub label: String,
+}
+
+/// source 引用 target
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Subgraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// 釋字、憲判、判例、決議與法條之間的引用關係，載入資料時建立一次
+#[derive(Clone, Debug, Default)]
+pub struct CitationGraph {
+    nodes: Vec<GraphNode>,
+    index: HashMap<(NodeKind, String), usize>,
+    cites: Vec<Vec<usize>>,
+    cited_by: Vec<Vec<usize>>,
+}
+
+fn label(kind: NodeKind, id: &str) -> String {
+    match kind {
+        NodeKind::Law => match id.rsplit_once('-') {
+            // 「民法-184-1」->「民法第184條之1」
+            Some((rest, part)) if rest.contains('-') => match rest.rsplit_once('-') {
+                Some((name, num)) => format!("{name}第{num}條之{part}"),
+                None => id.to_string(),
+            },
+            Some((name, num)) => format!("{name}第{num}條"),
+            None => id.to_string(),
+        },
+        NodeKind::OldInterpretation => format!("釋字第{id}號"),
+        _ => id.to_string(),
+    }
+}
+
+impl CitationGraph {
+    fn node(&mut self, kind: NodeKind, id: &str, name: Option<&str>) -> usize {
+        if let Some(&index) = self.index.get(&(kind, id.to_string())) {
+            if let Some(name) = name {
+                self.nodes[index].label = name.to_string();
+            }
+            return index;
+        }
+        let index = self.nodes.len();
+        self.nodes.push(GraphNode {
+            key: format!("{}:{}", kind.as_str(), id),
+            kind,
+            id: id.to_string(),
+            label: name.map_or_else(|| label(kind, id), |name| name.to_string()),
+        });
+        self.index.insert((kind, id.to_string()), index);
+        self.cites.push(Vec::new());
+        self.cited_by.push(Vec::new());
+        index
+    }
+
+    fn edge(&mut self, source: usize, target: usize) {
+        if source != target && !self.cites[source].contains(&target) {
+            self.cites[source].push(target);
+            self.cited_by[target].push(source);
+        }
+    }
+
+    fn edges_from_references(&mut self, source: usize, references: &References) {
+        let targets = [
+            (NodeKind::Law, &references.reflawid),
+            (NodeKind::OldInterpretation, &references.refinter),
+            (NodeKind::Precedent, &references.refprecedent),
+        ];
+        for (kind, ids) in targets {
+            for id in ids {
+                let target = self.node(kind, id, None);
+                self.edge(source, target);
+            }
+        }
+    }
+
+    pub fn build(
+        old_inters: &[OldInterpretation],
+        new_inters: &[NewInter],
+        precedents: &[Precedent],
+        resolutions: &[Resolution],
+        finder: &CitationFinder,
+    ) -> CitationGraph {
+        let mut graph = CitationGraph::default();
+
+        for inter in old_inters {
+            let source = graph.node(NodeKind::OldInterpretation, &inter.id, None);
+            for law in inter.reflawid.iter().flatten() {
+                let target = graph.node(NodeKind::Law, law, None);
+                graph.edge(source, target);
+            }
+            for other in inter.refinter.iter().flatten() {
+                let target = graph.node(NodeKind::OldInterpretation, other, None);
+                graph.edge(source, target);
+            }
+        }
+
+        for inter in new_inters {
+            let source = graph.node(NodeKind::NewInterpretation, &inter.id, Some(&inter.name));
+            for law in &inter.reflaws {
+                let target = graph.node(NodeKind::Law, law, None);
+                graph.edge(source, target);
+            }
+            // 憲判理由中引用的釋字
+            graph.edges_from_references(source, &References::extract(&inter.reason, finder));
+        }
+
+        for precedent in precedents {
+            let source = graph.node(NodeKind::Precedent, &precedent.id, Some(&precedent.name));
+            graph.edges_from_references(source, &precedent.references(finder));
+        }
+
+        for resolution in resolutions {
+            let source = graph.node(NodeKind::Resolution, &resolution.id, Some(&resolution.name));
+            graph.edges_from_references(source, &resolution.references(finder));
+        }
+
+        graph
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn find(&self, kind: NodeKind, id: &str) -> Option<usize> {
+        self.index.get(&(kind, id.to_string())).copied()
+    }
+
+    /// 這份資料引用了哪些資料
+    pub fn cites(&self, kind: NodeKind, id: &str) -> Option<Vec<&GraphNode>> {
+        self.find(kind, id)
+            .map(|index| self.cites[index].iter().map(|&i| &self.nodes[i]).collect())
+    }
+
+    /// 哪些資料引用了這份資料
+    pub fn cited_by(&self, kind: NodeKind, id: &str) -> Option<Vec<&GraphNode>> {
+        self.find(kind, id).map(|index| {
+            self.cited_by[index]
+                .iter()
+                .map(|&i| &self.nodes[i])
+                .collect()
+        })
+    }
+
+    /// 不分方向往外 depth 層內的節點，以及這些節點之間的引用
+    pub fn neighborhood(&self, kind: NodeKind, id: &str, depth: usize) -> Option<Subgraph> {
+        let start = self.find(kind, id)?;
+        let depth = depth.min(MAX_DEPTH);
+        let mut seen = HashSet::from([start]);
+        let mut order = vec![start];
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((index, level)) = queue.pop_front() {
+            if level == depth {
+                continue;
+            }
+            for &next in self.cites[index].iter().chain(&self.cited_by[index]) {
+                if seen.insert(next) {
+                    order.push(next);
+                    queue.push_back((next, level + 1));
+                }
+            }
+        }
+        Some(self.subgraph(&order, &seen))
+    }
+
+    fn subgraph(&self, order: &[usize], included: &HashSet<usize>) -> Subgraph {
+        let nodes = order.iter().map(|&i| self.nodes[i].clone()).collect();
+        let edges = order
+            .iter()
+            .flat_map(|&source| {
+                self.cites[source]
+                    .iter()
+                    .filter(|target| included.contains(target))
+                    .map(move |&target| GraphEdge {
+                        source: self.nodes[source].key.clone(),
+                        target: self.nodes[target].key.clone(),
+                    })
+            })
+            .collect();
+        Subgraph { nodes, edges }
+    }
+
+    /// 整張圖
+    pub fn export(&self) -> Subgraph {
+        let order: Vec<usize> = (0..self.nodes.len()).collect();
+        let included = order.iter().copied().collect();
+        self.subgraph(&order, &included)
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Subgraph {
+    /// 轉成 GraphML，可直接匯入 Gephi、yEd 等工具
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+            "  <graph id=\"citations\" edgedefault=\"directed\">\n",
+        ));
+        for node in &self.nodes {
+            xml.push_str(&format!(
+                "    <node id=\"{}\"><data key=\"kind\">{}</data><data key=\"label\">{}</data></node>\n",
+                escape_xml(&node.key),
+                node.kind.as_str(),
+                escape_xml(&node.label)
+            ));
+        }
+        for edge in &self.edges {
+            xml.push_str(&format!(
+                "    <edge source=\"{}\" target=\"{}\"/>\n",
+                escape_xml(&edge.source),
+                escape_xml(&edge.target)
+            ));
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use new_law::alias::LawAliases;
+
+    fn old(id: &str, laws: &[&str], inters: &[&str]) -> OldInterpretation {
+        OldInterpretation {
+            id: id.to_string(),
+            date: String::new(),
+            reasoning: None,
+            content: None,
+            trouble: None,
+            related_law: None,
+            source: String::new(),
+            reflaws: None,
+            reflawid: Some(laws.iter().map(|s| s.to_string()).collect()),
+            refinter: Some(inters.iter().map(|s| s.to_string()).collect()),
+            provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn traverses_and_exports() {
+        let aliases = LawAliases::builtin();
+        let finder = CitationFinder::new(["民法"], &aliases);
+        let precedent = Precedent {
+            id: "p1".to_string(),
+            name: "18年上字第1號".to_string(),
+            holding: "依民法第184條及釋字第748號".to_string(),
+            source: String::new(),
+            year: 18,
+            num: 1,
+            specific: String::new(),
+            court: None,
+            case_type: None,
+            reflawid: None,
+            refinter: None,
+            refprecedent: None,
+            provenance: Default::default(),
+        };
+        let graph = CitationGraph::build(
+            &[old("748", &["民法-972"], &["554"]), old("554", &[], &[])],
+            &[],
+            &[precedent],
+            &[],
+            &finder,
+        );
+
+        let cites: Vec<&str> = graph
+            .cites(NodeKind::OldInterpretation, "748")
+            .unwrap()
+            .iter()
+            .map(|node| node.label.as_str())
+            .collect();
+        assert_eq!(cites, vec!["民法第972條", "釋字第554號"]);
+        let cited_by = graph.cited_by(NodeKind::OldInterpretation, "748").unwrap();
+        assert_eq!(cited_by[0].label, "18年上字第1號");
+
+        let one_hop = graph
+            .neighborhood(NodeKind::OldInterpretation, "554", 1)
+            .unwrap();
+        assert_eq!(one_hop.nodes.len(), 2);
+        let two_hops = graph
+            .neighborhood(NodeKind::OldInterpretation, "554", 2)
+            .unwrap();
+        assert_eq!(two_hops.nodes.len(), 4);
+        assert!(
+            graph
+                .export()
+                .to_graphml()
+                .contains("<edge source=\"precedent:p1\" target=\"law:民法-184\"/>")
+        );
+    }
+}
@@ -6,6 +6,20 @@ use serde::Deserialize;
 use serde::Serialize;
 use sqlx::PgPool;
 use uuid::Uuid;
+use fetch::{default_fetcher, HttpFetcher};
+use new_law::citation::CitationFinder;
+use new_law::provenance::Provenance;
+use new_law::sync_run::{compare_hash, content_hash, WriteOutcome};
+use precedent::CaseNumber;
+use references::References;
+
+pub mod export;
+pub mod fetch;
+pub mod graph;
+pub mod precedent;
+pub mod references;
+pub mod sync;
+pub mod timeline;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NewInterpretation {
@@ -19,6 +33,8 @@ pub struct NewInterpretation {
     pub source: String,
     pub year: i16,
     pub number: i16,
+    #[serde(default)]
+    pub provenance: Provenance,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,11 +51,14 @@ pub struct NewInter {
     pub year: i16,
     pub number: i16,
     pub reflaws: Vec<String>,
+    #[serde(default)]
+    pub provenance: Provenance,
 }
 
 pub async fn get_newinterpretations(pool: &PgPool) -> Vec<NewInterpretation> {
     match sqlx::query("SELECT * from newinterpretations")
         .map(|row: PgRow| NewInterpretation {
+            provenance: Provenance::from_row(&row, "source"),
             id: row.get("id"),
             content: row.get("content"),
             no: row.get("no"),
@@ -65,6 +84,7 @@ pub async fn get_newinterpretations(pool: &PgPool) -> Vec<NewInterpretation> {
 pub async fn get_newinters(pool: &PgPool) -> Vec<NewInter> {
     match sqlx::query("SELECT * from newinterpretations")
         .map(|row: PgRow| NewInter {
+            provenance: Provenance::from_row(&row, "source"),
             id: row.get("id"),
             casename: row.get("casename"),
             casesummary: row.get("casesummary"),
@@ -91,9 +111,10 @@ pub async fn get_newinters(pool: &PgPool) -> Vec<NewInter> {
 
 impl NewInter {
     pub async fn add_to_pool(self, pool: &PgPool) {
+        let hash = content_hash(&(&self.maincontent, &self.reason));
         match sqlx::query(
-            "INSERT INTO newinters (id, casename, name, casesummary, date, reason, maincontent, related_law, source, year, number, reflaws)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            "INSERT INTO newinters (id, casename, name, casesummary, date, reason, maincontent, related_law, source, year, number, reflaws, fetched_at, content_hash, upstream_date)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, COALESCE($13, now()), $14, $15)",
         )
         .bind(self.id)
         .bind(self.casename)
@@ -107,6 +128,9 @@ impl NewInter {
         .bind(self.year)
         .bind(self.number)
         .bind(self.reflaws)
+        .bind(self.provenance.fetched_at)
+        .bind(hash)
+        .bind(self.provenance.upstream_date)
         .execute(pool)
         .await
         {
@@ -119,10 +143,11 @@ impl NewInter {
 impl NewInterpretation {
     pub async fn add_to_pool(self, pool: &PgPool) {
         let uuid = Uuid::new_v4().to_string();
+        let hash = content_hash(&self.content);
 
         match sqlx::query(
-            "INSERT INTO newinterpretations (id, no, name, date, reason, content, related_law, source, year, number)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            "INSERT INTO newinterpretations (id, no, name, date, reason, content, related_law, source, year, number, fetched_at, content_hash, upstream_date)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, COALESCE($11, now()), $12, $13)",
         )
         .bind(uuid)
         .bind(self.no)
@@ -134,6 +159,9 @@ impl NewInterpretation {
         .bind(self.source)
         .bind(self.year)
         .bind(self.number)
+        .bind(self.provenance.fetched_at)
+        .bind(hash)
+        .bind(self.provenance.upstream_date)
         .execute(pool)
         .await
         {
@@ -144,8 +172,22 @@ impl NewInterpretation {
 }
 
 pub async fn scrapeNewInterpretation(num: usize, href: String) -> NewInterpretation {
-    let html = get(href.clone()).await.unwrap().text().await.unwrap();
-    let doc = Document::from(html.as_str());
+    scrape_new_interpretation_with(default_fetcher(), num, href)
+        .await
+        .unwrap()
+}
+
+pub async fn scrape_new_interpretation_with<F: HttpFetcher>(
+    fetcher: &F,
+    num: usize,
+    href: String,
+) -> Result<NewInterpretation, fetch::FetchError> {
+    let html = fetcher.fetch(&href).await?;
+    Ok(parse_new_interpretation(num, href, &html))
+}
+
+pub fn parse_new_interpretation(num: usize, href: String, html: &str) -> NewInterpretation {
+    let doc = Document::from(html);
     let mut inter = NewInterpretation {
         id: num.to_string(),
         no: "".to_string(),
@@ -154,9 +196,10 @@ pub async fn scrapeNewInterpretation(num: usize, href: String) -> NewInterpretat
         related_law: None,
         reason: None,
         content: None,
-        source: href,
+        source: href.clone(),
         year: 0,
         number: 0,
+        provenance: Provenance::fetched(href, None),
     };
 
     for pre in doc.find(Name("pre")) {
@@ -184,6 +227,7 @@ pub async fn scrapeNewInterpretation(num: usize, href: String) -> NewInterpretat
                 inter.name = date;
             } else if th.text() == "裁判日期：" {
                 let date = tr.find(Name("td")).next().unwrap().text();
+                inter.provenance.upstream_date = Some(date.clone());
                 inter.date = date;
             } else if th.text() == "相關法條：" {
                 let date = tr.find(Name("td")).next().unwrap().text();
@@ -194,7 +238,6 @@ pub async fn scrapeNewInterpretation(num: usize, href: String) -> NewInterpretat
             }
         }
     }
-    println!("{:#?}", inter);
     inter
 }
 
@@ -210,13 +253,16 @@ pub struct OldInterpretation {
     pub reflaws: Option<Vec<String>>,
     pub reflawid: Option<Vec<String>>,
     pub refinter: Option<Vec<String>>,
+    #[serde(default)]
+    pub provenance: Provenance,
 }
 
 impl OldInterpretation {
     pub async fn add_to_pool(self, pool: &PgPool) {
+        let hash = content_hash(&(&self.content, &self.reasoning));
         match sqlx::query(
-            "INSERT INTO oldinters (id, date, reasoning, content, trouble, related_law, source, reflaws, reflawid, refinter)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            "INSERT INTO oldinters (id, date, reasoning, content, trouble, related_law, source, reflaws, reflawid, refinter, fetched_at, content_hash, upstream_date)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, COALESCE($11, now()), $12, $13)",
         )
         .bind(self.id)
         .bind(self.date)
@@ -228,6 +274,9 @@ impl OldInterpretation {
         .bind(self.reflaws)
         .bind(self.reflawid)
         .bind(self.refinter)
+        .bind(self.provenance.fetched_at)
+        .bind(hash)
+        .bind(self.provenance.upstream_date)
         .execute(pool)
         .await
         {
@@ -260,6 +309,7 @@ impl OldInterpretation {
 pub async fn get_all_oldinterpretation(pool: &PgPool) -> Vec<OldInterpretation> {
     match sqlx::query("SELECT * FROM oldinters")
         .map(|row: PgRow| OldInterpretation {
+            provenance: Provenance::from_row(&row, "source"),
             id: row.get("id"),
             date: row.get("date"),
             reasoning: row.get("reasoning"),
@@ -282,13 +332,28 @@ pub async fn get_all_oldinterpretation(pool: &PgPool) -> Vec<OldInterpretation>
     }
 }
 
+pub fn old_interpretation_href(num: &str) -> String {
+    format!("https://mojlaw.moj.gov.tw/LawContentExShow.aspx?id=D%2C{num}&type=c&kw=")
+}
+
 pub async fn scrapeOldInterpretation(num: String) -> OldInterpretation {
-    let href = format!(
-        "https://mojlaw.moj.gov.tw/LawContentExShow.aspx?id=D%2C{}&type=c&kw=",
-        num.clone()
-    );
-    let html = get(href.clone()).await.unwrap().text().await.unwrap();
-    let doc = Document::from(html.as_str());
+    let href = old_interpretation_href(&num);
+    scrape_old_interpretation_with(default_fetcher(), num, href)
+        .await
+        .unwrap()
+}
+
+pub async fn scrape_old_interpretation_with<F: HttpFetcher>(
+    fetcher: &F,
+    num: String,
+    href: String,
+) -> Result<OldInterpretation, fetch::FetchError> {
+    let html = fetcher.fetch(&href).await?;
+    Ok(parse_old_interpretation(num, href, &html))
+}
+
+pub fn parse_old_interpretation(num: String, href: String, html: &str) -> OldInterpretation {
+    let doc = Document::from(html);
     let mut inter = OldInterpretation {
         id: num.to_string(),
         trouble: None,
@@ -300,6 +365,7 @@ pub async fn scrapeOldInterpretation(num: String) -> OldInterpretation {
         reflaws: Some(Vec::new()),
         reflawid: Some(Vec::new()),
         refinter: Some(Vec::new()),
+        provenance: Provenance::fetched(href, None),
     };
     for pre in doc.find(Name("pre")) {
         if (pre.text().starts_with("理 由 書：")) {
@@ -314,6 +380,7 @@ pub async fn scrapeOldInterpretation(num: String) -> OldInterpretation {
                 inter.reasoning = Some(res);
             } else if th.text() == "解釋日期：" {
                 let date = tr.find(Name("td")).next().unwrap().text();
+                inter.provenance.upstream_date = Some(date.clone());
                 inter.date = date;
             } else if th.text() == "相關法條：" {
                 let date = tr.find(Name("td")).next().unwrap().text();
@@ -335,7 +402,19 @@ pub struct Precedent {
     pub source: String,
     pub year: i16,
     pub num: i16,
-    pub specific: String,
+    pub specific: String, // 字別，例如「台上」
+    #[serde(default)]
+    pub court: Option<String>,
+    #[serde(default)]
+    pub case_type: Option<String>, // 民事、刑事或行政
+    #[serde(default)]
+    pub reflawid: Option<Vec<String>>,
+    #[serde(default)]
+    pub refinter: Option<Vec<String>>,
+    #[serde(default)]
+    pub refprecedent: Option<Vec<String>>,
+    #[serde(default)]
+    pub provenance: Provenance,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -345,13 +424,30 @@ pub struct OtherSourceList {
     pub sourcetype: String,
 }
 
-pub async fn scrape_precedent(num: usize) -> Vec<Precedent> {
-    let href = format!(
+/// 判例搜尋結果的第 page 頁
+pub fn precedent_list_href(page: usize) -> String {
+    format!(
         "https://mojlaw.moj.gov.tw/LawResultList.aspx?id=&check=jtype&search=3&valid=3&star=&end=&number=&kw=&sort=&LawType=jtype&iPageSize=10&page={}",
-        num.clone()
-    );
-    let html = get(href.clone()).await.unwrap().text().await.unwrap();
-    let doc = Document::from(html.as_str());
+        page
+    )
+}
+
+pub async fn scrape_precedent(num: usize) -> Vec<Precedent> {
+    let href = precedent_list_href(num);
+    scrape_precedent_with(default_fetcher(), &href).await.unwrap()
+}
+
+pub async fn scrape_precedent_with<F: HttpFetcher>(
+    fetcher: &F,
+    href: &str,
+) -> Result<Vec<Precedent>, fetch::FetchError> {
+    let html = fetcher.fetch(href).await?;
+    Ok(parse_precedent_list(&html))
+}
+
+/// 判例搜尋結果的一頁，每個 tr 為一則判例
+pub fn parse_precedent_list(html: &str) -> Vec<Precedent> {
+    let doc = Document::from(html);
     let mut buffer = Vec::new();
 
     for node in doc.find(Name("tr")) {
@@ -363,22 +459,27 @@ pub async fn scrape_precedent(num: usize) -> Vec<Precedent> {
             year: 0,
             num: 0,
             specific: "".to_string(),
+            court: None,
+            case_type: None,
+            reflawid: None,
+            refinter: None,
+            refprecedent: None,
+            provenance: Provenance::default(),
         };
 
         if let Some(a) = node.find(Name("a")).next() {
             let name = a.text();
             let href = a.attr("href").unwrap();
             let new_href = format!("https://mojlaw.moj.gov.tw/{}", href);
-            let re =
-                Regex::new(r"(?P<year>\d{2,4})年(?P<word>.+?)字第(?P<number>\d{1,4})號").unwrap();
-            let caps = re.captures(&name).unwrap();
-            let year = caps.name("year").unwrap().as_str().parse().ok().unwrap();
-            let word = caps.name("word").unwrap().as_str().to_string();
-            let number = caps.name("number").unwrap().as_str().parse().ok().unwrap();
-            pre.specific = word;
-            pre.num = number;
-            pre.year = year;
+            if let Some(case_no) = CaseNumber::parse(&name) {
+                pre.specific = case_no.word;
+                pre.num = case_no.number;
+                pre.year = case_no.year;
+                pre.court = case_no.court;
+                pre.case_type = case_no.case_type;
+            }
             pre.name = name;
+            pre.provenance = Provenance::fetched(new_href.clone(), None);
             pre.source = new_href;
         };
 
@@ -392,11 +493,93 @@ pub async fn scrape_precedent(num: usize) -> Vec<Precedent> {
 }
 
 impl Precedent {
+    /// 由字號補上法院、年度、字別、號次與案件類別，無法解析時回傳 false
+    pub fn parse_name(&mut self) -> bool {
+        match CaseNumber::parse(&self.name) {
+            Some(case_no) => {
+                self.year = case_no.year;
+                self.specific = case_no.word;
+                self.num = case_no.number;
+                self.court = case_no.court;
+                self.case_type = case_no.case_type;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub async fn update_metadata(&self, pool: &PgPool) {
+        match sqlx::query(
+            "UPDATE precedents
+            SET court = $1, case_type = $2, year = $3, specific = $4, num = $5
+            WHERE id = $6",
+        )
+        .bind(self.court.clone())
+        .bind(self.case_type.clone())
+        .bind(self.year)
+        .bind(self.specific.clone())
+        .bind(self.num)
+        .bind(self.id.clone())
+        .execute(pool)
+        .await
+        {
+            Ok(_) => println!("Update successful: {}", self.id),
+            Err(e) => eprintln!("Update failed: {}", e),
+        }
+    }
+
+    /// 匯入前從要旨抽出引用的法條、釋字與其他判例
+    pub fn link_references(&mut self, finder: &CitationFinder) {
+        let references = References::extract(&self.holding, finder);
+        self.reflawid = Some(references.reflawid);
+        self.refinter = Some(references.refinter);
+        // 要旨中提到自己的字號時不算引用
+        let id = format!("{}-{}-{}", self.year, self.specific, self.num);
+        self.refprecedent = Some(
+            references
+                .refprecedent
+                .into_iter()
+                .filter(|other| *other != id)
+                .collect(),
+        );
+    }
+
+    /// 已存的引用，尚未補上時才從要旨抽出
+    pub fn references(&self, finder: &CitationFinder) -> References {
+        match (&self.reflawid, &self.refinter, &self.refprecedent) {
+            (Some(reflawid), Some(refinter), Some(refprecedent)) => References {
+                reflawid: reflawid.clone(),
+                refinter: refinter.clone(),
+                refprecedent: refprecedent.clone(),
+            },
+            _ => References::extract(&self.holding, finder),
+        }
+    }
+
+    pub async fn update_references(&self, pool: &PgPool) {
+        match sqlx::query(
+            "UPDATE precedents
+            SET reflawid = $1, refinter = $2, refprecedent = $3
+            WHERE id = $4",
+        )
+        .bind(self.reflawid.clone())
+        .bind(self.refinter.clone())
+        .bind(self.refprecedent.clone())
+        .bind(self.id.clone())
+        .execute(pool)
+        .await
+        {
+            Ok(_) => println!("Update successful: {}", self.id),
+            Err(e) => eprintln!("Update failed: {}", e),
+        }
+    }
+
     pub async fn add_to_pool(self, pool: &PgPool) {
         let id = format!("{}-{}-{}", self.year, self.specific, self.num);
+        let hash = content_hash(&self.holding);
         match sqlx::query(
-            "INSERT INTO precedents (id, name, holding, source, year, num, specific)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            "INSERT INTO precedents (id, name, holding, source, year, num, specific, court, case_type, reflawid, refinter, refprecedent, fetched_at, content_hash, upstream_date)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, COALESCE($13, now()), $14, $15)",
         )
         .bind(id.clone())
         .bind(self.name)
@@ -405,6 +588,14 @@ impl Precedent {
         .bind(self.year)
         .bind(self.num)
         .bind(self.specific)
+        .bind(self.court)
+        .bind(self.case_type)
+        .bind(self.reflawid)
+        .bind(self.refinter)
+        .bind(self.refprecedent)
+        .bind(self.provenance.fetched_at)
+        .bind(hash)
+        .bind(self.provenance.upstream_date)
         .execute(pool)
         .await
         {
@@ -427,14 +618,65 @@ pub struct Resolution {
     pub source: String,
     pub year: i16,
     pub time: i16,
+    #[serde(default)]
+    pub reflawid: Option<Vec<String>>,
+    #[serde(default)]
+    pub refinter: Option<Vec<String>>,
+    #[serde(default)]
+    pub refprecedent: Option<Vec<String>>,
+    #[serde(default)]
+    pub provenance: Provenance,
 }
 
 impl Resolution {
+    /// 匯入前從決議內容與相關法條抽出引用
+    pub fn link_references(&mut self, finder: &CitationFinder) {
+        let references = References::extract(&self.reference_text(), finder);
+        self.reflawid = Some(references.reflawid);
+        self.refinter = Some(references.refinter);
+        self.refprecedent = Some(references.refprecedent);
+    }
+
+    /// 已存的引用，尚未補上時才從內容抽出
+    pub fn references(&self, finder: &CitationFinder) -> References {
+        match (&self.reflawid, &self.refinter, &self.refprecedent) {
+            (Some(reflawid), Some(refinter), Some(refprecedent)) => References {
+                reflawid: reflawid.clone(),
+                refinter: refinter.clone(),
+                refprecedent: refprecedent.clone(),
+            },
+            _ => References::extract(&self.reference_text(), finder),
+        }
+    }
+
+    fn reference_text(&self) -> String {
+        format!("{}\n{}", self.related_law, self.content)
+    }
+
+    pub async fn update_references(&self, pool: &PgPool) {
+        match sqlx::query(
+            "UPDATE resolution
+            SET reflawid = $1, refinter = $2, refprecedent = $3
+            WHERE id = $4",
+        )
+        .bind(self.reflawid.clone())
+        .bind(self.refinter.clone())
+        .bind(self.refprecedent.clone())
+        .bind(self.id.clone())
+        .execute(pool)
+        .await
+        {
+            Ok(_) => println!("Update successful: {}", self.id),
+            Err(e) => eprintln!("Update failed: {}", e),
+        }
+    }
+
     pub async fn add_to_pool(self, pool: &PgPool) {
         let id = format!("{}-{}", self.year, self.time);
+        let hash = content_hash(&self.content);
         match sqlx::query(
-            "INSERT INTO resolution (id, lawtype, related_law, name, content, source, year, time)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            "INSERT INTO resolution (id, lawtype, related_law, name, content, source, year, time, reflawid, refinter, refprecedent, fetched_at, content_hash, upstream_date)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, COALESCE($12, now()), $13, $14)",
         )
         .bind(id.clone())
         .bind(self.lawtype)
@@ -444,6 +686,12 @@ impl Resolution {
         .bind(self.source)
         .bind(self.year)
         .bind(self.time)
+        .bind(self.reflawid)
+        .bind(self.refinter)
+        .bind(self.refprecedent)
+        .bind(self.provenance.fetched_at)
+        .bind(hash)
+        .bind(self.provenance.upstream_date)
         .execute(pool)
         .await
         {
@@ -456,25 +704,63 @@ impl Resolution {
     }
 }
 
-use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+pub async fn scrape_resolution(href: String) -> Resolution {
+    scrape_resolution_with(default_fetcher(), &href)
+        .await
+        .unwrap()
+}
+
+pub async fn scrape_resolution_with<F: HttpFetcher>(
+    fetcher: &F,
+    href: &str,
+) -> Result<Resolution, fetch::FetchError> {
+    let html = fetcher.fetch(href).await?;
+    Ok(parse_resolution(href, &html))
+}
 
-pub fn scrapeResolution(num: String) -> Resolution {
-    let read_file_path = format!("民庭決議2/{num}.html");
-    let mut file = File::open(read_file_path).expect("ppp");
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
+/// 決議導覽頁中各決議的名稱與網址
+pub async fn scrape_resolution_index_with<F: HttpFetcher>(
+    fetcher: &F,
+    index: &str,
+) -> Result<Vec<(String, String)>, fetch::FetchError> {
+    let html = fetcher.fetch(index).await?;
+    Ok(parse_resolution_index(index, &html))
+}
 
-    let doc = Document::from(contents.as_str());
+/// 相對網址以導覽頁的網址為基準補成完整網址
+pub fn parse_resolution_index(index: &str, html: &str) -> Vec<(String, String)> {
+    let base = reqwest::Url::parse(index).ok();
+    let doc = Document::from(html);
+    let mut entries = Vec::new();
+    if let Some(node) = doc.find(And(Class("list"), Name("div"))).next() {
+        for a in node.find(Name("a")) {
+            if let (Some(title), Some(href)) = (a.attr("title"), a.attr("href")) {
+                let href = base
+                    .as_ref()
+                    .and_then(|base| base.join(href).ok())
+                    .map_or(href.to_string(), |url| url.to_string());
+                entries.push((title.to_string(), href));
+            }
+        }
+    }
+    entries
+}
+
+pub fn parse_resolution(href: &str, html: &str) -> Resolution {
+    let doc = Document::from(html);
     let mut resolution = Resolution {
         id: "".to_string(),
         lawtype: "民事".to_string(),
         related_law: "".to_string(),
         name: "".to_string(),
         content: "".to_string(),
-        source: "".to_string(),
+        source: href.to_string(),
         year: 0,
         time: 0,
+        reflawid: None,
+        refinter: None,
+        refprecedent: None,
+        provenance: Provenance::fetched(href, None),
     };
     if let Some(node) = doc.find(And(Class("title"), Name("h2"))).next() {
         let title = node.text();
@@ -654,6 +940,17 @@ pub fn scrape_lawinformation(html: String) -> Result<Lawinformation, Box<dyn Err
 
 use std::error::Error;
 pub async fn scrape_new_law(title: String, html: String) -> Result<NewLaws, Box<dyn Error>> {
+    scrape_new_law_with(default_fetcher(), title, html)
+        .await
+        .map_err(|e| e as Box<dyn Error>)
+}
+
+/// html 為法規全文頁，有章節時再以 fetcher 取得章節頁
+pub async fn scrape_new_law_with<F: HttpFetcher>(
+    fetcher: &F,
+    title: String,
+    html: String,
+) -> Result<NewLaws, fetch::FetchError> {
     let document = Document::from(html.as_str());
     let mut law_vec: Vec<NewLaw> = Vec::new();
     document.find(Class("row")).for_each(|row| {
@@ -665,6 +962,8 @@ pub async fn scrape_new_law(title: String, html: String) -> Result<NewLaws, Box<
             let lines = format_lines(row);
             let id = format!("{title}-{num}");
             let chapter = vec![title.clone()];
+            let provenance =
+                Provenance::fetched(format!("https://law.moj.gov.tw/LawClass/{href}"), None);
 
             law_vec.push(NewLaw {
                 id,
@@ -672,6 +971,7 @@ pub async fn scrape_new_law(title: String, html: String) -> Result<NewLaws, Box<
                 chapter,
                 num,
                 lines,
+                provenance,
             });
         }
     });
@@ -680,7 +980,8 @@ pub async fn scrape_new_law(title: String, html: String) -> Result<NewLaws, Box<
     if let Some(node) = document.find(Attr("id", "hlkHD_CHAR")).next() {
         let href = node.attr("href").unwrap();
         let new_href = format!("https://law.moj.gov.tw{}", href);
-        chapter_vec = scrape_chapter(new_href, title.clone()).await;
+        let chapter_html = fetcher.fetch(&new_href).await?;
+        chapter_vec = parse_chapters(&chapter_html, title.clone());
     } else {
         return Ok(NewLaws { lines: law_vec });
     }
@@ -714,13 +1015,12 @@ pub async fn scrape_new_law(title: String, html: String) -> Result<NewLaws, Box<
 
 use std::collections::HashMap;
 pub async fn scrape_chapter(href: String, maintitle: String) -> Vec<Chapter> {
-    let html = reqwest::get(href.clone())
-        .await
-        .unwrap()
-        .text()
-        .await
-        .unwrap();
-    let doc = Document::from(html.as_str());
+    let html = default_fetcher().fetch(&href).await.unwrap();
+    parse_chapters(&html, maintitle)
+}
+
+pub fn parse_chapters(html: &str, maintitle: String) -> Vec<Chapter> {
+    let doc = Document::from(html);
     let mut char_map = HashMap::new();
     char_map.insert(1, "".to_string());
     char_map.insert(2, "".to_string());
@@ -774,25 +1074,10 @@ pub async fn scrape_chapter(href: String, maintitle: String) -> Vec<Chapter> {
     }
     let mut vec = Vec::new();
     map.values().for_each(|x| vec.push(x.clone()));
-    vec.sort_by(|a, b| {
-        to_f32(a.num.clone())
-            .partial_cmp(&to_f32(b.num.clone()))
-            .unwrap()
-    });
+    vec.sort_by(|a, b| new_law::article::compare_nums(&a.num, &b.num));
     vec
 }
 
-fn to_f32(s: String) -> f32 {
-    if s.contains("-") {
-        let (big, small) = s.split_once("-").unwrap();
-        let big_number: f32 = big.parse().unwrap();
-        let small_number: f32 = small.parse().unwrap();
-        big_number + small_number * 0.1
-    } else {
-        s.parse().unwrap()
-    }
-}
-
 #[derive(Clone, Debug)]
 pub struct Chapter {
     pub title: String,
@@ -809,27 +1094,38 @@ pub struct Lawinformation {
 }
 
 impl Lawinformation {
-    pub async fn add_to_pool(&self, pool: &PgPool) {
+    /// update_date 是爬取當天，不列入 content_hash，內容沒變時保留上次變動的日期
+    pub async fn add_to_pool(&self, pool: &PgPool) -> Result<WriteOutcome, sqlx::Error> {
+        let hash = content_hash(&(&self.originalid, &self.name, &self.release_date));
+        let stored = sqlx::query("SELECT content_hash FROM lawinformation WHERE originalid = $1")
+            .bind(&self.originalid)
+            .map(|row: PgRow| row.get::<Option<String>, _>("content_hash"))
+            .fetch_optional(pool)
+            .await?;
+        let outcome = compare_hash(stored, &hash);
+        if outcome == WriteOutcome::Unchanged {
+            return Ok(outcome);
+        }
+
         let query = r#"
-            INSERT INTO lawinformation (originalid, name, update_date, release_date)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO lawinformation (originalid, name, update_date, release_date, content_hash)
+            VALUES ($1, $2, $3, $4, $5)
             ON CONFLICT (originalid) DO UPDATE
             SET name = EXCLUDED.name,
                 update_date = EXCLUDED.update_date,
-                release_date = EXCLUDED.release_date
+                release_date = EXCLUDED.release_date,
+                content_hash = EXCLUDED.content_hash
         "#;
 
-        match sqlx::query(query)
+        sqlx::query(query)
             .bind(&self.originalid)
             .bind(&self.name)
             .bind(&self.update_date)
             .bind(&self.release_date)
+            .bind(&hash)
             .execute(pool)
-            .await
-        {
-            Ok(_) => println!("✅ Upserted: {}", self.originalid),
-            Err(e) => eprintln!("❌ Failed [{}]: {}", self.originalid, e),
-        }
+            .await?;
+        Ok(outcome)
     }
 }
 
@@ -863,11 +1159,71 @@ pub struct HistoryLaw {
     pub date: String,
     pub content: String,
     pub no: i16,
+    #[serde(default)]
+    pub provenance: Provenance,
+}
+
+pub async fn get_all_precedent(pool: &PgPool) -> Vec<Precedent> {
+    let result = sqlx::query("SELECT * FROM precedents")
+        .map(|row: PgRow| Precedent {
+            provenance: Provenance::from_row(&row, "source"),
+            id: row.get("id"),
+            name: row.get("name"),
+            holding: row.get("holding"),
+            source: row.get("source"),
+            year: row.get("year"),
+            num: row.get("num"),
+            specific: row.get("specific"),
+            court: row.get("court"),
+            case_type: row.get("case_type"),
+            reflawid: row.get("reflawid"),
+            refinter: row.get("refinter"),
+            refprecedent: row.get("refprecedent"),
+        })
+        .fetch_all(pool)
+        .await;
+
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            eprintln!("Database query failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub async fn get_all_resolution(pool: &PgPool) -> Vec<Resolution> {
+    let result = sqlx::query("SELECT * FROM resolution")
+        .map(|row: PgRow| Resolution {
+            provenance: Provenance::from_row(&row, "source"),
+            id: row.get("id"),
+            lawtype: row.get("lawtype"),
+            related_law: row.get("related_law"),
+            name: row.get("name"),
+            content: row.get("content"),
+            source: row.get("source"),
+            year: row.get("year"),
+            time: row.get("time"),
+            reflawid: row.get("reflawid"),
+            refinter: row.get("refinter"),
+            refprecedent: row.get("refprecedent"),
+        })
+        .fetch_all(pool)
+        .await;
+
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            eprintln!("Database query failed: {}", e);
+            Vec::new()
+        }
+    }
 }
 
 pub async fn get_all_historylaw(pool: &PgPool) -> Vec<HistoryLaw> {
     let result = sqlx::query("SELECT * FROM history_law")
         .map(|row: PgRow| HistoryLaw {
+            provenance: Provenance::from_row(&row, "source"),
             id: row.get("id"),
             lawid: row.get("lawid"),
             date: row.get("date"),
@@ -890,6 +1246,7 @@ pub async fn get_historylaw(lawid: String, pool: &PgPool) -> Vec<HistoryLaw> {
     let result = sqlx::query("SELECT * FROM history_law WHERE lawid  = $1")
         .bind(lawid)
         .map(|row: PgRow| HistoryLaw {
+            provenance: Provenance::from_row(&row, "source"),
             id: row.get("id"),
             lawid: row.get("lawid"),
             date: row.get("date"),
@@ -911,8 +1268,8 @@ pub async fn get_historylaw(lawid: String, pool: &PgPool) -> Vec<HistoryLaw> {
 impl HistoryLaw {
     pub async fn add_to_pool(&self, pool: &PgPool) {
         let query = "
-            INSERT INTO history_law (id, lawid, date, content, no)
-            VALUES ($1, $2, $3, $4, $5)";
+            INSERT INTO history_law (id, lawid, date, content, no, source, fetched_at, content_hash, upstream_date)
+            VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, now()), $8, $9)";
 
         match sqlx::query(query)
             .bind(&self.id)
@@ -920,6 +1277,10 @@ impl HistoryLaw {
             .bind(&self.date)
             .bind(&self.content)
             .bind(self.no)
+            .bind(&self.provenance.source_url)
+            .bind(self.provenance.fetched_at)
+            .bind(content_hash(&self.content))
+            .bind(&self.provenance.upstream_date)
             .execute(pool)
             .await
         {
@@ -933,19 +1294,33 @@ pub async fn scrape_historylaw(
     href: String,
     chapter: String,
     lawnumber: String,
-) -> Result<Vec<HistoryLaw>, Box<dyn Error>> {
-    /*
-    let html = get(href.clone()).await?.text().await?;
-    let doc = Document::from(html.as_str());
-    */
-    let path = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), href);
-    println!("{path}");
-
-    println!("開檔 href: {:?} len: {}", href, href.len());
-    let mut file = File::open(&path).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-    let doc = Document::from(contents.as_str());
+) -> Result<Vec<HistoryLaw>, fetch::FetchError> {
+    scrape_historylaw_with(default_fetcher(), &href, chapter, lawnumber).await
+}
+
+pub async fn scrape_historylaw_with<F: HttpFetcher>(
+    fetcher: &F,
+    href: &str,
+    chapter: String,
+    lawnumber: String,
+) -> Result<Vec<HistoryLaw>, fetch::FetchError> {
+    let html = fetcher.fetch(href).await?;
+    Ok(parse_historylaw(href, &html, chapter, lawnumber))
+}
+
+/// 條文頁「LawSingle.aspx」對應的條文沿革頁
+pub fn history_href(article_href: &str) -> String {
+    article_href.replacen("LawSingle.aspx", "LawSingleHistory.aspx", 1)
+}
+
+/// 沿革頁中 row 兩兩一組：修正日期與當時的條文
+pub fn parse_historylaw(
+    href: &str,
+    html: &str,
+    chapter: String,
+    lawnumber: String,
+) -> Vec<HistoryLaw> {
+    let doc = Document::from(html);
 
     let mut rowvec = Vec::new();
 
@@ -958,21 +1333,19 @@ pub async fn scrape_historylaw(
     let lawid = format!("{}-{}", chapter, lawnumber);
     let mut buffer = Vec::new();
 
-    if rowvec.len() == 5 {
-        println!("{href}");
-    }
-
-    loop {
-        if number == rowvec.len() {
-            return Ok(buffer);
-        }
-
+    // 最後若只剩日期沒有條文就略過
+    while number + 1 < rowvec.len() {
         let date = rowvec[number].text();
-        let content = rowvec[number + 1].find(Name("pre")).next().unwrap().text();
+        let content = rowvec[number + 1]
+            .find(Name("pre"))
+            .next()
+            .map(|pre| pre.text())
+            .unwrap_or_default();
         let id = format!("{}-{}", lawid, no);
         let thehistory = HistoryLaw {
             id,
             lawid: lawid.clone(),
+            provenance: Provenance::fetched(href, Some(date.clone())),
             date,
             content,
             no,
@@ -983,22 +1356,23 @@ pub async fn scrape_historylaw(
         number += 2;
         no += 1;
     }
+    buffer
 }
 
 pub async fn scrape_historylaw2(
     href: String,
     chapter: String,
-) -> Result<Vec<HistoryLaw>, Box<dyn Error>> {
-    /*
-    let html = get(href.clone()).await?.text().await?;
-    let doc = Document::from(html.as_str());
-    */
-    let path = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), href);
-    println!("{path}");
-
-    let mut file = File::open(&path)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
+) -> Result<Vec<HistoryLaw>, fetch::FetchError> {
+    scrape_historylaw2_with(default_fetcher(), &href, chapter).await
+}
+
+/// 整部法規某次修正的條文對照頁
+pub async fn scrape_historylaw2_with<F: HttpFetcher>(
+    fetcher: &F,
+    href: &str,
+    chapter: String,
+) -> Result<Vec<HistoryLaw>, fetch::FetchError> {
+    let contents = fetcher.fetch(href).await?;
     let doc = Document::from(contents.as_str());
 
     let mut buffer = HistoryLaw {
@@ -1007,6 +1381,7 @@ pub async fn scrape_historylaw2(
         date: "".to_string(),
         content: "".to_string(),
         no: 1,
+        provenance: Provenance::default(),
     };
 
     if let Some(thedate) = doc.find(Name("b")).next() {
@@ -1042,6 +1417,7 @@ pub async fn scrape_historylaw2(
             date: buffer.date.clone(),
             content: contentbuffer[num].clone(),
             no: 1,
+            provenance: Provenance::fetched(href, Some(buffer.date.clone())),
         };
 
         historylawbuffer.push(thebuffer);
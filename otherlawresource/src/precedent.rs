@@ -0,0 +1,100 @@
+use new_law::article::parse_number;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+static CASE_NO_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?P<year>[0-9０-９〇零一二三四五六七八九十百]{1,6})\s*年度?\s*(?P<word>[^\s\d年度字第]{1,4}?)\s*字\s*第\s*(?P<number>[0-9０-９〇零一二三四五六七八九十百千]{1,8})\s*號").unwrap()
+});
+
+// 長的放前面，避免「最高行政法院」被當成「行政法院」
+const COURTS: [&str; 5] = [
+    "最高行政法院",
+    "最高法院",
+    "行政法院",
+    "公務員懲戒委員會",
+    "司法院",
+];
+
+/// 判例字號拆出的欄位，例如「最高法院49年台上字第929號民事判例」
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct CaseNumber {
+    pub court: Option<String>,
+    pub year: i16,
+    pub word: String, // 字別，例如「台上」、「台抗」
+    pub number: i16,
+    pub case_type: Option<String>, // 民事、刑事或行政
+}
+
+fn to_i16(text: &str) -> Option<i16> {
+    let text: String = text
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_digit(c as u32 - '０' as u32, 10).unwrap(),
+            _ => c,
+        })
+        .collect();
+    parse_number(&text).and_then(|n| i16::try_from(n).ok())
+}
+
+// 字號中沒寫明時，依字別與法院推斷案件類別
+fn infer_case_type(name: &str, court: Option<&str>, word: &str) -> Option<String> {
+    for case_type in ["民事", "刑事", "行政"] {
+        if name.contains(case_type) {
+            return Some(case_type.to_string());
+        }
+    }
+    if matches!(court, Some("行政法院" | "最高行政法院")) || word.contains('判') {
+        return Some("行政".to_string());
+    }
+    // 非常上訴只有刑事
+    if word.contains('非') {
+        return Some("刑事".to_string());
+    }
+    None
+}
+
+impl CaseNumber {
+    pub fn parse(name: &str) -> Option<CaseNumber> {
+        let caps = CASE_NO_RE.captures(name)?;
+        let court = COURTS
+            .iter()
+            .find(|court| name.contains(*court))
+            .map(|court| court.to_string());
+        let word = caps["word"].to_string();
+        Some(CaseNumber {
+            case_type: infer_case_type(name, court.as_deref(), &word),
+            court,
+            year: to_i16(&caps["year"])?,
+            word,
+            number: to_i16(&caps["number"])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_case_numbers() {
+        assert_eq!(
+            CaseNumber::parse("最高法院 49 年台上字第 929 號民事判例"),
+            Some(CaseNumber {
+                court: Some("最高法院".to_string()),
+                year: 49,
+                word: "台上".to_string(),
+                number: 929,
+                case_type: Some("民事".to_string()),
+            })
+        );
+        let criminal = CaseNumber::parse("最高法院四十四年台非字第五四號").unwrap();
+        assert_eq!((criminal.year, criminal.number), (44, 54));
+        assert_eq!(criminal.case_type.as_deref(), Some("刑事"));
+        let admin = CaseNumber::parse("最高行政法院９１年判字第１號").unwrap();
+        assert_eq!(admin.court.as_deref(), Some("最高行政法院"));
+        assert_eq!(admin.case_type.as_deref(), Some("行政"));
+        assert_eq!(CaseNumber::parse("18年上字第1號").unwrap().court, None);
+        assert!(CaseNumber::parse("釋字第748號").is_none());
+    }
+}
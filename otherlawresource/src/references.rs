@@ -0,0 +1,57 @@
+use new_law::citation::{CitationFinder, CitationKind};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+/// 從全文抽出的引用，欄位名稱與 OldInterpretation 的 reflawid、refinter 相同
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct References {
+    pub reflawid: Vec<String>,     // 法條 id，例如「民法-184」
+    pub refinter: Vec<String>,     // 釋字號碼
+    pub refprecedent: Vec<String>, // 判例 id，例如「49-台上-929」
+}
+
+impl References {
+    /// 重複的引用只留第一次出現的
+    pub fn extract(text: &str, finder: &CitationFinder) -> References {
+        let text = TAG_RE.replace_all(text, "");
+        let mut references = References::default();
+        for citation in finder.find(&text) {
+            let list = match citation.kind {
+                CitationKind::Law => &mut references.reflawid,
+                CitationKind::OldInterpretation => &mut references.refinter,
+                CitationKind::Precedent => &mut references.refprecedent,
+            };
+            if !list.contains(&citation.id) {
+                list.push(citation.id);
+            }
+        }
+        references
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use new_law::alias::LawAliases;
+
+    #[test]
+    fn extracts_from_html() {
+        let aliases = LawAliases::builtin();
+        let finder = CitationFinder::new(["民法"], &aliases);
+        let references = References::extract(
+            "<p>民法第184條</p><p>同法第184條、釋字第748號、49年台上字第929號判例</p>",
+            &finder,
+        );
+        assert_eq!(
+            references,
+            References {
+                reflawid: vec!["民法-184".to_string()],
+                refinter: vec!["748".to_string()],
+                refprecedent: vec!["49-台上-929".to_string()],
+            }
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use std::collections::HashSet;
+
+/// 每個來源已完成的項目，中斷後重跑會略過這些項目
+#[derive(Clone, Debug, Default)]
+pub struct Checkpoint {
+    source: String,
+    done: HashSet<String>,
+}
+
+/// 套用 migrations/ 中的資料表結構，與 law_web --migrate-only 相同
+pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::migrate::MigrateError> {
+    sqlx::migrate!("../migrations").run(pool).await
+}
+
+impl Checkpoint {
+    pub fn new(source: &str) -> Self {
+        Checkpoint {
+            source: source.to_string(),
+            done: HashSet::new(),
+        }
+    }
+
+    // 資料表不存在時（例如第一次 dry-run）視為沒有進度
+    pub async fn load(pool: &PgPool, source: &str) -> Self {
+        let mut checkpoint = Checkpoint::new(source);
+        match sqlx::query("SELECT key FROM sync_checkpoint WHERE source = $1")
+            .bind(source)
+            .map(|row: PgRow| row.get::<String, _>("key"))
+            .fetch_all(pool)
+            .await
+        {
+            Ok(keys) => checkpoint.done.extend(keys),
+            Err(e) => eprintln!("讀取 {} 的進度失敗: {}", source, e),
+        }
+        checkpoint
+    }
+
+    pub async fn reset(pool: &PgPool, source: &str) {
+        match sqlx::query("DELETE FROM sync_checkpoint WHERE source = $1")
+            .bind(source)
+            .execute(pool)
+            .await
+        {
+            Ok(result) => println!("清除 {} 的進度 {} 筆", source, result.rows_affected()),
+            Err(e) => eprintln!("清除 {} 的進度失敗: {}", source, e),
+        }
+    }
+
+    pub fn is_done(&self, key: &str) -> bool {
+        self.done.contains(key)
+    }
+
+    pub async fn mark_done(&mut self, pool: &PgPool, key: &str) {
+        match sqlx::query(
+            "INSERT INTO sync_checkpoint (source, key) VALUES ($1, $2)
+            ON CONFLICT (source, key) DO UPDATE SET done_at = now()",
+        )
+        .bind(&self.source)
+        .bind(key)
+        .execute(pool)
+        .await
+        {
+            Ok(_) => {
+                self.done.insert(key.to_string());
+            }
+            Err(e) => eprintln!("記錄 {}:{} 的進度失敗: {}", self.source, key, e),
+        }
+    }
+}
+
+/// --since 與 --only 的篩選條件
+#[derive(Clone, Debug, Default)]
+pub struct SyncFilter {
+    pub since: Option<u32>, // 編號不小於 since，例如釋字號、判例頁數
+    pub only: Vec<String>,  // 只處理這些項目，「民法」也包含「民法-184」
+}
+
+impl SyncFilter {
+    /// number 為項目的編號，沒有編號的項目不受 since 限制
+    pub fn allows(&self, key: &str, number: Option<u32>) -> bool {
+        if let (Some(since), Some(number)) = (self.since, number)
+            && number < since
+        {
+            return false;
+        }
+        self.only.is_empty()
+            || self
+                .only
+                .iter()
+                .any(|only| key == only || key.starts_with(&format!("{only}-")))
+    }
+}
+
+/// 一次同步要處理的項目，dry-run 時只印出不執行
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SyncPlan {
+    pub source: String,
+    pub pending: Vec<String>,
+    pub existing: usize, // 資料庫已有
+    pub resumed: usize,  // 上次中斷前已完成
+    pub filtered: usize, // 不符合 --since、--only
+    pub refresh: bool,   // 沒有既有資料可比對，每一項都會重新抓取，寫入時才知道是否變動
+}
+
+impl SyncPlan {
+    /// candidates 為 (key, 編號)，existing 為資料庫中已有的 key；
+    /// 每次都重新比對內容的來源（例如法規）傳 None
+    pub fn build(
+        candidates: impl IntoIterator<Item = (String, Option<u32>)>,
+        existing: Option<&HashSet<String>>,
+        checkpoint: &Checkpoint,
+        filter: &SyncFilter,
+    ) -> Self {
+        let mut plan = SyncPlan {
+            source: checkpoint.source.clone(),
+            refresh: existing.is_none(),
+            ..Default::default()
+        };
+        for (key, number) in candidates {
+            if !filter.allows(&key, number) {
+                plan.filtered += 1;
+            } else if existing.is_some_and(|existing| existing.contains(&key)) {
+                plan.existing += 1;
+            } else if checkpoint.is_done(&key) {
+                plan.resumed += 1;
+            } else if !plan.pending.contains(&key) {
+                plan.pending.push(key);
+            }
+        }
+        plan
+    }
+
+    pub fn report(&self, dry_run: bool) {
+        if self.refresh {
+            println!(
+                "[{}] {}{} 項，上次已完成 {} 項，篩選略過 {} 項",
+                self.source,
+                if dry_run { "將比對" } else { "待比對" },
+                self.pending.len(),
+                self.resumed,
+                self.filtered
+            );
+        } else {
+            println!(
+                "[{}] {}{} 項，已存在 {} 項，上次已完成 {} 項，篩選略過 {} 項",
+                self.source,
+                if dry_run { "將新增" } else { "待處理" },
+                self.pending.len(),
+                self.existing,
+                self.resumed,
+                self.filtered
+            );
+        }
+        if dry_run {
+            let mark = if self.refresh { "~" } else { "+" };
+            for key in &self.pending {
+                println!("  {mark} {key}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(keys: &[(&str, Option<u32>)]) -> Vec<(String, Option<u32>)> {
+        keys.iter()
+            .map(|(key, number)| (key.to_string(), *number))
+            .collect()
+    }
+
+    #[test]
+    fn builds_plan() {
+        let mut checkpoint = Checkpoint::new("oldinter");
+        checkpoint.done.insert("802".to_string());
+        let existing = HashSet::from(["801".to_string()]);
+        let filter = SyncFilter {
+            since: Some(801),
+            only: Vec::new(),
+        };
+        let plan = SyncPlan::build(
+            candidates(&[
+                ("800", Some(800)),
+                ("801", Some(801)),
+                ("802", Some(802)),
+                ("803", Some(803)),
+                ("803", Some(803)),
+            ]),
+            Some(&existing),
+            &checkpoint,
+            &filter,
+        );
+        assert_eq!(
+            plan,
+            SyncPlan {
+                source: "oldinter".to_string(),
+                pending: vec!["803".to_string()],
+                existing: 1,
+                resumed: 1,
+                filtered: 1,
+                refresh: false,
+            }
+        );
+
+        let plan = SyncPlan::build(
+            candidates(&[("B0000001", None), ("B0000010", None)]),
+            None,
+            &Checkpoint::new("laws"),
+            &SyncFilter::default(),
+        );
+        assert!(plan.refresh);
+        assert_eq!(plan.pending.len(), 2);
+    }
+
+    #[test]
+    fn filters_by_only() {
+        let filter = SyncFilter {
+            since: Some(100),
+            only: vec!["民法".to_string()],
+        };
+        assert!(filter.allows("民法-184", None));
+        assert!(filter.allows("民法", None));
+        assert!(!filter.allows("民法施行法-1", None));
+        assert!(!filter.allows("民法-1", Some(1)));
+    }
+}
@@ -0,0 +1,246 @@
+use crate::HistoryLaw;
+use chrono::{Datelike, Days, NaiveDate};
+use new_law::article::compare_nums;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::LazyLock;
+
+static ROC_DATE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d{2,3})\s*(?:年|\.|/|-)\s*(\d{1,2})\s*(?:月|\.|/|-)\s*(\d{1,2})\s*日?").unwrap()
+});
+
+/// 解析「中華民國108年5月29日」、「108.05.29」這類民國日期
+pub fn parse_roc_date(text: &str) -> Option<NaiveDate> {
+    let caps = ROC_DATE_RE.captures(text)?;
+    let year: i32 = caps[1].parse().ok()?;
+    let month: u32 = caps[2].parse().ok()?;
+    let day: u32 = caps[3].parse().ok()?;
+    NaiveDate::from_ymd_opt(year + 1911, month, day)
+}
+
+/// 同時接受西元「2019-05-01」與民國「108-05-01」
+pub fn parse_query_date(text: &str) -> Option<NaiveDate> {
+    match NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d") {
+        Ok(date) if date.year_ce().1 > 1911 => Some(date),
+        _ => parse_roc_date(text),
+    }
+}
+
+/// 條文的一個版本，effective_to 為下一版本生效日（不含），現行版本為 None
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LawVersion {
+    pub id: String,
+    pub no: i16,
+    pub date: String,
+    pub promulgated: NaiveDate,
+    pub effective_from: NaiveDate,
+    pub effective_to: Option<NaiveDate>,
+    pub content: String,
+}
+
+/// 依公布日期排序 history_law，建立條文的修正時間軸
+/// 生效日依中央法規標準法第13條，自公布之日起算至第三日
+/// 無法解析日期的紀錄不列入
+pub fn build_timeline(history: Vec<HistoryLaw>) -> Vec<LawVersion> {
+    let mut versions: Vec<LawVersion> = history
+        .into_iter()
+        .filter_map(|h| {
+            let promulgated = parse_roc_date(&h.date)?;
+            Some(LawVersion {
+                id: h.id,
+                no: h.no,
+                date: h.date,
+                promulgated,
+                effective_from: promulgated.checked_add_days(Days::new(2))?,
+                effective_to: None,
+                content: h.content,
+            })
+        })
+        .collect();
+    versions.sort_by_key(|v| (v.promulgated, v.no));
+
+    for i in 1..versions.len() {
+        let next_from = versions[i].effective_from;
+        versions[i - 1].effective_to = Some(next_from);
+    }
+    versions
+}
+
+/// 找出指定日期當天有效的版本，早於第一個版本時回傳 None
+pub fn version_as_of(timeline: &[LawVersion], date: NaiveDate) -> Option<&LawVersion> {
+    timeline.iter().rev().find(|v| v.effective_from <= date)
+}
+
+// 法務部的沿革把已刪除的條文記為「（刪除）」
+fn is_deleted(content: &str) -> bool {
+    let trimmed = content
+        .trim()
+        .trim_matches(|c| c == '（' || c == '）' || c == '(' || c == ')');
+    trimmed == "刪除"
+}
+
+// 「民法-877-1」排在「民法-877」之後、「民法-878」之前
+fn compare_lawids(a: &str, b: &str) -> Ordering {
+    let num = |id: &str| {
+        id.split_once('-')
+            .map(|(_, num)| num.to_string())
+            .unwrap_or_default()
+    };
+    compare_nums(&num(a), &num(b))
+}
+
+/// 某次修正公布時新增、修改、刪除的條文
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ChangelogEntry {
+    pub date: NaiveDate,
+    pub added: Vec<String>,
+    pub changed: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+/// 整部法規的修正紀錄
+/// removed 為現行條文沒有、沿革中卻沒有「（刪除）」紀錄的條文，無從得知刪除日期，因此不歸入任何一次修正
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Changelog {
+    pub entries: Vec<ChangelogEntry>,
+    pub removed: Vec<String>,
+}
+
+/// 由整部法規的 history_law 建立修正紀錄
+/// 只有沿革中記為「（刪除）」的版本才算刪除，current_ids 為 newlaw 中現行條文的 id
+pub fn build_changelog(history: Vec<HistoryLaw>, current_ids: &HashSet<String>) -> Changelog {
+    let mut by_article: BTreeMap<String, Vec<HistoryLaw>> = BTreeMap::new();
+    for h in history {
+        by_article.entry(h.lawid.clone()).or_default().push(h);
+    }
+
+    let mut by_date: BTreeMap<NaiveDate, ChangelogEntry> = BTreeMap::new();
+    let mut removed = Vec::new();
+    for (lawid, rows) in by_article {
+        let timeline = build_timeline(rows);
+        for (i, version) in timeline.iter().enumerate() {
+            let entry = by_date
+                .entry(version.promulgated)
+                .or_insert_with(|| ChangelogEntry {
+                    date: version.promulgated,
+                    added: Vec::new(),
+                    changed: Vec::new(),
+                    deleted: Vec::new(),
+                });
+            if is_deleted(&version.content) {
+                entry.deleted.push(lawid.clone());
+            } else if i == 0 {
+                entry.added.push(lawid.clone());
+            } else {
+                entry.changed.push(lawid.clone());
+            }
+        }
+        let deleted = timeline.last().is_some_and(|v| is_deleted(&v.content));
+        if !timeline.is_empty() && !deleted && !current_ids.contains(&lawid) {
+            removed.push(lawid);
+        }
+    }
+    removed.sort_by(|a, b| compare_lawids(a, b));
+
+    let entries = by_date
+        .into_values()
+        .map(|mut entry| {
+            entry.added.sort_by(|a, b| compare_lawids(a, b));
+            entry.changed.sort_by(|a, b| compare_lawids(a, b));
+            entry.deleted.sort_by(|a, b| compare_lawids(a, b));
+            entry
+        })
+        .collect();
+    Changelog { entries, removed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(no: i16, date: &str, content: &str) -> HistoryLaw {
+        HistoryLaw {
+            id: format!("中華民國刑法-2-{no}"),
+            lawid: "中華民國刑法-2".to_string(),
+            date: date.to_string(),
+            content: content.to_string(),
+            no,
+            provenance: Default::default(),
+        }
+    }
+
+    #[test]
+    fn finds_version_in_force() {
+        let timeline = build_timeline(vec![
+            history(2, "中華民國94年2月2日", "second"),
+            history(1, "中華民國24年1月1日", "first"),
+            history(3, "無日期", "ignored"),
+        ]);
+        assert_eq!(timeline.len(), 2);
+        assert_eq!(timeline[0].content, "first");
+        assert_eq!(
+            timeline[0].effective_to,
+            NaiveDate::from_ymd_opt(2005, 2, 4)
+        );
+
+        let on = |s: &str| version_as_of(&timeline, parse_query_date(s).unwrap());
+        assert_eq!(on("2005-02-03").unwrap().content, "first");
+        assert_eq!(on("2019-05-01").unwrap().content, "second");
+        assert_eq!(on("108-05-01").unwrap().content, "second");
+        assert!(on("1930-01-01").is_none());
+    }
+
+    #[test]
+    fn groups_changes_by_date() {
+        let row = |lawid: &str, no: i16, date: &str, content: &str| HistoryLaw {
+            id: format!("{lawid}-{no}"),
+            lawid: lawid.to_string(),
+            date: date.to_string(),
+            content: content.to_string(),
+            no,
+            provenance: Default::default(),
+        };
+        let history = vec![
+            row("民法-1", 1, "中華民國18年5月23日", "a"),
+            row("民法-2", 1, "中華民國18年5月23日", "b"),
+            row("民法-2", 2, "中華民國98年12月30日", "（刪除）"),
+            row("民法-1", 2, "中華民國98年12月30日", "a2"),
+            row("民法-1-1", 1, "中華民國98年12月30日", "c"),
+        ];
+        let current: HashSet<String> = ["民法-1", "民法-1-1"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let log = build_changelog(history, &current).entries;
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].added, vec!["民法-1", "民法-2"]);
+        assert_eq!(log[1].changed, vec!["民法-1"]);
+        assert_eq!(log[1].added, vec!["民法-1-1"]);
+        assert_eq!(log[1].deleted, vec!["民法-2"]);
+    }
+
+    #[test]
+    fn missing_articles_without_deletion_row_are_not_dated() {
+        let row = |lawid: &str, no: i16, date: &str| HistoryLaw {
+            id: format!("{lawid}-{no}"),
+            lawid: lawid.to_string(),
+            date: date.to_string(),
+            content: "a".to_string(),
+            no,
+            provenance: Default::default(),
+        };
+        let history = vec![
+            row("民法-1", 1, "中華民國18年5月23日"),
+            row("民法-3", 1, "中華民國18年5月23日"),
+            row("民法-3", 2, "中華民國98年12月30日"),
+        ];
+        let current: HashSet<String> = ["民法-1".to_string()].into_iter().collect();
+        let log = build_changelog(history, &current);
+        assert_eq!(log.entries[0].added, vec!["民法-1", "民法-3"]);
+        assert_eq!(log.entries[1].changed, vec!["民法-3"]);
+        assert!(log.entries.iter().all(|entry| entry.deleted.is_empty()));
+        assert_eq!(log.removed, vec!["民法-3"]);
+    }
+}
@@ -0,0 +1,164 @@
+// 以 tests/fixtures 中存下的網頁測試各爬蟲的解析，不需要網路與資料庫
+use otherlawresource::fetch::{FixtureFetcher, HttpFetcher};
+use std::path::PathBuf;
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+fn read(file_name: &str) -> String {
+    std::fs::read_to_string(fixtures().join(file_name)).unwrap()
+}
+
+#[tokio::test]
+async fn parses_new_interpretation() {
+    let href = "https://cons.judicial.gov.tw/docdata.aspx?fid=38&id=309956";
+    let fetcher = FixtureFetcher::new(fixtures()).with(href, "new_interpretation.html");
+    let inter = otherlawresource::scrape_new_interpretation_with(&fetcher, 1, href.to_string())
+        .await
+        .unwrap();
+    assert_eq!((inter.year, inter.number), (111, 1));
+    assert_eq!(inter.no, "111年憲判字第1號");
+    assert_eq!(inter.name, "公務人員年資併社團專職人員年資計退案");
+    assert!(inter.content.unwrap().starts_with("憲法法庭判決"));
+    assert_eq!(inter.source, href);
+}
+
+#[tokio::test]
+async fn parses_old_interpretation() {
+    let href = "https://mojlaw.moj.gov.tw/LawContentExShow.aspx?id=D%2C748&type=c&kw=";
+    let fetcher = FixtureFetcher::new(fixtures()).with(href, "old_interpretation.html");
+    let inter = otherlawresource::scrape_old_interpretation_with(
+        &fetcher,
+        "748".to_string(),
+        href.to_string(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(inter.id, "748");
+    assert_eq!(inter.date, "民國 106 年 05 月 24 日");
+    assert_eq!(inter.provenance.source_url, href);
+    assert_eq!(
+        inter.provenance.upstream_date.as_deref(),
+        Some("民國 106 年 05 月 24 日")
+    );
+    assert!(inter.provenance.fetched_at.is_some());
+    assert!(inter.reasoning.unwrap().contains("婚姻自由"));
+    assert!(inter.content.unwrap().contains("釋字第554號"));
+}
+
+#[tokio::test]
+async fn parses_precedent_list() {
+    let href = "https://mojlaw.moj.gov.tw/LawResultList.aspx?page=1";
+    let fetcher = FixtureFetcher::new(fixtures()).with(href, "precedent_list.html");
+    let precedents = otherlawresource::scrape_precedent_with(&fetcher, href)
+        .await
+        .unwrap();
+    assert_eq!(precedents.len(), 2);
+    let first = &precedents[0];
+    assert_eq!(
+        (first.year, first.specific.as_str(), first.num),
+        (49, "台上", 929)
+    );
+    assert_eq!(first.case_type.as_deref(), Some("民事"));
+    assert_eq!(
+        first.source,
+        "https://mojlaw.moj.gov.tw/LawContent.aspx?id=GL000001"
+    );
+    assert!(first.holding.contains("民法第184條"));
+    assert_eq!(precedents[1].case_type.as_deref(), Some("刑事"));
+}
+
+#[tokio::test]
+async fn parses_resolution() {
+    let href = "https://resolution.example/ShowResolution.aspx?id=77-9";
+    let fetcher = FixtureFetcher::new(fixtures()).with(href, "resolution.html");
+    let resolution = otherlawresource::scrape_resolution_with(&fetcher, href)
+        .await
+        .unwrap();
+    assert_eq!(resolution.name, "最高法院77年度第9次民事庭會議決議");
+    assert_eq!(resolution.source, href);
+    assert_eq!(resolution.provenance.source_url, href);
+    assert!(resolution.provenance.fetched_at.is_some());
+    assert!(resolution.content.contains("決議：採甲說。"));
+    assert_eq!(
+        otherlawresource::extract_year_and_time(&resolution.name),
+        Some((77, 9))
+    );
+}
+
+#[tokio::test]
+async fn parses_new_law_with_chapters() {
+    let fetcher = FixtureFetcher::new(fixtures()).with(
+        "https://law.moj.gov.tw/LawClass/LawAllChapter.aspx?pcode=B0000001",
+        "chapter.html",
+    );
+    let laws =
+        otherlawresource::scrape_new_law_with(&fetcher, "民法".to_string(), read("new_law.html"))
+            .await
+            .unwrap();
+    let ids: Vec<&str> = laws.lines.iter().map(|law| law.id.as_str()).collect();
+    assert_eq!(ids, vec!["民法-1", "民法-2", "民法-6"]);
+    assert_eq!(
+        laws.lines[0].provenance.source_url,
+        "https://law.moj.gov.tw/LawClass/LawSingle.aspx?pcode=B0000001&flno=1"
+    );
+    assert_eq!(
+        laws.lines[1].chapter,
+        vec!["民法", "第一編 總則", "第一章 法例"]
+    );
+    assert_eq!(
+        laws.lines[2].chapter,
+        vec!["民法", "第一編 總則", "第二章 人"]
+    );
+    let lines = &laws.lines[2].lines;
+    assert_eq!(
+        (lines[0].line_type.as_str(), lines[1].line_type.as_str()),
+        ("normal", "indent")
+    );
+}
+
+#[test]
+fn resolves_resolution_index_links() {
+    let html = r#"<div class="list"><a title="最高法院77年度第9次民事庭會議決議" href="ShowResolution.aspx?id=77-9">77-9</a></div>"#;
+    let entries =
+        otherlawresource::parse_resolution_index("https://resolution.example/list.aspx", html);
+    assert_eq!(
+        entries,
+        vec![(
+            "最高法院77年度第9次民事庭會議決議".to_string(),
+            "https://resolution.example/ShowResolution.aspx?id=77-9".to_string()
+        )]
+    );
+}
+
+#[tokio::test]
+async fn parses_history_law() {
+    let article = "https://law.moj.gov.tw/LawClass/LawSingle.aspx?pcode=B0000001&flno=1";
+    let href = otherlawresource::history_href(article);
+    assert_eq!(
+        href,
+        "https://law.moj.gov.tw/LawClass/LawSingleHistory.aspx?pcode=B0000001&flno=1"
+    );
+    let fetcher = FixtureFetcher::new(fixtures()).with(&href, "history_law.html");
+    let history = otherlawresource::scrape_historylaw_with(
+        &fetcher,
+        &href,
+        "民法".to_string(),
+        "1".to_string(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[1].id, "民法-1-2");
+    assert_eq!(history[1].date, "民國 110 年 01 月 13 日");
+    assert_eq!(history[1].provenance.source_url, href);
+    assert!(history[1].provenance.fetched_at.is_some());
+    assert!(history[0].content.ends_with("依習慣。"));
+}
+
+#[tokio::test]
+async fn missing_fixture_is_an_error() {
+    let fetcher = FixtureFetcher::new(fixtures());
+    assert!(fetcher.fetch("https://law.moj.gov.tw/").await.is_err());
+}
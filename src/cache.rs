@@ -0,0 +1,108 @@
+use crate::storage::Store;
+use indexmap::IndexMap;
+use new_law::alias::LawAliases;
+use new_law::citation::CitationFinder;
+use new_law::toc::LawToc;
+use new_law::NewLaws;
+use otherlawresource::graph::CitationGraph;
+use otherlawresource::{NewInter, OldInterpretation, Precedent, Resolution};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::info;
+
+/// 一次從資料庫載入的法規與其他資源，載入後不再修改
+pub struct Snapshot {
+    pub new_laws: Arc<IndexMap<String, NewLaws>>,
+    pub tocs: Arc<HashMap<String, LawToc>>, // 每部法規的章節樹
+    pub new_inters: Arc<Vec<NewInter>>,
+    pub old_inters: Arc<Vec<OldInterpretation>>,
+    pub resolutions: Arc<Vec<Resolution>>,
+    pub precedents: Arc<Vec<Precedent>>,
+    pub graph: Arc<CitationGraph>, // 釋字、憲判、判例、決議與法條的引用關係
+}
+
+impl Snapshot {
+    pub async fn load(store: &Store) -> Result<Snapshot, handle_errors::Error> {
+        let mut new_inters = store.clone().get_newinterpretations().await?;
+        new_inters.sort_by(|a, b| (a.year, a.number).cmp(&(b.year, b.number)));
+
+        let mut old_inters = store.get_all_oldinterpretation().await?;
+        old_inters.sort_by(|a, b| {
+            let a_num = a.id.parse().unwrap_or(0);
+            let b_num = b.id.parse().unwrap_or(0);
+            a_num.cmp(&b_num)
+        });
+        old_inters.reverse();
+
+        let mut resolutions = store.clone().get_all_resolution().await?;
+        resolutions.sort_by(|a, b| (a.year, a.time).cmp(&(b.year, b.time)));
+
+        let mut precedents = store.get_all_precedents().await?;
+        precedents.sort_by(|a, b| (a.year, a.num).cmp(&(b.year, b.num)));
+        precedents.reverse();
+
+        let new_law = store.get_new_laws().await?;
+
+        let new_laws = new_law.categories(0);
+        let tocs = LawToc::build_all(&new_laws);
+
+        let aliases = LawAliases::from_list(
+            store.get_law_aliases().await.unwrap_or_else(|e| {
+                tracing::warn!("讀取法規簡稱失敗，僅使用內建簡稱：{:?}", e);
+                Vec::new()
+            }),
+        );
+        let finder = CitationFinder::new(new_laws.keys().map(|name| name.as_str()), &aliases);
+        let graph = CitationGraph::build(
+            &old_inters,
+            &new_inters,
+            &precedents,
+            &resolutions,
+            &finder,
+        );
+
+        Ok(Snapshot {
+            new_laws: Arc::new(new_laws),
+            tocs: Arc::new(tocs),
+            new_inters: Arc::new(new_inters),
+            old_inters: Arc::new(old_inters),
+            resolutions: Arc::new(resolutions),
+            precedents: Arc::new(precedents),
+            graph: Arc::new(graph),
+        })
+    }
+}
+
+/// 可整批替換的快取，每個請求拿到的都是替換前或替換後的完整 Snapshot
+#[derive(Clone)]
+pub struct Cache {
+    current: Arc<RwLock<Arc<Snapshot>>>,
+    store: Store,
+}
+
+impl Cache {
+    pub async fn new(store: Store) -> Result<Cache, handle_errors::Error> {
+        let snapshot = Snapshot::load(&store).await?;
+        Ok(Cache {
+            current: Arc::new(RwLock::new(Arc::new(snapshot))),
+            store,
+        })
+    }
+
+    pub fn snapshot(&self) -> Arc<Snapshot> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// 先完整載入新的資料再替換，載入失敗時保留舊資料
+    pub async fn reload(&self) -> Result<(), handle_errors::Error> {
+        let snapshot = Snapshot::load(&self.store).await?;
+        info!(
+            "重新載入快取：{}部法規、{}則判例、引用圖{}個節點",
+            snapshot.new_laws.len(),
+            snapshot.precedents.len(),
+            snapshot.graph.len()
+        );
+        *self.current.write().unwrap() = Arc::new(snapshot);
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+// 列表頁面用的 HTML 片段，所有使用者輸入都先經過 escape
+use crate::types::directory::Directory;
+use crate::types::file::File;
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn dir_list(dirs: &[Directory]) -> String {
+    dirs.iter()
+        .map(|k| format!("<li class='the-dir'><a>{}<a></li>", escape(&k.directory)))
+        .collect()
+}
+
+pub fn pub_dir_list(dirs: &[Directory]) -> String {
+    dirs.iter()
+        .map(|k| {
+            format!(
+                "
+                <div class='public-dir' id='pub-{}'>
+                    <div>write by：<span>{}</span></div>
+                    <h2>{}</h2>
+                    <div class='summary'>summary：<span>{}</span></div>
+                </div>",
+                escape(&k.slug), // 前端沿用舊版的 user_name-directory
+                escape(&k.user_name),
+                escape(&k.directory),
+                escape(&k.description)
+            )
+        })
+        .collect()
+}
+
+pub fn dir_options(dirs: &[Directory]) -> String {
+    dirs.iter()
+        .map(|k| {
+            let directory = escape(&k.directory);
+            format!(
+                "<div class='option'><input type='checkbox' id='option-{}'>
+                            <label for='option-{}'>{}</label></div>",
+                directory, directory, directory
+            )
+        })
+        .collect()
+}
+
+pub fn file_list(files: &[File]) -> String {
+    files
+        .iter()
+        .map(|file| {
+            format!(
+                "<li class='the-file'><a>{}<a></li>",
+                escape(&file.file_name)
+            )
+        })
+        .collect()
+}
+
+pub fn chapter_options<'a>(names: impl Iterator<Item = &'a String>) -> String {
+    names
+        .map(|name| format!("<option value='{}'>", escape(name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_replaces_html_special_characters() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+        assert_eq!(escape("民法第184條"), "民法第184條");
+    }
+
+    #[test]
+    fn chapter_options_escape_names() {
+        let names = vec!["民法'><script>".to_string()];
+        assert_eq!(
+            chapter_options(names.iter()),
+            "<option value='民法&#39;&gt;&lt;script&gt;'>"
+        );
+    }
+}
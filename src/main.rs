@@ -1,16 +1,21 @@
 #![recursion_limit = "512"]
+mod cache;
+mod html;
 pub mod routes;
+mod storage;
 mod store;
 use redis::aio::ConnectionManager;
 use redis::{AsyncCommands, Client, RedisError, RedisResult};
 pub mod types;
 use crate::routes::note::get_gzip_json;
+use crate::types::pagination::ListQuery;
 use config::Config;
 #[allow(unused_imports)]
 use handle_errors::return_error;
 use note::Block;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tokio::time::{interval, Duration};
 use tracing_subscriber::fmt::format::FmtSpan;
 use warp::{http::Method, Filter};
@@ -19,6 +24,16 @@ use warp::{http::Method, Filter};
 pub struct Args {
     log_level: String,
     port: u16,
+    #[serde(default)]
+    reload_interval: Option<u64>, // 秒，未設定則不定期重新載入
+    #[serde(default)]
+    storage: Option<String>, // "postgres"（預設）或 "sqlite"
+    #[serde(default)]
+    sqlite_path: Option<String>,
+    #[serde(default)]
+    sqlite_seed: Option<String>, // otherlawresource export 的輸出目錄，空資料庫時匯入
+    #[serde(default)]
+    migrate: bool, // 啟動時執行 migrations/
 }
 
 #[macro_export]
@@ -78,66 +93,155 @@ async fn main() -> Result<(), handle_errors::Error> {
     // 初始化
     dotenv::dotenv().ok();
 
-    if let Err(_) = std::env::var("DATABASE_PUBLIC_URL") {
-        panic!("找不到資料庫");
-    }
+    // --migrate-only：只更新資料表結構後結束，不需要 Redis
+    let migrate_only = std::env::args().any(|arg| arg == "--migrate-only");
+
+    let sqlite_mode = config.storage.as_deref() == Some("sqlite");
+    let store: storage::Store = match config.storage.as_deref() {
+        Some("sqlite") => {
+            let path = config.sqlite_path.as_deref().unwrap_or("law_web.db");
+            let sqlite = storage::sqlite::SqliteStore::new(path).await;
+            if let Some(dir) = &config.sqlite_seed {
+                match sqlite.seed_from_export(std::path::Path::new(dir)).await {
+                    Ok(count) => println!("從 {} 匯入 {} 筆", dir, count),
+                    Err(e) => panic!("匯入 {} 失敗：{}", dir, e),
+                }
+            }
+            Arc::new(sqlite)
+        }
+        _ => {
+            if let Err(_) = std::env::var("DATABASE_PUBLIC_URL") {
+                panic!("找不到資料庫");
+            }
+            let db_url = std::env::var("DATABASE_PUBLIC_URL").unwrap();
+            println!("{}", db_url);
+            let pg = store::PgStore::new(&db_url).await;
+            if config.migrate || migrate_only {
+                if let Err(e) = pg.run_migrations().await {
+                    panic!("資料表 migration 失敗：{e}");
+                }
+                println!("資料表 migration 完成");
+            }
+            Arc::new(pg)
+        }
+    };
 
-    if let Err(_) = std::env::var("REDIS_PUBLIC_URL") {
-        panic!("找不到Redis");
+    // SQLite 在開啟時已建立資料表
+    if migrate_only {
+        return Ok(());
     }
 
-    if let Err(_) = std::env::var("PASETO_KEY") {
+    // SQLite 模式可以不用 Redis，筆記直接寫入資料庫
+    if std::env::var("REDIS_PUBLIC_URL").is_err() && !sqlite_mode {
         panic!("找不到Redis");
     }
 
-    let db_url = std::env::var("DATABASE_PUBLIC_URL").unwrap();
-    println!("{}", db_url);
-    let store = store::Store::new(&db_url).await;
+    if std::env::var("PASETO_KEY").is_err() {
+        if !sqlite_mode {
+            panic!("找不到PASETO_KEY");
+        }
+        // 本機使用時每次啟動產生新的金鑰，重新啟動後需要重新登入
+        tracing::warn!("未設定 PASETO_KEY，使用隨機金鑰");
+        let key: String = rand::thread_rng()
+            .sample_iter(&rand::distributions::Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+        std::env::set_var("PASETO_KEY", key);
+    }
 
     // 建立redis資料庫聯繫
-    let redis_url = std::env::var("REDIS_PUBLIC_URL").unwrap_or("redis://127.0.0.1/".to_string());
-    println!("{}", redis_url);
-    let client = Client::open(redis_url).unwrap();
-    let manager = ConnectionManager::new(client).await.unwrap();
-
-    let mut new_inters = store.clone().get_newinterpretations().await?;
-    new_inters.sort_by(|a, b| (a.year, a.number).cmp(&(b.year, b.number)));
-    let new_inter_shared = Arc::new(new_inters);
-    let new_inters_filter = warp::any().map(move || new_inter_shared.clone());
-
-    let mut old_inters = store.clone().get_all_oldinterpretation().await?;
-    old_inters.sort_by(|a, b| {
-        let a_num = a.id.parse().unwrap_or(0);
-        let b_num = b.id.parse().unwrap_or(0);
-        a_num.cmp(&b_num)
+    let manager: Option<ConnectionManager> = match std::env::var("REDIS_PUBLIC_URL") {
+        Ok(redis_url) => {
+            println!("{}", redis_url);
+            let client = Client::open(redis_url).unwrap();
+            Some(ConnectionManager::new(client).await.unwrap())
+        }
+        Err(_) => None,
+    };
+
+    // 法規與其他資源的快取，POST /admin/reload 或定期重新載入
+    let cache = cache::Cache::new(store.clone()).await?;
+
+    // 每個請求只取一次 Snapshot，需要多個欄位的路由從同一個 Snapshot 取出
+    let snapshot_cache = cache.clone();
+    let snapshot_filter = warp::any().map(move || snapshot_cache.snapshot());
+    let new_inters_filter = snapshot_filter
+        .clone()
+        .map(|s: Arc<cache::Snapshot>| s.new_inters.clone());
+    let old_inters_filter = snapshot_filter
+        .clone()
+        .map(|s: Arc<cache::Snapshot>| s.old_inters.clone());
+    let resolution_filter = snapshot_filter
+        .clone()
+        .map(|s: Arc<cache::Snapshot>| s.resolutions.clone());
+    let pecedent_filter = snapshot_filter
+        .clone()
+        .map(|s: Arc<cache::Snapshot>| s.precedents.clone());
+    let new_law_filter = snapshot_filter
+        .clone()
+        .map(|s: Arc<cache::Snapshot>| s.new_laws.clone());
+    let toc_filter = snapshot_filter
+        .clone()
+        .map(|s: Arc<cache::Snapshot>| s.tocs.clone());
+    let graph_filter = snapshot_filter
+        .clone()
+        .map(|s: Arc<cache::Snapshot>| s.graph.clone());
+    // 條文與目錄須來自同一個 Snapshot，目錄中的位置才會對得上
+    let law_toc_filter = snapshot_filter
+        .clone()
+        .map(|s: Arc<cache::Snapshot>| (s.new_laws.clone(), s.tocs.clone()))
+        .untuple_one();
+
+    if let Some(seconds) = config.reload_interval.filter(|s| *s > 0) {
+        let reload_cache = cache.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(seconds));
+            // 第一次 tick 會立即觸發，啟動時已載入過
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = reload_cache.reload().await {
+                    tracing::error!("重新載入快取失敗：{:?}", e);
+                }
+            }
+        });
+    }
+
+    // 法規簡稱，管理員修改後即時生效
+    let law_aliases = new_law::alias::LawAliases::from_list(
+        store.get_law_aliases().await.unwrap_or_else(|e| {
+            // 尚未建立 law_alias 資料表時仍可啟動，只是沒有自訂簡稱
+            tracing::warn!("讀取法規簡稱失敗，僅使用內建簡稱：{:?}", e);
+            Vec::new()
+        }),
+    );
+    let law_aliases_shared = Arc::new(RwLock::new(law_aliases));
+    let alias_filter = warp::any().map(move || law_aliases_shared.clone());
+
+    // 每六小時比對一次筆記中的法條卡片
+    let stale_store = store.clone();
+    let stale_cache = cache.clone();
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(6 * 60 * 60));
+        loop {
+            ticker.tick().await;
+            let laws = stale_cache.snapshot().new_laws.clone();
+            if let Err(e) = routes::note::check_stale_cards(stale_store.clone(), laws).await {
+                tracing::error!("過時法條卡片檢查失敗：{:?}", e);
+            }
+        }
     });
-    old_inters.reverse();
-    let old_inter_shared = Arc::new(old_inters);
-    let old_inters_filter = warp::any().map(move || old_inter_shared.clone());
-
-    let mut resolutions = store.clone().get_all_resolution().await?;
-    resolutions.sort_by(|a, b| (a.year, a.time).cmp(&(b.year, b.time)));
-    let resolution_shared = Arc::new(resolutions);
-    let resolution_filter = warp::any().map(move || resolution_shared.clone());
-
-    let mut precedents = store.clone().get_all_precedents().await?;
-    precedents.sort_by(|a, b| (a.year, a.num).cmp(&(b.year, b.num)));
-    precedents.reverse();
-    let precedents_shared = Arc::new(precedents);
-    let pecedent_filter = warp::any().map(move || precedents_shared.clone());
-
-    let new_law = new_law::NewLaws::from_pool(&db_url)
-        .await
-        .map_err(|e| handle_errors::Error::DatabaseQueryError(e))?;
-    let new_laws_shared = Arc::new(new_law.categories(0));
-    let new_law_filter = warp::any().map(move || new_laws_shared.clone());
 
+    let note_read = note_routes(store.clone(), manager.clone());
+    let cache_filter = warp::any().map(move || cache.clone());
     let store_filter = warp::any().map(move || store.clone());
     let redis_filter = warp::any().map(move || manager.clone());
 
     let cors = warp::cors()
         .allow_any_origin()
         .allow_headers(vec!["Content-Type", "Authorization"])
+        .expose_headers(vec!["X-Total-Count"])
         .allow_methods(&[
             Method::PUT,
             Method::DELETE,
@@ -180,7 +284,10 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(warp::path("note"))
         .and(warp::path::param::<String>())
         .and(warp::path::end())
+        .and(store_filter.clone())
         .and(redis_filter.clone())
+        .and(new_law_filter.clone())
+        .and(alias_filter.clone())
         .and(warp::body::json())
         .and_then(routes::note::update_content);
 
@@ -199,6 +306,7 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(warp::path::end())
         .and(store_filter.clone())
         .and(redis_filter.clone())
+        .and(alias_filter.clone())
         .and_then(routes::note::update_name);
 
     let clean_redis = warp::get()
@@ -206,15 +314,9 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(warp::path::end())
         .and(store_filter.clone())
         .and(redis_filter.clone())
+        .and(alias_filter.clone())
         .and_then(routes::note::clean_redis);
 
-    let get_note = warp::get()
-        .and(warp::path("note"))
-        .and(warp::path::param::<String>())
-        .and(store_filter.clone())
-        .and(redis_filter.clone())
-        .and_then(routes::note::get_content);
-
     let add_note = warp::post()
         .and(warp::path("note"))
         .and(warp::path::end())
@@ -229,6 +331,14 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(store_filter.clone())
         .and_then(routes::note::delete_note);
 
+    let refresh_law_card = warp::put()
+        .and(warp::path!("note" / String / "stale_cards" / usize))
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(redis_filter.clone())
+        .and(new_law_filter.clone())
+        .and_then(routes::note::refresh_law_card);
+
     let get_note_nav = warp::get()
         .and(warp::path("note_nav"))
         .and(warp::path::param::<String>())
@@ -274,8 +384,70 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(warp::path::param::<String>())
         .and(warp::path::end())
         .and(store_filter.clone())
+        .and(alias_filter.clone())
         .and_then(routes::new_law::get_history_law);
 
+    let get_law_timeline = warp::get()
+        .and(warp::path!("law" / String / "timeline"))
+        .and(store_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::new_law::get_law_timeline);
+
+    let get_law_as_of = warp::get()
+        .and(warp::path!("law" / String / "as_of" / String))
+        .and(store_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::new_law::get_law_as_of);
+
+    let get_law_diff = warp::get()
+        .and(warp::path!("law" / String / "diff"))
+        .and(warp::query::<routes::new_law::DiffQuery>())
+        .and(store_filter.clone())
+        .and(new_law_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::new_law::get_law_diff);
+
+    let get_law_changelog = warp::get()
+        .and(warp::path!("law" / String / "changelog"))
+        .and(warp::query::<routes::new_law::ChangelogQuery>())
+        .and(store_filter.clone())
+        .and(new_law_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::new_law::get_law_changelog);
+
+    let reload_cache = warp::post()
+        .and(warp::path!("admin" / "reload"))
+        .and(routes::authentication::admin())
+        .and(cache_filter.clone())
+        .and_then(routes::admin::reload_cache);
+
+    let get_sync_runs = warp::get()
+        .and(warp::path!("admin" / "sync_runs"))
+        .and(routes::authentication::admin())
+        .and(warp::query::<ListQuery>())
+        .and(store_filter.clone())
+        .and_then(routes::admin::get_sync_runs);
+
+    let reindex_references = warp::post()
+        .and(warp::path!("admin" / "reindex_references"))
+        .and(routes::authentication::admin())
+        .and(store_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::admin::reindex_references);
+
+    let get_export_manifest = warp::get()
+        .and(warp::path!("admin" / "export"))
+        .and(routes::authentication::admin())
+        .and(store_filter.clone())
+        .and_then(routes::admin::get_export_manifest);
+
+    let export_dataset = warp::get()
+        .and(warp::path!("admin" / "export" / String))
+        .and(routes::authentication::admin())
+        .and(warp::query::<routes::admin::ExportQuery>())
+        .and(store_filter.clone())
+        .and_then(routes::admin::export_dataset);
+
     let get_every_files = warp::get()
         .and(warp::path("every_file"))
         .and(store_filter.clone())
@@ -283,6 +455,7 @@ async fn main() -> Result<(), handle_errors::Error> {
 
     let get_every_notes = warp::get()
         .and(warp::path("every_notes"))
+        .and(warp::query::<ListQuery>())
         .and(store_filter.clone())
         .and_then(routes::note::get_every_note);
 
@@ -303,12 +476,21 @@ async fn main() -> Result<(), handle_errors::Error> {
     let get_dir_pub = warp::get()
         .and(warp::path("pub_dir"))
         .and(warp::path::end())
+        .and(warp::query::<ListQuery>())
         .and(store_filter.clone())
         .and_then(routes::directory::get_pub_dir);
 
+    let get_pub_dir_json = warp::get()
+        .and(warp::path("pub_dirs"))
+        .and(warp::path::end())
+        .and(warp::query::<ListQuery>())
+        .and(store_filter.clone())
+        .and_then(routes::directory::get_pub_dir_json);
+
     let get_dir_gallery = warp::get()
         .and(warp::path("gallery"))
         .and(warp::path::end())
+        .and(warp::query::<ListQuery>())
         .and(store_filter.clone())
         .and_then(routes::directory::get_gallery_dir);
 
@@ -331,7 +513,8 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(warp::path("allChapter"))
         .and(warp::path::param::<String>())
         .and(warp::path::end())
-        .and(new_law_filter.clone())
+        .and(toc_filter.clone())
+        .and(alias_filter.clone())
         .and_then(routes::new_law::get_all_chapter);
 
     let get_all_lawList = warp::get()
@@ -339,6 +522,7 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(warp::path::param::<String>())
         .and(warp::path::end())
         .and(new_law_filter.clone())
+        .and(alias_filter.clone())
         .and_then(routes::new_law::get_all_lawList);
 
     let get_all_chapters = warp::get()
@@ -350,10 +534,36 @@ async fn main() -> Result<(), handle_errors::Error> {
     let get_lawList_by_chapter = warp::post()
         .and(warp::path("lawList_by_chapter"))
         .and(warp::path::end())
-        .and(new_law_filter.clone())
+        .and(law_toc_filter.clone())
+        .and(alias_filter.clone())
         .and(warp::body::json())
         .and_then(routes::new_law::get_lawList_by_chapter);
 
+    let get_law_aliases = warp::get()
+        .and(warp::path("law_alias"))
+        .and(warp::path::end())
+        .and(alias_filter.clone())
+        .and_then(routes::new_law::get_law_aliases);
+
+    let add_law_alias = warp::post()
+        .and(warp::path("law_alias"))
+        .and(warp::path::end())
+        .and(routes::authentication::admin())
+        .and(store_filter.clone())
+        .and(alias_filter.clone())
+        .and(cache_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::new_law::add_law_alias);
+
+    let delete_law_alias = warp::delete()
+        .and(warp::path!("law_alias" / String))
+        .and(warp::path::end())
+        .and(routes::authentication::admin())
+        .and(store_filter.clone())
+        .and(alias_filter.clone())
+        .and(cache_filter.clone())
+        .and_then(routes::new_law::delete_law_alias);
+
     let add_file = warp::post()
         .and(warp::path("file"))
         .and(warp::path::end())
@@ -418,8 +628,77 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(warp::path::param::<String>())
         .and(warp::path::end())
         .and(new_law_filter.clone())
+        .and(alias_filter.clone())
         .and_then(routes::new_law::get_one_law);
 
+    let get_law_range = warp::get()
+        .and(warp::path!("laws" / String))
+        .and(warp::query::<routes::new_law::RangeQuery>())
+        .and(new_law_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::new_law::get_law_range);
+
+    let get_law_batch = warp::post()
+        .and(warp::path!("laws" / "batch"))
+        .and(new_law_filter.clone())
+        .and(alias_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::new_law::get_law_batch);
+
+    let get_law_toc = warp::get()
+        .and(warp::path!("law" / String / "toc"))
+        .and(toc_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::new_law::get_law_toc);
+
+    let get_toc_node = warp::get()
+        .and(warp::path!("law" / String / "toc" / String))
+        .and(law_toc_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::new_law::get_toc_node);
+
+    let get_dirs_json = warp::get()
+        .and(warp::path!("dirs" / String))
+        .and(routes::authentication::optional_auth())
+        .and(store_filter.clone())
+        .and_then(routes::directory::get_dirs_json);
+
+    let get_file_list_json = warp::get()
+        .and(warp::path!("files" / String / String))
+        .and(routes::authentication::optional_auth())
+        .and(store_filter.clone())
+        .and_then(routes::file::get_file_list_json);
+
+    let get_law_names = warp::get()
+        .and(warp::path!("law_names"))
+        .and(new_law_filter.clone())
+        .and_then(routes::new_law::get_law_names);
+
+    let get_graph_cites = warp::get()
+        .and(warp::path!("graph" / String / String / "cites"))
+        .and(graph_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::graph::get_cites);
+
+    let get_graph_cited_by = warp::get()
+        .and(warp::path!("graph" / String / String / "cited_by"))
+        .and(graph_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::graph::get_cited_by);
+
+    let get_graph_neighborhood = warp::get()
+        .and(warp::path!("graph" / String / String / "neighborhood"))
+        .and(warp::query::<routes::graph::GraphQuery>())
+        .and(graph_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::graph::get_neighborhood);
+
+    let export_graph = warp::get()
+        .and(warp::path!("graph" / "export"))
+        .and(warp::query::<routes::graph::GraphQuery>())
+        .and(graph_filter.clone())
+        .and_then(routes::graph::export_graph);
+
     let delete_file = warp::delete()
         .and(warp::path("file"))
         .and(warp::path::param::<String>())
@@ -501,6 +780,7 @@ async fn main() -> Result<(), handle_errors::Error> {
     let get_precedent_list = warp::get()
         .and(warp::path("precedentlist"))
         .and(warp::path::end())
+        .and(warp::query::<ListQuery>())
         .and(pecedent_filter.clone())
         .and_then(routes::otherlawresource::get_precedent_list);
 
@@ -540,9 +820,23 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(store_filter.clone())
         .and_then(routes::otherlawresource::get_resolution_by_id);
 
+    let get_notes_by_law = warp::get()
+        .and(warp::path!("law" / String / "notes"))
+        .and(routes::authentication::optional_auth())
+        .and(store_filter.clone())
+        .and(alias_filter.clone())
+        .and_then(routes::note::get_notes_by_law);
+
+    let get_notes_by_oldinter = warp::get()
+        .and(warp::path!("oldinterpretation" / String / "notes"))
+        .and(routes::authentication::optional_auth())
+        .and(store_filter.clone())
+        .and_then(routes::note::get_notes_by_oldinterpretation);
+
     let get_oldinter_list = warp::get()
         .and(warp::path!("oldinterpretationlist"))
         .and(warp::path::end())
+        .and(warp::query::<ListQuery>())
         .and(old_inters_filter.clone())
         .and_then(routes::otherlawresource::get_oldinter_list);
 
@@ -550,6 +844,7 @@ async fn main() -> Result<(), handle_errors::Error> {
         .and(warp::path!("lawnamelist"))
         .and(warp::path::end())
         .and(new_law_filter.clone())
+        .and(alias_filter.clone())
         .and_then(routes::otherlawresource::get_all_lawname_list);
 
     let get_dictionary = warp::get()
@@ -579,12 +874,14 @@ async fn main() -> Result<(), handle_errors::Error> {
     let get_newinter_list = warp::get()
         .and(warp::path!("newinterpretationlist"))
         .and(warp::path::end())
+        .and(warp::query::<ListQuery>())
         .and(new_inters_filter.clone())
         .and_then(routes::otherlawresource::get_newinter_list);
 
     let get_resolution_list = warp::get()
         .and(warp::path!("resolutionlist"))
         .and(warp::path::end())
+        .and(warp::query::<ListQuery>())
         .and(resolution_filter.clone())
         .and_then(routes::otherlawresource::get_resolution_list);
 
@@ -645,6 +942,7 @@ async fn main() -> Result<(), handle_errors::Error> {
     let get_by_user = warp::get()
         .and(warp::path!("vocabitem" / "by_user" / String))
         .and(warp::path::end())
+        .and(warp::query::<ListQuery>())
         .and(store_filter.clone())
         .and_then(routes::dictionary::get_vocabitems_by_user);
 
@@ -722,7 +1020,7 @@ async fn main() -> Result<(), handle_errors::Error> {
         .or(get_note_nav)
         .or(get_note_list)
         .or(add_note)
-        .or(get_note)
+        .or(note_read)
         .or(update_note)
         .or(update_file_name)
         .or(image)
@@ -757,6 +1055,33 @@ async fn main() -> Result<(), handle_errors::Error> {
         .or(get_dictionary)
         .or(delete_dictionary)
         .or(add_dictionary)
+        .or(get_law_aliases)
+        .or(add_law_alias)
+        .or(delete_law_alias)
+        .or(get_notes_by_law)
+        .or(get_notes_by_oldinter)
+        .or(refresh_law_card)
+        .or(get_law_timeline)
+        .or(get_law_as_of)
+        .or(get_law_diff)
+        .or(get_law_changelog)
+        .or(reload_cache)
+        .or(get_sync_runs)
+        .or(reindex_references)
+        .or(get_export_manifest)
+        .or(export_dataset)
+        .or(get_law_range)
+        .or(get_law_batch)
+        .or(get_law_toc)
+        .or(get_toc_node)
+        .or(get_dirs_json)
+        .or(get_pub_dir_json)
+        .or(get_file_list_json)
+        .or(get_law_names)
+        .or(get_graph_cites)
+        .or(get_graph_cited_by)
+        .or(get_graph_neighborhood)
+        .or(export_graph)
         .with(warp::trace::request()) // 提供靜態文件
         .with(cors)
         .recover(return_error);
@@ -764,3 +1089,84 @@ async fn main() -> Result<(), handle_errors::Error> {
 
     Ok(())
 }
+
+// GET /note/{id} 與 GET /note/{id}/stale_cards
+fn note_routes(
+    store: storage::Store,
+    redis: Option<ConnectionManager>,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let store_filter = warp::any().map(move || store.clone());
+    let redis_filter = warp::any().map(move || redis.clone());
+
+    let get_note = warp::get()
+        .and(warp::path("note"))
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and(redis_filter)
+        .and_then(routes::note::get_content);
+
+    let get_stale_cards = warp::get()
+        .and(warp::path!("note" / String / "stale_cards"))
+        .and(routes::authentication::optional_auth())
+        .and(store_filter)
+        .and_then(routes::note::get_stale_cards);
+
+    get_note.or(get_stale_cards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Storage;
+    use crate::types::directory::Directory;
+    use crate::types::note::Note;
+
+    #[tokio::test]
+    async fn stale_cards_route_is_not_shadowed_by_get_note() {
+        let store = storage::sqlite::SqliteStore::memory().await;
+        store
+            .add_directory(Directory {
+                id: "d1".to_string(),
+                slug: Directory::make_slug("alice", "民法"),
+                user_name: "alice".to_string(),
+                directory: "民法".to_string(),
+                public: true,
+                description: String::new(),
+                note_order: Vec::new(),
+            })
+            .await
+            .unwrap();
+        store
+            .add_note(Note {
+                id: "n1".to_string(),
+                slug: Note::make_slug("alice", "民法", "總則"),
+                content: Some(serde_json::json!([])),
+                footer: None,
+                user_name: "alice".to_string(),
+                directory: "民法".to_string(),
+                file_name: "總則".to_string(),
+                public: true,
+            })
+            .await
+            .unwrap();
+        let routes = note_routes(Arc::new(store), None);
+
+        let res = warp::test::request()
+            .method("GET")
+            .path("/note/n1/stale_cards")
+            .reply(&routes)
+            .await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.body().as_ref(), b"[]");
+
+        let res = warp::test::request()
+            .method("GET")
+            .path("/note/n1")
+            .reply(&routes)
+            .await;
+        assert_eq!(res.status(), 200);
+        let note: serde_json::Value = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(note["id"], "n1");
+    }
+}
@@ -1,7 +1,7 @@
 use percent_encoding::percent_decode_str;
 use reqwest::StatusCode;
 use tracing::info;
-use crate::store::Store;
+use crate::storage::Store;
 use crate::types::file::File;
 use crate::types::Library::{Library, LibraryItem};
 
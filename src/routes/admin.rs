@@ -0,0 +1,127 @@
+use crate::cache::Cache;
+use crate::routes::note::index_references;
+use crate::storage::Store;
+use crate::types::account::Session;
+use crate::types::pagination::{with_total, ListQuery};
+use futures::TryStreamExt;
+use new_law::alias::LawAliases;
+use note::Block;
+use otherlawresource::export::{self, DatasetWriter, Manifest};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tracing::info;
+use warp::http::StatusCode;
+
+pub async fn reload_cache(
+    session: Session,
+    cache: Cache,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    info!("{} 重新載入快取", session.user_name);
+    cache.reload().await?;
+    Ok(warp::reply::with_status("Cache Reloaded", StatusCode::OK))
+}
+
+// GET /admin/sync_runs?type=laws&page=1&limit=20
+pub async fn get_sync_runs(
+    _session: Session,
+    query: ListQuery,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.get_sync_runs(&query).await {
+        Ok((runs, total)) => Ok(with_total(&runs, total)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+// POST /admin/reindex_references，重建所有筆記的引用索引，
+// 用於建立 note_reference 之前就存在的筆記，以及簡稱變更之後
+pub async fn reindex_references(
+    session: Session,
+    store: Store,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    info!("{} 重建筆記引用索引", session.user_name);
+    let notes = store.get_every_note().await?;
+    let mut count = 0;
+    for note in notes {
+        let blocks: Vec<Block> = match note.content.map(serde_json::from_value) {
+            Some(Ok(blocks)) => blocks,
+            Some(Err(e)) => {
+                tracing::warn!("筆記 {} 的內容無法解析：{}", note.id, e);
+                continue;
+            }
+            None => Vec::new(),
+        };
+        let refs = index_references(&blocks, &aliases.read().unwrap());
+        store.update_note_references(&note.id, &refs).await?;
+        count += 1;
+    }
+    Ok(warp::reply::json(&serde_json::json!({ "notes": count })))
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct ExportQuery {
+    format: Option<String>, // jsonl（預設）或 csv
+}
+
+// GET /admin/export，各資料集的筆數與 sha256，與匯出指令的 manifest.json 相同格式
+pub async fn get_export_manifest(
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    info!("{} 匯出資料集清單", session.user_name);
+    let mut manifest = Manifest {
+        created_at: chrono::Utc::now(),
+        datasets: Vec::new(),
+    };
+    // 逐筆計算雜湊，不保留輸出內容
+    for dataset in export::DATASETS {
+        let mut writer = DatasetWriter::new(std::io::sink(), std::io::sink());
+        let mut rows = store.export_rows(dataset);
+        while let Some(row) = rows.try_next().await? {
+            writer
+                .push(&row)
+                .map_err(|e| warp::reject::custom(handle_errors::Error::StdFileErroor(e.into())))?;
+        }
+        let entry = writer
+            .finish(dataset)
+            .map_err(|e| warp::reject::custom(handle_errors::Error::StdFileErroor(e.into())))?;
+        manifest.datasets.push(entry);
+    }
+    Ok(warp::reply::json(&manifest))
+}
+
+// GET /admin/export/newlaw?format=csv
+pub async fn export_dataset(
+    dataset: String,
+    session: Session,
+    query: ExportQuery,
+    store: Store,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    if !export::is_dataset(&dataset) {
+        return Err(warp::reject::custom(handle_errors::Error::DatasetNotFound(
+            dataset,
+        )));
+    }
+    info!("{} 匯出 {}", session.user_name, dataset);
+    let rows: Vec<serde_json::Value> = store.export_rows(&dataset).try_collect().await?;
+    let (body, extension, content_type) = match query.format.as_deref() {
+        Some("csv") => (
+            export::to_csv(&rows)
+                .map_err(|e| warp::reject::custom(handle_errors::Error::StdFileErroor(e.into())))?,
+            "csv",
+            "text/csv; charset=utf-8",
+        ),
+        _ => (
+            export::to_jsonl(&rows),
+            "jsonl",
+            "application/x-ndjson; charset=utf-8",
+        ),
+    };
+    let reply = warp::reply::with_header(body, "Content-Type", content_type);
+    Ok(Box::new(warp::reply::with_header(
+        reply,
+        "Content-Disposition",
+        format!("attachment; filename=\"{dataset}.{extension}\""),
+    )))
+}
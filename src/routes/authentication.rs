@@ -7,14 +7,16 @@ use redis::AsyncCommands;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 use warp::{http::StatusCode, Filter};
-use crate::store::Store;
+use crate::storage::Store;
 use crate::types::account::{Account, Redis_Database, Session};
 
 
 
 // 以姓名確認token是否存在
 pub async fn are_you_in_redis(you: String) -> Result<impl warp::Reply, warp::Rejection> {
-    let redis_url= std::env::var("REDIS_PUBLIC_URL").unwrap();
+    // 沒有 Redis（SQLite 模式）時不保存令牌
+    let redis_url = std::env::var("REDIS_PUBLIC_URL")
+        .map_err(|_| warp::reject::custom(handle_errors::Error::TokenNotFound))?;
     let mut redis_database = Redis_Database::new(&redis_url).await
         .map_err(|e| warp::reject::custom(handle_errors::Error::CacheError(e)))?;
     let exists_or_not: Result<Option<String>, redis::RedisError> = redis_database.connection.get(&you).await;
@@ -131,19 +133,23 @@ async fn issue_token(
         .build()
         .expect("建立令牌失敗");
 
-    let redis_url= std::env::var("REDIS_PUBLIC_URL").unwrap();
-    let mut redis_database = Redis_Database::new(&redis_url).await
-        .map_err(|e| handle_errors::Error::CacheError(e))?;
-    let _: () = redis_database.connection.set_ex(user_name, token.clone(), 86400).await
-        .map_err(|e| handle_errors::Error::CacheError(e))?;
+    if let Ok(redis_url) = std::env::var("REDIS_PUBLIC_URL") {
+        let mut redis_database = Redis_Database::new(&redis_url).await
+            .map_err(|e| handle_errors::Error::CacheError(e))?;
+        let _: () = redis_database.connection.set_ex(user_name, token.clone(), 86400).await
+            .map_err(|e| handle_errors::Error::CacheError(e))?;
+    }
     Ok(token)
 }
 
 pub fn verify_token(token: String) -> Result<Session, handle_errors::Error> {
+    // 與 issue_token 使用同一把金鑰
+    let paseto_key = std::env::var("PASETO_KEY")
+        .map_err(|_| handle_errors::Error::CannotDecryptToken)?;
     let token = paseto::tokens::validate_local_token(
         &token,
         None,// footer
-        &"RANDOM WORDS WINTER MACINTOSH PC".as_bytes(), //key
+        paseto_key.as_bytes(), //key
         &paseto::tokens::TimeBackend::Chrono, //backend
     ).map_err(|_| handle_errors::Error::CannotDecryptToken)?;
 
@@ -161,4 +167,30 @@ pub fn auth() -> impl Filter<Extract = (Session,), Error = warp::Rejection> + Cl
 
         future::ready(Ok(token))
     })
-}
\ No newline at end of file
+}
+
+// 未登入或 token 無效時為 None，用於公開、私人內容皆可看的頁面
+pub fn optional_auth() -> impl Filter<Extract = (Option<Session>,), Error = warp::Rejection> + Clone
+{
+    warp::header::optional::<String>("Authorization")
+        .map(|token: Option<String>| token.and_then(|token| verify_token(token).ok()))
+}
+
+// 管理員名單由環境變數 ADMIN_USERS 設定，以逗號分隔
+pub fn is_admin(user_name: &str) -> bool {
+    std::env::var("ADMIN_USERS")
+        .map(|users| users.split(',').any(|name| name.trim() == user_name))
+        .unwrap_or(false)
+}
+
+pub fn admin() -> impl Filter<Extract = (Session,), Error = warp::Rejection> + Clone {
+    auth().and_then(|session: Session| {
+        if is_admin(&session.user_name) {
+            future::ready(Ok(session))
+        } else {
+            future::ready(Err(warp::reject::custom(
+                handle_errors::Error::Unauthorized,
+            )))
+        }
+    })
+}
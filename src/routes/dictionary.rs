@@ -1,5 +1,6 @@
-use crate::store::Store;
+use crate::storage::Store;
 use crate::types::dictionary::{Dictionary, VocabItem, VocabItemLaw};
+use crate::types::pagination::{with_total, ListQuery};
 use percent_encoding::percent_decode_str;
 
 pub async fn get_dictionary(
@@ -104,11 +105,16 @@ pub async fn get_vocabitems_by_definition(
 
 pub async fn get_vocabitems_by_user(
     user_name: String,
+    query: ListQuery,
     store: Store,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    // 單字沒有類型欄位，依字典篩選請用 dictionary
+    if query.kind.is_some() {
+        return Err(warp::reject::custom(handle_errors::Error::MissingParameters));
+    }
     let user_name = percent_decode_str(&user_name).decode_utf8_lossy();
-    let items = store.get_vocabitem_user(user_name.as_ref()).await?;
-    Ok(warp::reply::json(&items))
+    let (items, total) = store.get_vocabitem_user(user_name.as_ref(), &query).await?;
+    Ok(with_total(&items, total))
 }
 
 pub async fn add_vocabitem_law(
@@ -1,23 +1,34 @@
-use crate::store::Store;
+use crate::html;
+use crate::storage::Store;
 use crate::types::account::Session;
 use crate::types::directory::Directory;
+use crate::types::pagination::{with_total, ListQuery};
 use percent_encoding::percent_decode_str;
 use reqwest::StatusCode;
 use tracing::info;
+use uuid::Uuid;
 
 pub async fn get_dir_by_user(
     user_name: String,
     stroe: Store,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let mut s = String::new();
     let user_name = percent_decode_str(&user_name).decode_utf8_lossy();
     let dirs = stroe.get_directory_user(&user_name.to_owned()).await?;
-    dirs.iter()
-        .map(|k| format!("<li class='the-dir'><a>{}<a></li>", k.directory))
-        .for_each(|str| {
-            s.push_str(&str);
-        });
-    Ok(warp::reply::html(s))
+    Ok(warp::reply::html(html::dir_list(&dirs)))
+}
+
+// 使用者的資料夾，給非網頁的用戶端使用；本人才看得到私人資料夾
+pub async fn get_dirs_json(
+    user_name: String,
+    session: Option<Session>,
+    stroe: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let user_name = percent_decode_str(&user_name).decode_utf8_lossy();
+    let mut dirs = stroe.get_directory_user(&user_name.to_owned()).await?;
+    if session.map_or(true, |s| s.user_name != user_name) {
+        dirs.retain(|dir| dir.public);
+    }
+    Ok(warp::reply::json(&dirs))
 }
 
 pub async fn get_dir_information(
@@ -25,15 +36,17 @@ pub async fn get_dir_information(
     stroe: Store,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy();
-    match stroe.get_directory(&id.into_owned()).await {
+    let id = stroe.resolve_directory_id(&id).await?;
+    match stroe.get_directory(&id).await {
         Ok(dir) => Ok(warp::reply::json(&dir)),
         Err(e) => Err(warp::reject::custom(e)),
     }
 }
 
 pub async fn updtae_dir(store: Store, dir: Directory) -> Result<impl warp::Reply, warp::Rejection> {
+    let id = store.resolve_directory_id(&dir.id).await?;
     match store
-        .update_directory(dir.public, dir.description, dir.id)
+        .update_directory(dir.public, dir.description, id)
         .await
     {
         Ok(dir) => Ok(warp::reply::json(&dir)),
@@ -41,37 +54,42 @@ pub async fn updtae_dir(store: Store, dir: Directory) -> Result<impl warp::Reply
     }
 }
 
-pub async fn get_pub_dir(stroe: Store) -> Result<impl warp::Reply, warp::Rejection> {
-    let mut s = String::new();
-
-    match stroe.get_directory_pub().await {
-        Ok(dirs) => {
-            dirs.iter()
-                .take(20)
-                .map(|k| {
-                    format!(
-                        "
-                <div class='public-dir' id='pub-{}'>
-                    <div>write by：<span>{}</span></div>
-                    <h2>{}</h2>
-                    <div class='summary'>summary：<span>{}</span></div>
-                </div>",
-                        k.id, k.user_name, k.directory, k.description
-                    )
-                })
-                .for_each(|str| {
-                    s.push_str(&str);
-                });
-        }
-        Err(e) => return Err(warp::reject::custom(e)),
+pub async fn get_pub_dir(
+    query: ListQuery,
+    stroe: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    // 網頁版首頁預設只顯示 20 個
+    let query = ListQuery {
+        limit: query.limit.or(Some(20)),
+        ..query
     };
+    match stroe.get_directory_pub(&query).await {
+        Ok((dirs, _)) => Ok(warp::reply::html(html::pub_dir_list(&dirs))),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
 
-    Ok(warp::reply::html(s))
+// 與 get_pub_dir 相同的公開資料夾，以 JSON 回傳
+pub async fn get_pub_dir_json(
+    query: ListQuery,
+    stroe: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let query = ListQuery {
+        limit: query.limit.or(Some(20)),
+        ..query
+    };
+    match stroe.get_directory_pub(&query).await {
+        Ok((dirs, total)) => Ok(with_total(&dirs, total)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
 }
 
-pub async fn get_gallery_dir(stroe: Store) -> Result<impl warp::Reply, warp::Rejection> {
-    match stroe.get_directory_pub().await {
-        Ok(dirs) => Ok(warp::reply::json(&dirs)),
+pub async fn get_gallery_dir(
+    query: ListQuery,
+    stroe: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match stroe.get_directory_pub(&query).await {
+        Ok((dirs, total)) => Ok(with_total(&dirs, total)),
         Err(e) => Err(warp::reject::custom(e)),
     }
 }
@@ -80,6 +98,12 @@ pub async fn add_dir(
     store: Store,
     directory: Directory,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    // id 一律由伺服器產生，用戶端送來的舊式 id 不再使用
+    let directory = Directory {
+        id: Uuid::new_v4().to_string(),
+        slug: Directory::make_slug(&directory.user_name, &directory.directory),
+        ..directory
+    };
     match store.add_directory(directory).await {
         Ok(dir) => {
             info!("成功新增：{}", dir.id);
@@ -96,12 +120,10 @@ pub async fn delete_dir(
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let user_name = percent_decode_str(&user_name).decode_utf8_lossy();
     let dir_name = percent_decode_str(&dir_name).decode_utf8_lossy();
-    let id = format!("{user_name}-{dir_name}");
-    match store.delete_directory(id).await {
-        Ok(dir) => {
-            store.delete_folder_note(dir_name.as_ref()).await?;
-            Ok(warp::reply::with_status("Directory added", StatusCode::OK))
-        }
+    let dir = store.get_directory_by_name(&user_name, &dir_name).await?;
+    // 目錄中的筆記由 directory_id 的外鍵一併刪除
+    match store.delete_directory(dir.id).await {
+        Ok(_) => Ok(warp::reply::with_status("Directory added", StatusCode::OK)),
         Err(e) => Err(warp::reject::custom(e)),
     }
 }
@@ -110,23 +132,9 @@ pub async fn get_dir_for_pop(
     user_name: String,
     stroe: Store,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let mut s = String::new();
     let user_name = percent_decode_str(&user_name).decode_utf8_lossy();
-    println!("{user_name}");
     let dirs = stroe.get_directory_user(&user_name.to_owned()).await?;
-    dirs.iter()
-        .map(|k| {
-            format!(
-                "<div class='option'><input type='checkbox' id='option-{}'>
-                            <label for='option-{}'>{}</label></div>",
-                k.directory, k.directory, k.directory
-            )
-        })
-        .for_each(|str| {
-            println!("{str}");
-            s.push_str(&str);
-        });
-    Ok(warp::reply::html(s))
+    Ok(warp::reply::html(html::dir_options(&dirs)))
 }
 
 pub async fn update_note_order(
@@ -138,8 +146,8 @@ pub async fn update_note_order(
     let mut s = String::new();
     let user_name = percent_decode_str(&user_name).decode_utf8_lossy();
     let dir_name = percent_decode_str(&dir_name).decode_utf8_lossy();
-    let dir_id = format!("{user_name}-{dir_name}");
-    match store.update_note_order(dir_id, list).await {
+    let dir = store.get_directory_by_name(&user_name, &dir_name).await?;
+    match store.update_note_order(dir.id, list).await {
         Ok(dir) => Ok(warp::reply::html(dir)),
         Err(e) => Err(warp::reject::custom(e)),
     }
@@ -147,6 +155,7 @@ pub async fn update_note_order(
 
 pub async fn get_note_order(id: String, stroe: Store) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy();
+    let id = stroe.resolve_directory_id(&id).await?;
     match stroe.get_directory(&id).await {
         Ok(dir) => Ok(warp::reply::json(&dir.note_order)),
         Err(e) => Err(warp::reject::custom(e)),
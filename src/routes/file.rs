@@ -1,5 +1,6 @@
-use crate::store::Store;
-use crate::types::file::File;
+use crate::storage::Store;
+use crate::types::account::Session;
+use crate::types::file::{File, FileEntry};
 use bytes::BufMut;
 use futures::{StreamExt, TryStreamExt};
 use lol_html::element;
@@ -226,20 +227,32 @@ pub async fn get_file_list(
     dir: String,
     stroe: Store,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let mut s = String::new();
     let user_name = percent_decode_str(&user_name).decode_utf8_lossy();
     let dir = percent_decode_str(&dir).decode_utf8_lossy();
     let files = stroe
         .get_file_user(&user_name.to_owned(), &dir.to_owned())
         .await?;
-    files
-        .vec_files
-        .iter()
-        .map(|file| format!("<li class='the-file'><a>{}<a></li>", file.file_name))
-        .for_each(|str| {
-            s.push_str(&str);
-        });
-    Ok(warp::reply::html(s))
+    Ok(warp::reply::html(crate::html::file_list(&files.vec_files)))
+}
+
+// 資料夾內的檔案，不含內容；私人資料夾只有擁有者能查看
+pub async fn get_file_list_json(
+    user_name: String,
+    dir: String,
+    session: Option<Session>,
+    stroe: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let user_name = percent_decode_str(&user_name).decode_utf8_lossy();
+    let dir = percent_decode_str(&dir).decode_utf8_lossy();
+    let is_owner = session.map_or(false, |s| s.user_name == user_name);
+    if !is_owner && !stroe.get_directory_by_name(&user_name, &dir).await?.public {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+    let files = stroe
+        .get_file_user(&user_name.to_owned(), &dir.to_owned())
+        .await?;
+    let entries: Vec<FileEntry> = files.vec_files.iter().map(FileEntry::from).collect();
+    Ok(warp::reply::json(&entries))
 }
 
 pub async fn get_file_list2(
@@ -0,0 +1,97 @@
+use new_law::alias::LawAliases;
+use otherlawresource::graph::{CitationGraph, NodeKind, Subgraph};
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tracing::info;
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct GraphQuery {
+    depth: Option<usize>,   // 預設 1，最多 otherlawresource::graph::MAX_DEPTH
+    format: Option<String>, // json（預設）或 graphml
+}
+
+// 路徑中的 kind 與 id，法條 id 可用簡稱，例如「刑法-2」
+fn node_param(
+    kind: &str,
+    id: &str,
+    aliases: &RwLock<LawAliases>,
+) -> Result<(NodeKind, String), warp::Rejection> {
+    let kind = NodeKind::parse(kind)
+        .ok_or(warp::reject::custom(handle_errors::Error::MissingParameters))?;
+    let id = percent_decode_str(id).decode_utf8_lossy();
+    let id = match kind {
+        NodeKind::Law => aliases.read().unwrap().resolve_law_id(&id),
+        _ => id.to_string(),
+    };
+    Ok((kind, id))
+}
+
+fn reply_subgraph(subgraph: Subgraph, format: Option<&str>) -> Box<dyn warp::Reply> {
+    match format {
+        Some("graphml") => Box::new(warp::reply::with_header(
+            subgraph.to_graphml(),
+            "Content-Type",
+            "application/graphml+xml; charset=utf-8",
+        )),
+        _ => Box::new(warp::reply::json(&subgraph)),
+    }
+}
+
+// GET /graph/{kind}/{id}/cites
+pub async fn get_cites(
+    kind: String,
+    id: String,
+    graph: Arc<CitationGraph>,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (kind, id) = node_param(&kind, &id, &aliases)?;
+    match graph.cites(kind, &id) {
+        Some(nodes) => Ok(warp::reply::json(&nodes)),
+        None => Err(warp::reject::custom(handle_errors::Error::QuestionNotFound)),
+    }
+}
+
+// GET /graph/{kind}/{id}/cited_by
+pub async fn get_cited_by(
+    kind: String,
+    id: String,
+    graph: Arc<CitationGraph>,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (kind, id) = node_param(&kind, &id, &aliases)?;
+    match graph.cited_by(kind, &id) {
+        Some(nodes) => Ok(warp::reply::json(&nodes)),
+        None => Err(warp::reject::custom(handle_errors::Error::QuestionNotFound)),
+    }
+}
+
+// GET /graph/{kind}/{id}/neighborhood?depth=2&format=graphml
+pub async fn get_neighborhood(
+    kind: String,
+    id: String,
+    query: GraphQuery,
+    graph: Arc<CitationGraph>,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    let (kind, id) = node_param(&kind, &id, &aliases)?;
+    let subgraph = graph
+        .neighborhood(kind, &id, query.depth.unwrap_or(1))
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+    info!(
+        "{}:{} 鄰近節點{}個、引用{}筆",
+        kind.as_str(),
+        id,
+        subgraph.nodes.len(),
+        subgraph.edges.len()
+    );
+    Ok(reply_subgraph(subgraph, query.format.as_deref()))
+}
+
+// GET /graph/export?format=graphml
+pub async fn export_graph(
+    query: GraphQuery,
+    graph: Arc<CitationGraph>,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    Ok(reply_subgraph(graph.export(), query.format.as_deref()))
+}
@@ -1,8 +1,10 @@
 pub mod Library;
+pub mod admin;
 pub mod authentication;
 pub mod dictionary;
 pub(crate) mod directory;
 pub mod file;
+pub mod graph;
 pub mod new_law;
 pub(crate) mod note;
 pub mod otherlawresource;
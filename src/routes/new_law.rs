@@ -1,11 +1,16 @@
-use crate::store::Store;
+use crate::cache::Cache;
+use crate::storage::Store;
 use new_law::*;
 #[allow(unused_imports)]
 use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use indexmap::IndexMap;
+use new_law::alias::{LawAlias, LawAliases};
+use new_law::article::ArticleNumber;
+use new_law::toc::LawToc;
+use crate::types::account::Session;
 use tracing::{info, instrument};
 
 
@@ -15,8 +20,10 @@ pub async fn get_one_law(
     cate: String,
     num: String,
     map: Arc<IndexMap<String, NewLaws>>,
+    aliases: Arc<RwLock<LawAliases>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let cate = percent_decode_str(&cate).decode_utf8_lossy();
+    let cate = aliases.read().unwrap().resolve(&cate).to_string();
     let num = percent_decode_str(&num).decode_utf8_lossy();
     info!("獲取{cate}第{num}條");
     let x = format!("{}-{}", cate, num);
@@ -29,11 +36,103 @@ pub async fn get_one_law(
     }
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct RangeQuery {
+    from: Option<String>, // 「184」、「184-1」或「第一百八十四條」
+    to: Option<String>,
+}
+
+// 條號範圍內的條文（含頭尾），依條號排序
+pub async fn get_law_range(
+    cate: String,
+    query: RangeQuery,
+    map: Arc<IndexMap<String, NewLaws>>,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let cate = percent_decode_str(&cate).decode_utf8_lossy();
+    let cate = aliases.read().unwrap().resolve(&cate).to_string();
+    let parse = |num: Option<String>| match num {
+        Some(num) => num
+            .parse::<ArticleNumber>()
+            .map(Some)
+            .map_err(|_| warp::reject::custom(handle_errors::Error::MissingParameters)),
+        None => Ok(None),
+    };
+    let from = parse(query.from)?;
+    let to = parse(query.to)?;
+
+    let laws = map
+        .get(&cate)
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+    let mut result: Vec<NewLaw> = laws
+        .lines
+        .iter()
+        .filter(|law| match law.num.parse::<ArticleNumber>() {
+            Ok(num) => {
+                from.as_ref().map_or(true, |from| &num >= from)
+                    && to.as_ref().map_or(true, |to| &num <= to)
+            }
+            Err(_) => false,
+        })
+        .cloned()
+        .collect();
+    new_law::article::sort_laws(&mut result);
+    info!("獲取{cate}共{}條", result.len());
+    Ok(warp::reply::json(&result))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BatchLaws {
+    laws: Vec<NewLaw>,
+    missing: Vec<String>,
+}
+
+const BATCH_LIMIT: usize = 500;
+
+/// 一次取得多條條文，例如 citation 解析出的 id，依法規順序與條號排序
+pub async fn get_law_batch(
+    map: Arc<IndexMap<String, NewLaws>>,
+    aliases: Arc<RwLock<LawAliases>>,
+    ids: Vec<String>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if ids.len() > BATCH_LIMIT {
+        return Err(warp::reject::custom(handle_errors::Error::MissingParameters));
+    }
+    let ids: Vec<String> = {
+        let aliases = aliases.read().unwrap();
+        ids.iter().map(|id| aliases.resolve_law_id(id)).collect()
+    };
+
+    let mut found: Vec<(usize, &NewLaw)> = Vec::new();
+    let mut missing = Vec::new();
+    let mut seen = HashSet::new();
+    for id in ids {
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        match find_law(&map, &id) {
+            Some(law) => found.push(law),
+            None => missing.push(id),
+        }
+    }
+    found.sort_by(|(ia, a), (ib, b)| {
+        ia.cmp(ib)
+            .then_with(|| new_law::article::compare_nums(&a.num, &b.num))
+    });
+
+    Ok(warp::reply::json(&BatchLaws {
+        laws: found.into_iter().map(|(_, law)| law.clone()).collect(),
+        missing,
+    }))
+}
+
 pub async fn get_history_law(
     lawid: String,
-    store: Store
+    store: Store,
+    aliases: Arc<RwLock<LawAliases>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let lawid = percent_decode_str(&lawid).decode_utf8_lossy().to_string();
+    let lawid = aliases.read().unwrap().resolve_law_id(&lawid);
     match store.get_historylaw(lawid).await {
         Ok(n) => {
             Ok(warp::reply::json(&n))
@@ -42,19 +141,174 @@ pub async fn get_history_law(
     }
 }
 
+pub async fn get_law_timeline(
+    lawid: String,
+    store: Store,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let lawid = percent_decode_str(&lawid).decode_utf8_lossy().to_string();
+    let lawid = aliases.read().unwrap().resolve_law_id(&lawid);
+    let history = store.get_historylaw(lawid).await?;
+    let timeline = otherlawresource::timeline::build_timeline(history);
+    Ok(warp::reply::json(&timeline))
+}
+
+// date 可為西元 2019-05-01 或民國 108-05-01
+pub async fn get_law_as_of(
+    lawid: String,
+    date: String,
+    store: Store,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let lawid = percent_decode_str(&lawid).decode_utf8_lossy().to_string();
+    let lawid = aliases.read().unwrap().resolve_law_id(&lawid);
+    let date = percent_decode_str(&date).decode_utf8_lossy();
+    let date = otherlawresource::timeline::parse_query_date(&date)
+        .ok_or(warp::reject::custom(handle_errors::Error::MissingParameters))?;
+    info!("獲取{lawid}於{date}的條文");
+    let history = store.get_historylaw(lawid).await?;
+    let timeline = otherlawresource::timeline::build_timeline(history);
+    match otherlawresource::timeline::version_as_of(&timeline, date) {
+        Some(version) => Ok(warp::reply::json(version)),
+        None => Err(warp::reject::custom(handle_errors::Error::QuestionNotFound)),
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct DiffQuery {
+    from: Option<String>, // history_law 的 no，或 current
+    to: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct LawDiff {
+    lawid: String,
+    from: String,
+    to: String,
+    lines: Vec<new_law::diff::LineDiff>,
+    html: String,
+}
+
+// 依法條 id 找出條文，以及其法規在 map 中的順序
+fn find_law<'a>(map: &'a IndexMap<String, NewLaws>, id: &str) -> Option<(usize, &'a NewLaw)> {
+    let (chapter, _) = new_law::law_id::split(id)?;
+    let (index, _, laws) = map.get_full(chapter)?;
+    let law = laws.lines.iter().find(|law| law.id == id)?;
+    Some((index, law))
+}
+
+// 取出某個版本的條文，每行一個元素
+fn version_lines(
+    version: &str,
+    lawid: &str,
+    history: &[otherlawresource::HistoryLaw],
+    map: &IndexMap<String, NewLaws>,
+) -> Option<Vec<String>> {
+    if version == "current" {
+        let (_, law) = find_law(map, lawid)?;
+        return Some(law.lines.iter().map(|line| line.content.clone()).collect());
+    }
+    let no: i16 = version.parse().ok()?;
+    let h = history.iter().find(|h| h.no == no)?;
+    Some(
+        h.content
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+    )
+}
+
+/// 比較條文的兩個版本，預設為前一版與現行條文
+pub async fn get_law_diff(
+    lawid: String,
+    query: DiffQuery,
+    store: Store,
+    map: Arc<IndexMap<String, NewLaws>>,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let lawid = percent_decode_str(&lawid).decode_utf8_lossy().to_string();
+    let lawid = aliases.read().unwrap().resolve_law_id(&lawid);
+    let history = store.get_historylaw(lawid.clone()).await?;
+    let timeline = otherlawresource::timeline::build_timeline(history.clone());
+
+    let from = match query.from {
+        Some(from) => from,
+        None => timeline
+            .iter()
+            .rev()
+            .nth(1)
+            .or(timeline.last())
+            .map(|v| v.no.to_string())
+            .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?,
+    };
+    let to = query.to.unwrap_or("current".to_string());
+
+    let old = version_lines(&from, &lawid, &history, &map)
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+    let new = version_lines(&to, &lawid, &history, &map)
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+    let lines = new_law::diff::diff_lines(&old, &new);
+    let html = new_law::diff::redline_html(&lines);
+    info!("比較{lawid}：{from} -> {to}");
+    Ok(warp::reply::json(&LawDiff {
+        lawid,
+        from,
+        to,
+        lines,
+        html,
+    }))
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ChangelogQuery {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+// from、to 可為西元或民國日期，皆包含當天
+pub async fn get_law_changelog(
+    name: String,
+    query: ChangelogQuery,
+    store: Store,
+    map: Arc<IndexMap<String, NewLaws>>,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let name = percent_decode_str(&name).decode_utf8_lossy();
+    let name = aliases.read().unwrap().resolve(&name).to_string();
+    let laws = map
+        .get(&name)
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+    let parse = |date: Option<String>| match date {
+        Some(date) => otherlawresource::timeline::parse_query_date(&date)
+            .map(Some)
+            .ok_or(warp::reject::custom(handle_errors::Error::MissingParameters)),
+        None => Ok(None),
+    };
+    let from = parse(query.from)?;
+    let to = parse(query.to)?;
+
+    let current_ids: HashSet<String> = laws.lines.iter().map(|law| law.id.clone()).collect();
+    let history = store.get_historylaw_by_chapter(&name).await?;
+    let mut changelog = otherlawresource::timeline::build_changelog(history, &current_ids);
+    changelog.entries.retain(|entry| {
+        from.map_or(true, |from| entry.date >= from) && to.map_or(true, |to| entry.date <= to)
+    });
+    info!("獲取{name}修正紀錄：{}筆", changelog.entries.len());
+    Ok(warp::reply::json(&changelog))
+}
+
 pub async fn get_all_lawList(
     cate: String,
     map: Arc<IndexMap<String, NewLaws>>,
+    aliases: Arc<RwLock<LawAliases>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let cate = percent_decode_str(&cate).decode_utf8_lossy();
+    let cate = aliases.read().unwrap().resolve(&cate).to_string();
     let mut laws =
         map.get(&cate.to_string()).ok_or(LawError::NOThisChapter)
             .map_err(|_| warp::reject::custom(handle_errors::Error::QuestionNotFound))?.to_owned();
-    laws.lines.sort_by(|a, b| {
-        to_f32(a.num.clone())
-            .partial_cmp(&to_f32(b.num.clone()))
-            .unwrap()
-    });
+    new_law::article::sort_laws(&mut laws.lines);
     match laws.lawList_create() {
         Ok(n) => Ok(warp::reply::json(&n)),
         _ => Err(warp::reject::custom(handle_errors::Error::QuestionNotFound)),
@@ -65,62 +319,196 @@ pub async fn get_all_lawList(
 pub struct Chapter {
     chapter1: String,
     chapter2: String,
+    #[serde(default)]
+    node_id: Option<String>, // 目錄的 node id，有給時直接查表
 }
 
 pub async fn get_lawList_by_chapter(
     map: Arc<IndexMap<String, NewLaws>>,
+    tocs: Arc<HashMap<String, LawToc>>,
+    aliases: Arc<RwLock<LawAliases>>,
     chapter: Chapter,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    println!("{}：{}", chapter.chapter1, chapter.chapter2);
-    let mut laws =
-        map.get(&chapter.chapter1.to_string()).ok_or(LawError::NOThisChapter)
-            .map_err(|_| warp::reject::custom(handle_errors::Error::QuestionNotFound))?.to_owned();
-    laws.lines.sort_by(|a, b| {
-        to_f32(a.num.clone())
-            .partial_cmp(&to_f32(b.num.clone()))
-            .unwrap()
-    });
+    let chapter1 = aliases.read().unwrap().resolve(&chapter.chapter1).to_string();
+    let laws = map
+        .get(&chapter1)
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+
+    if let Some(toc) = tocs.get(&chapter1) {
+        let node_id = chapter
+            .node_id
+            .as_deref()
+            .or_else(|| toc.node_id(&chapter.chapter2));
+        if let Some(result) = node_id.and_then(|id| toc.laws_in(id, laws)) {
+            info!("獲取{chapter1}：{}共{}條", chapter.chapter2, result.len());
+            return Ok(warp::reply::json(&result));
+        }
+    }
+
+    // 不是完整的章節路徑時，沿用舊的部分比對
+    let mut laws = laws.to_owned();
+    new_law::article::sort_laws(&mut laws.lines);
     laws.lines.retain(|law| law.chapter.join("/").replace(" ", "").contains(&chapter.chapter2));
     Ok(warp::reply::json(&laws.lines))
 }
 
 pub async fn get_all_chapter(
     chapter: String,
-    map: Arc<IndexMap<String, NewLaws>>,
+    tocs: Arc<HashMap<String, LawToc>>,
+    aliases: Arc<RwLock<LawAliases>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let chapter = percent_decode_str(&chapter).decode_utf8_lossy();
-    let mut laws =
-        map.get(&chapter.to_string()).ok_or(LawError::NOThisChapter)
-            .map_err(|_| warp::reject::custom(handle_errors::Error::QuestionNotFound))?.to_owned();
-    laws.lines.sort_by(|a, b| {
-        to_f32(a.num.clone())
-            .partial_cmp(&to_f32(b.num.clone()))
-            .unwrap()
-    });
-    let s = laws.get_chapterUlList();
-    if s.is_ok() {
-        Ok(warp::reply::json(&s.unwrap()))
-    } else {
-        Err(warp::reject::custom(handle_errors::Error::QuestionNotFound))
-    }
+    let chapter = aliases.read().unwrap().resolve(&chapter).to_string();
+    let toc = tocs
+        .get(&chapter)
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+    Ok(warp::reply::json(&toc.chapter_ul_list()))
 }
 
-fn to_f32(s: String) -> f32 {
-    if s.contains("-") {
-        let (big, small) = s.split_once("-").unwrap();
-        let big_number: f32 = big.parse().unwrap();
-        let small_number: f32 = small.parse().unwrap();
-        big_number + small_number * 0.1
-    } else {
-        s.parse().unwrap()
+// 法規目錄：各章節的 node id、條號範圍與條數
+pub async fn get_law_toc(
+    name: String,
+    tocs: Arc<HashMap<String, LawToc>>,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let name = percent_decode_str(&name).decode_utf8_lossy();
+    let name = aliases.read().unwrap().resolve(&name).to_string();
+    match tocs.get(&name) {
+        Some(toc) => Ok(warp::reply::json(&toc.nodes)),
+        None => Err(warp::reject::custom(handle_errors::Error::QuestionNotFound)),
     }
 }
 
+pub async fn get_toc_node(
+    name: String,
+    node_id: String,
+    map: Arc<IndexMap<String, NewLaws>>,
+    tocs: Arc<HashMap<String, LawToc>>,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let name = percent_decode_str(&name).decode_utf8_lossy();
+    let name = aliases.read().unwrap().resolve(&name).to_string();
+    let laws = map
+        .get(&name)
+        .zip(tocs.get(&name))
+        .and_then(|(laws, toc)| toc.laws_in(&node_id, laws))
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+    Ok(warp::reply::json(&laws))
+}
+
 pub async fn get_all_chapters(map: Arc<IndexMap<String, NewLaws>>,) -> Result<impl warp::Reply, warp::Rejection> {
-    let mut s = String::new();
-    for key in map.keys().filter(|&chapter| chapter != "") {
-        let format_key = format!("<option value='{}'>", key);
-        s.push_str(&format_key);
+    let names = map.keys().filter(|&chapter| chapter != "");
+    Ok(warp::reply::html(crate::html::chapter_options(names)))
+}
+
+// 所有法規名稱，順序與 all_chapters 相同
+pub async fn get_law_names(map: Arc<IndexMap<String, NewLaws>>,) -> Result<impl warp::Reply, warp::Rejection> {
+    let names: Vec<&String> = map.keys().filter(|&chapter| chapter != "").collect();
+    Ok(warp::reply::json(&names))
+}
+
+pub async fn get_law_aliases(
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let list = aliases.read().unwrap().list();
+    Ok(warp::reply::json(&list))
+}
+
+pub async fn add_law_alias(
+    session: Session,
+    store: Store,
+    aliases: Arc<RwLock<LawAliases>>,
+    cache: Cache,
+    alias: LawAlias,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if alias.alias.trim().is_empty() || alias.name.trim().is_empty() {
+        return Err(warp::reject::custom(handle_errors::Error::MissingParameters));
+    }
+    let alias = LawAlias {
+        alias: alias.alias.trim().to_string(),
+        name: alias.name.trim().to_string(),
+    };
+    let res = store.add_law_alias(alias).await?;
+    aliases.write().unwrap().insert(res.clone());
+    info!("{} 新增簡稱：{} → {}", session.user_name, res.alias, res.name);
+    // 快取中的引用圖是用載入當下的簡稱建立的，需重新載入
+    cache.reload().await?;
+    Ok(warp::reply::json(&res))
+}
+
+pub async fn delete_law_alias(
+    alias: String,
+    session: Session,
+    store: Store,
+    aliases: Arc<RwLock<LawAliases>>,
+    cache: Cache,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let alias = percent_decode_str(&alias).decode_utf8_lossy().to_string();
+    store.delete_law_alias(&alias).await?;
+    // 刪除自訂簡稱後，若有同名的預設簡稱則恢復預設值
+    let list = {
+        let mut aliases = aliases.write().unwrap();
+        aliases.remove(&alias);
+        if let Some(builtin) = LawAliases::builtin()
+            .list()
+            .into_iter()
+            .find(|item| item.alias == alias)
+        {
+            aliases.insert(builtin);
+        }
+        aliases.list()
+    };
+    info!("{} 刪除簡稱：{}", session.user_name, alias);
+    cache.reload().await?;
+    Ok(warp::reply::json(&list))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn law(id: &str, content: &str) -> NewLaw {
+        let (chapter, num) = new_law::law_id::split(id).unwrap();
+        NewLaw {
+            id: id.to_string(),
+            href: String::new(),
+            chapter: vec![chapter.to_string()],
+            num: num.to_string(),
+            lines: vec![Line {
+                line_type: "normal".to_string(),
+                content: content.to_string(),
+            }],
+            provenance: Default::default(),
+        }
+    }
+
+    fn law_map() -> IndexMap<String, NewLaws> {
+        let mut map = IndexMap::new();
+        map.insert(
+            "民法".to_string(),
+            NewLaws {
+                lines: vec![law("民法-877", "本條"), law("民法-877-1", "之一")],
+            },
+        );
+        map
+    }
+
+    #[test]
+    fn current_version_of_sub_article() {
+        let map = law_map();
+        assert_eq!(
+            version_lines("current", "民法-877-1", &[], &map),
+            Some(vec!["之一".to_string()])
+        );
+        assert_eq!(version_lines("current", "民法-878-1", &[], &map), None);
+    }
+
+    #[test]
+    fn finds_sub_articles_by_id() {
+        let map = law_map();
+        let (index, found) = find_law(&map, "民法-877-1").unwrap();
+        assert_eq!((index, found.num.as_str()), (0, "877-1"));
+        assert_eq!(find_law(&map, "民法-877").unwrap().1.num, "877");
+        assert!(find_law(&map, "刑法-877-1").is_none());
     }
-    Ok(warp::reply::html(s))
 }
@@ -1,13 +1,18 @@
-use crate::store::Store;
-use crate::types::account::Redis_Database;
+use crate::storage::Store;
+use crate::types::account::{Redis_Database, Session};
 use crate::types::file::File;
-use crate::types::note::Note;
+use crate::types::note::{Note, StaleCard};
+use crate::types::pagination::{with_total, ListQuery};
 use bytes::BufMut;
 use futures::{StreamExt, TryStreamExt};
 use handle_errors::Error;
 use lol_html::element;
 use lol_html::{html_content::ContentType, HtmlRewriter, Settings};
-use note::{Block, InlineNode};
+use indexmap::IndexMap;
+use new_law::alias::LawAliases;
+use new_law::citation::CitationFinder;
+use new_law::NewLaws;
+use note::{Block, InlineNode, LinkSpan};
 #[allow(unused_imports)]
 use percent_encoding::percent_decode_str;
 use pulldown_cmark::{html, Options, Parser};
@@ -23,6 +28,7 @@ use std::fs;
 use std::hash::{Hash, Hasher};
 use std::path::Path;
 use std::process::{Command, Stdio};
+use std::sync::{Arc, RwLock};
 use std::time::Instant;
 use tokio::fs::File as OtherFile;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -35,14 +41,21 @@ pub async fn add_note(
     store: Store,
     note: crate::types::note::Note,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    // id 一律由伺服器產生，用戶端送來的舊式 id 不再使用
+    let note = Note {
+        id: Uuid::new_v4().to_string(),
+        slug: Note::make_slug(&note.user_name, &note.directory, &note.file_name),
+        ..note
+    };
     match store.add_note(note).await {
         Ok(note) => {
             info!("成功新增：{}", note.id);
-            let directory_id = format!("{}-{}", note.user_name, note.directory);
-            let dir = store.clone().get_directory(&directory_id).await?;
+            let dir = store
+                .get_directory_by_name(&note.user_name, &note.directory)
+                .await?;
             let mut oldorder = dir.note_order;
             oldorder.push(note.file_name.clone());
-            store.update_note_order(directory_id, oldorder).await?;
+            store.update_note_order(dir.id, oldorder).await?;
             Ok(warp::reply::json(&note))
         }
         Err(e) => Err(warp::reject::custom(e)),
@@ -60,6 +73,7 @@ pub async fn update_note_date(
     note_date: Note_Date,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy().to_string();
+    let id = store.resolve_note_id(&id).await?;
 
     println!("{}", note_date.date);
 
@@ -81,29 +95,60 @@ pub async fn update_note_date(
 
 pub async fn get_note_date(id: String, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy().to_string();
+    let id = store.resolve_note_id(&id).await?;
     let date = store.get_note_date(id).await?;
     Ok(warp::reply::json(&date.to_rfc3339()))
 }
 
 pub async fn delete_note(id: String, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy();
+    let id = store.resolve_note_id(&id).await?;
     match store.delete_note(&id).await {
         Ok(note) => {
+            // note_reference 由外鍵 ON DELETE CASCADE 一併刪除
             let message = format!("成功刪除：{}", note.id);
-            let directory_id = format!("{}-{}", note.user_name, note.directory);
-            let dir = store.clone().get_directory(&directory_id).await?;
+            let dir = store
+                .get_directory_by_name(&note.user_name, &note.directory)
+                .await?;
             let oldorder = dir.note_order;
             let neworder = oldorder
                 .into_iter()
                 .filter(|notename| notename != &note.file_name)
                 .collect::<Vec<String>>();
-            store.update_note_order(directory_id, neworder).await?;
+            store.update_note_order(dir.id, neworder).await?;
             Ok(warp::reply::with_status(message, StatusCode::OK))
         }
         Err(e) => Err(warp::reject::custom(e)),
     }
 }
 
+// 引用某條文的筆記：公開筆記，加上登入者自己的筆記
+pub async fn get_notes_by_law(
+    id: String,
+    session: Option<Session>,
+    store: Store,
+    aliases: Arc<RwLock<LawAliases>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let id = percent_decode_str(&id).decode_utf8_lossy();
+    let id = aliases.read().unwrap().resolve_law_id(&id);
+    let user_name = session.map(|s| s.user_name);
+    let notes = store.get_notes_by_reference("law", &id, user_name).await?;
+    Ok(warp::reply::json(&notes))
+}
+
+pub async fn get_notes_by_oldinterpretation(
+    id: String,
+    session: Option<Session>,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let id = percent_decode_str(&id).decode_utf8_lossy();
+    let user_name = session.map(|s| s.user_name);
+    let notes = store
+        .get_notes_by_reference("oldinterpretation", &id, user_name)
+        .await?;
+    Ok(warp::reply::json(&notes))
+}
+
 use flate2::read::GzDecoder;
 use flate2::{write::GzEncoder, Compression};
 use std::io::Read;
@@ -144,33 +189,31 @@ pub async fn set_gzip_json<T: serde::Serialize>(
     redis.set(key, compressed).await
 }
 
+// 編輯中、尚未寫回資料庫的版本；沒有 Redis 時（SQLite 模式）筆記直接寫入資料庫
+async fn get_editing_blocks(redis: &mut Option<ConnectionManager>, id: &str) -> Option<Vec<Block>> {
+    match redis {
+        Some(redis) => get_gzip_json(redis, id).await.ok(),
+        None => None,
+    }
+}
+
 pub async fn get_content(
     id: String,
     store: Store,
-    mut redis: ConnectionManager,
+    mut redis: Option<ConnectionManager>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy();
-    let redisResult: Result<Vec<Block>, redis::RedisError> = get_gzip_json(&mut redis, &id).await;
-    match redisResult {
-        Ok(block) => {
-            let parts: Vec<&str> = id.split("-").collect();
-            let writerName = parts[0];
-            let dirNmae = parts[1];
-            let noteName = parts[2];
-            let note = Note {
-                id: id.to_string(),
-                directory: dirNmae.to_string(),
-                user_name: writerName.to_string(),
-                footer: None,
-                content: Some(serde_json::to_value(&block).unwrap()),
-                file_name: noteName.to_string(),
-                public: true,
-            };
+    let id = store.resolve_note_id(&id).await?;
+    match get_editing_blocks(&mut redis, &id).await {
+        Some(block) => {
+            // 名稱等資料以資料庫為準，內容用 redis 中編輯中的版本
+            let mut note = store.get_note(id).await?;
+            note.content = Some(serde_json::to_value(&block).unwrap());
             return Ok(warp::reply::json(&note));
         }
-        Err(_) => {
+        None => {
             println!("not in redis");
-            match store.get_note(id.to_string()).await {
+            match store.get_note(id).await {
                 Ok(note) => {
                     println!("成功獲取：{}", note.id);
                     Ok(warp::reply::json(&note))
@@ -192,10 +235,25 @@ fn gzip_string(data: &str) -> Vec<u8> {
     encoder.finish().unwrap()
 }
 
+/// 收集筆記的引用，law 卡片的章節名稱先轉成完整法規名稱，與查詢時的 resolve_law_id 一致
+pub fn index_references(blocks: &[Block], aliases: &LawAliases) -> Vec<note::Reference> {
+    let mut refs = note::collect_references(blocks);
+    for r in refs.iter_mut().filter(|r| r.ref_type == "law") {
+        r.ref_id = aliases.resolve_law_id(&r.ref_id);
+    }
+    let mut seen = HashSet::new();
+    refs.retain(|r| seen.insert(r.clone()));
+    refs
+}
+
 pub async fn clean_redis(
     store: Store,
-    mut redis: ConnectionManager,
+    redis: Option<ConnectionManager>,
+    aliases: Arc<RwLock<LawAliases>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let Some(mut redis) = redis else {
+        return Ok(warp::reply::with_status("Redis Clean", StatusCode::OK));
+    };
     let idset: Vec<String> = redis
         .smembers("noteIdSet")
         .await
@@ -205,14 +263,18 @@ pub async fn clean_redis(
         let blocks: Vec<Block> = get_gzip_json(&mut redis, &id)
             .await
             .map_err(|e| handle_errors::Error::CacheError(e))?;
+        let refs = index_references(&blocks, &aliases.read().unwrap());
         let json = serde_json::to_value(blocks).map_err(|_| handle_errors::Error::TokenNotFound)?;
-        let note = store.update_the_note(json, id.clone()).await?;
+        // 集合中可能還有改用 uuid 之前的舊式 id
+        let note_id = store.resolve_note_id(&id).await?;
+        let note = store.update_the_note(json, note_id).await?;
+        store.update_note_references(&note.id, &refs).await?;
         let x: String = redis
-            .del(note.id.clone())
+            .del(id.clone())
             .await
             .map_err(|e| handle_errors::Error::CacheError(e))?;
         println!("ㄚㄚ{id}");
-        let _: () = redis.srem("noteIdSet", note.id.clone()).await.unwrap_or(());
+        let _: () = redis.srem("noteIdSet", id.clone()).await.unwrap_or(());
     }
     Ok(warp::reply::with_status("Redis Clean", StatusCode::OK))
 }
@@ -220,14 +282,50 @@ pub async fn clean_redis(
 use redis::pipe;
 use tracing_subscriber::fmt::format;
 
+// 把段落中的「民法第184條」、「釋字第748號」轉成 LawLink
+fn link_note_citations(
+    blocks: &mut [Block],
+    map: &IndexMap<String, NewLaws>,
+    aliases: &LawAliases,
+) {
+    let finder = CitationFinder::new(map.keys().map(|name| name.as_str()), aliases);
+    note::link_citations(blocks, |text| {
+        finder
+            .find(text)
+            .into_iter()
+            .map(|citation| LinkSpan {
+                start: citation.start,
+                end: citation.end,
+                link_type: citation.kind.as_str().to_string(),
+                law_id: citation.id,
+            })
+            .collect()
+    });
+}
+
 pub async fn update_content(
     id: String,
-    mut redis: ConnectionManager,
+    store: Store,
+    redis: Option<ConnectionManager>,
+    map: Arc<IndexMap<String, NewLaws>>,
+    aliases: Arc<RwLock<LawAliases>>,
     content: UpdateContent,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy();
+    let mut note = store.get_note(store.resolve_note_id(&id).await?).await?;
+    let id = note.id.clone();
     let content = update_nav(content.content);
-    let blocks = note::parse_note(&content);
+    let mut blocks = note::parse_note(&content);
+    link_note_citations(&mut blocks, &map, &aliases.read().unwrap());
+
+    let Some(mut redis) = redis else {
+        let refs = index_references(&blocks, &aliases.read().unwrap());
+        let note = store
+            .update_the_note(serde_json::to_value(&blocks).unwrap(), id)
+            .await?;
+        store.update_note_references(&note.id, &refs).await?;
+        return Ok(warp::reply::json(&note));
+    };
     let json = serde_json::to_string(&blocks).unwrap();
 
     let _ = redis
@@ -240,21 +338,7 @@ pub async fn update_content(
         .await
         .map_err(|e| warp::reject::custom(handle_errors::Error::CacheError(e)))?;
 
-    let parts: Vec<&str> = id.split("-").collect();
-    let writerName = parts[0];
-    let dirNmae = parts[1];
-    let noteName = parts[2];
-
-    let note = Note {
-        id: id.to_string(),
-        directory: dirNmae.to_string(),
-        user_name: writerName.to_string(),
-        footer: None,
-        content: Some(serde_json::to_value(blocks).unwrap()),
-        file_name: noteName.to_string(),
-        public: true,
-    };
-
+    note.content = Some(serde_json::to_value(blocks).unwrap());
     Ok(warp::reply::json(&note))
 }
 
@@ -262,15 +346,18 @@ pub async fn update_name(
     id: String,
     newname: String,
     store: Store,
-    mut redis: ConnectionManager,
+    mut redis: Option<ConnectionManager>,
+    aliases: Arc<RwLock<LawAliases>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy();
     let newname = percent_decode_str(&newname).decode_utf8_lossy();
 
-    let parts: Vec<&str> = id.split("-").collect();
-    let writerName = parts.get(0).unwrap_or(&"no");
-    let oldName = parts.get(2).unwrap_or(&"no");
-    let dirName = parts.get(1).unwrap_or(&"no");
+    let current = store.get_note(store.resolve_note_id(&id).await?).await?;
+    let id = current.id.clone();
+    let writerName = current.user_name.as_str();
+    let oldName = current.file_name.as_str();
+    let dirName = current.directory.as_str();
+    let newslug = Note::make_slug(writerName, dirName, &newname);
 
     match store.get_note_name_by_dir(writerName, dirName).await {
         Ok(names) => {
@@ -296,27 +383,28 @@ pub async fn update_name(
     // 3.如果沒有
     // 3.1直接往資料夾更新
     // 3.2將資料回傳
-    let redisResult: Result<Vec<Block>, redis::RedisError> = get_gzip_json(&mut redis, &id).await;
-    match redisResult {
-        Ok(block) => {
+    match get_editing_blocks(&mut redis, &id).await {
+        Some(block) => {
             // 2.1銷毀
-            redis
-                .del(id.clone())
-                .await
-                .map_err(|e| warp::reject::custom(handle_errors::Error::CacheError(e)))?;
+            if let Some(redis) = redis.as_mut() {
+                let _: () = redis
+                    .del(id.clone())
+                    .await
+                    .map_err(|e| warp::reject::custom(handle_errors::Error::CacheError(e)))?;
+            }
 
             // 2.2.1更新block
-            let oldnote = store
+            store
                 .update_the_note(serde_json::to_value(&block).unwrap(), id.to_string())
                 .await?;
-            let newid = format!("{}-{}-{newname}", oldnote.user_name, oldnote.directory);
-            //2.2.2更新筆記名
+            //2.2.2更新筆記名，id 不變，只更新 slug
             let note = store
-                .update_note_name(id.to_string(), newname.to_string(), newid)
+                .update_note_name(id.to_string(), newname.to_string(), newslug)
                 .await?;
+            let refs = index_references(&block, &aliases.read().unwrap());
+            store.update_note_references(&note.id, &refs).await?;
             //2.2.3更新成功後，更新order表
-            let dir_id = format!("{writerName}-{dirName}");
-            let dir = store.clone().get_directory(&dir_id).await?;
+            let dir = store.get_directory_by_name(writerName, dirName).await?;
             let new_order: Vec<String> = dir
                 .note_order
                 .iter()
@@ -328,16 +416,15 @@ pub async fn update_name(
                     }
                 })
                 .collect();
-            store.update_note_order(dir_id, new_order).await?;
+            store.update_note_order(dir.id, new_order).await?;
 
             Ok(warp::reply::json(&note))
         }
-        Err(_) => {
+        None => {
             info!("not in redis");
             //3.1更新筆記名
-            let newid = format!("{writerName}-{dirName}-{newname}");
             let note = store
-                .update_note_name(id.to_string(), newname.to_string(), newid)
+                .update_note_name(id.to_string(), newname.to_string(), newslug)
                 .await?;
 
             Ok(warp::reply::json(&note))
@@ -351,6 +438,7 @@ pub async fn update_state(
     store: Store,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy();
+    let id = store.resolve_note_id(&id).await?;
     let state = percent_decode_str(&state).decode_utf8_lossy().to_string();
     let public: bool;
 
@@ -364,15 +452,18 @@ pub async fn update_state(
         ));
     }
 
-    match store.update_note_state(id.to_string(), public).await {
+    match store.update_note_state(id, public).await {
         Ok(id) => Ok(warp::reply::Response::new(id.into())),
         Err(e) => Err(warp::reject::custom(e)),
     }
 }
 
-pub async fn get_every_note(store: Store) -> Result<impl warp::Reply, warp::Rejection> {
-    let notes = store.get_every_note().await?;
-    Ok(warp::reply::json(&notes))
+pub async fn get_every_note(
+    query: ListQuery,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (notes, total) = store.get_note_page(&query).await?;
+    Ok(with_total(&notes, total))
 }
 
 pub async fn get_note_list(
@@ -503,17 +594,19 @@ pub async fn get_pdf(
 
 pub async fn delete_file(id: String, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
     let id = percent_decode_str(&id).decode_utf8_lossy();
-    let res = match store.delete_note(&id.to_string()).await {
+    let id = store.resolve_note_id(&id).await?;
+    let res = match store.delete_note(&id).await {
         Ok(note) => {
             info!("成功刪除筆記：{}", note.id);
-            let directory_id = format!("{}-{}", note.user_name, note.directory);
-            let dir = store.clone().get_directory(&directory_id).await?;
+            let dir = store
+                .get_directory_by_name(&note.user_name, &note.directory)
+                .await?;
             let oldorder = dir.note_order;
             let neworder = oldorder
                 .into_iter()
                 .filter(|notename| notename != &note.file_name)
                 .collect::<Vec<String>>();
-            store.update_note_order(directory_id, neworder).await?;
+            store.update_note_order(dir.id, neworder).await?;
         }
         Err(e) => return Err(warp::reject::custom(e)),
     };
@@ -572,20 +665,20 @@ pub struct H3Nav {
 pub async fn get_note_nav(
     id: String,
     store: Store,
-    mut redis: ConnectionManager,
+    mut redis: Option<ConnectionManager>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let mut h2NavVec = Vec::new();
     let id = percent_decode_str(&id).decode_utf8_lossy();
+    let id = store.resolve_note_id(&id).await?;
     let mut blocks: Vec<Block>;
 
-    let redisResult: Result<Vec<Block>, redis::RedisError> = get_gzip_json(&mut redis, &id).await;
-    match redisResult {
-        Ok(block) => {
+    match get_editing_blocks(&mut redis, &id).await {
+        Some(block) => {
             blocks = block;
         }
-        Err(_) => {
+        None => {
             info!("not in redis");
-            match store.get_note(id.to_string()).await {
+            match store.get_note(id).await {
                 Ok(note) => {
                     blocks = from_value(note.content.into()).unwrap();
                 }
@@ -616,6 +709,9 @@ pub async fn get_note_nav(
                         InlineNode::Text { text, attributes } => {
                             vec.push(text);
                         }
+                        InlineNode::LawLink { text, .. } => {
+                            vec.push(text);
+                        }
                         _ => {}
                     }
                 }
@@ -638,6 +734,9 @@ pub async fn get_note_nav(
                         InlineNode::Text { text, attributes } => {
                             vec.push(text);
                         }
+                        InlineNode::LawLink { text, .. } => {
+                            vec.push(text);
+                        }
                         _ => {}
                     }
                 }
@@ -653,3 +752,360 @@ pub async fn get_note_nav(
     h2NavVec.push(buffer.clone());
     Ok(warp::reply::json(&h2NavVec))
 }
+
+// 忽略空白後比較，避免 parse_note 去掉空白造成誤判
+fn same_text(a: &str, b: &str) -> bool {
+    a.chars()
+        .filter(|c| !c.is_whitespace())
+        .eq(b.chars().filter(|c| !c.is_whitespace()))
+}
+
+fn find_stale_cards(
+    note_id: &str,
+    blocks: &[Block],
+    map: &IndexMap<String, NewLaws>,
+) -> Vec<StaleCard> {
+    let mut stale = Vec::new();
+    for (index, block) in blocks.iter().enumerate() {
+        let Some(card) = note::LawCard::from_block(block) else {
+            continue;
+        };
+        // 找不到法規時無從比對，直接略過
+        let Some(laws) = map.get(card.chapter.trim()) else {
+            continue;
+        };
+        let law_id = format!("{}-{}", card.chapter.trim(), card.num.trim());
+        let current_lines: Vec<String> = laws
+            .lines
+            .iter()
+            .find(|law| law.id == law_id)
+            .map(|law| law.lines.iter().map(|line| line.content.clone()).collect())
+            .unwrap_or_default();
+        let card_lines = card.text_lines();
+        let unchanged = card_lines.len() == current_lines.len()
+            && card_lines
+                .iter()
+                .zip(current_lines.iter())
+                .all(|(a, b)| same_text(a, b));
+        if !unchanged {
+            stale.push(StaleCard {
+                note_id: note_id.to_string(),
+                block_index: index as i32,
+                law_id,
+                card_lines,
+                current_lines,
+            });
+        }
+    }
+    stale
+}
+
+/// 定期比對所有筆記的 law 卡片與現行條文，回傳有過時卡片的筆記數
+pub async fn check_stale_cards(
+    store: Store,
+    map: Arc<IndexMap<String, NewLaws>>,
+) -> Result<usize, handle_errors::Error> {
+    let notes = store.get_every_note().await?;
+    let mut flagged = 0;
+    for note in notes {
+        let blocks: Vec<Block> = match note.content {
+            Some(content) => from_value(content).unwrap_or_default(),
+            None => continue,
+        };
+        let stale = find_stale_cards(&note.id, &blocks, &map);
+        if !stale.is_empty() {
+            flagged += 1;
+        }
+        store.replace_stale_cards(&note.id, &stale).await?;
+    }
+    info!("過時法條卡片檢查完成：{flagged} 篇筆記");
+    Ok(flagged)
+}
+
+// 公開筆記任何人都能查看，私人筆記只有作者本人
+pub async fn get_stale_cards(
+    id: String,
+    session: Option<Session>,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let id = percent_decode_str(&id).decode_utf8_lossy();
+    let note = store.get_note(store.resolve_note_id(&id).await?).await?;
+    let is_owner = session.map_or(false, |s| s.user_name == note.user_name);
+    if !note.public && !is_owner {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+    let cards = store.get_stale_cards(&note.id).await?;
+    Ok(warp::reply::json(&cards))
+}
+
+/// 將指定位置的 law 卡片更新為現行條文，舊內容存入 note_revision
+pub async fn refresh_law_card(
+    id: String,
+    index: usize,
+    session: Session,
+    store: Store,
+    mut redis: Option<ConnectionManager>,
+    map: Arc<IndexMap<String, NewLaws>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let id = percent_decode_str(&id).decode_utf8_lossy().to_string();
+    let mut note = store.get_note(store.resolve_note_id(&id).await?).await?;
+    if note.user_name != session.user_name {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+    let id = note.id.clone();
+
+    // 編輯中的筆記以 redis 內的版本為準
+    let editing = get_editing_blocks(&mut redis, &id).await;
+    let in_redis = editing.is_some();
+    let mut blocks = match editing {
+        Some(blocks) => blocks,
+        None => note
+            .content
+            .take()
+            .map(|content| from_value(content).unwrap_or_default())
+            .unwrap_or_default(),
+    };
+
+    let mut card = blocks
+        .get(index)
+        .and_then(note::LawCard::from_block)
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+    let law_id = format!("{}-{}", card.chapter.trim(), card.num.trim());
+    let law = map
+        .get(card.chapter.trim())
+        .and_then(|laws| laws.lines.iter().find(|law| law.id == law_id))
+        .ok_or(warp::reject::custom(handle_errors::Error::QuestionNotFound))?;
+
+    store
+        .add_note_revision(&id, serde_json::to_value(&blocks).unwrap(), "refresh_law_card")
+        .await?;
+
+    card.lines = law
+        .lines
+        .iter()
+        .map(|line| {
+            let class = if line.line_type == "indent" {
+                "law-indent"
+            } else {
+                "law-block-line"
+            };
+            note::Line {
+                line_type: line.line_type.clone(),
+                attributes: Some(note::Attributes {
+                    id: None,
+                    class: Some(class.to_string()),
+                    style: None,
+                    src: None,
+                    width: None,
+                    height: None,
+                }),
+                children: vec![InlineNode::Text {
+                    text: line.content.clone(),
+                    attributes: None,
+                }],
+            }
+        })
+        .collect();
+    blocks[index] = card.to_block();
+
+    let json = serde_json::to_value(&blocks).unwrap();
+    let note = match redis.as_mut().filter(|_| in_redis) {
+        Some(redis) => {
+            let compressed = gzip_string(&json.to_string());
+            let _: () = redis
+                .set(&id, &compressed)
+                .await
+                .map_err(|e| warp::reject::custom(handle_errors::Error::CacheError(e)))?;
+            note.content = Some(json);
+            note
+        }
+        None => store.update_the_note(json, id.clone()).await?,
+    };
+
+    store
+        .replace_stale_cards(&id, &find_stale_cards(&id, &blocks, &map))
+        .await?;
+    info!("成功更新法條卡片：{} {}", id, law_id);
+    Ok(warp::reply::json(&note))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::sqlite::SqliteStore;
+    use crate::storage::Storage;
+    use crate::types::directory::Directory;
+    use sqlx::Row;
+
+    fn laws(items: &[(&str, &[&str])]) -> Arc<IndexMap<String, NewLaws>> {
+        let mut map: IndexMap<String, NewLaws> = IndexMap::new();
+        for (id, lines) in items {
+            let (chapter, num) = new_law::law_id::split(id).unwrap();
+            map.entry(chapter.to_string())
+                .or_insert(NewLaws { lines: Vec::new() })
+                .lines
+                .push(new_law::NewLaw {
+                    id: id.to_string(),
+                    href: String::new(),
+                    chapter: vec![chapter.to_string()],
+                    num: num.to_string(),
+                    lines: lines
+                        .iter()
+                        .map(|content| new_law::Line {
+                            line_type: "normal".to_string(),
+                            content: content.to_string(),
+                        })
+                        .collect(),
+                    provenance: Default::default(),
+                });
+        }
+        Arc::new(map)
+    }
+
+    fn card(chapter: &str, num: &str, lines: &[&str]) -> Block {
+        note::LawCard {
+            chapter: chapter.to_string(),
+            num: num.to_string(),
+            lines: lines
+                .iter()
+                .map(|text| note::Line {
+                    line_type: "normal".to_string(),
+                    attributes: None,
+                    children: vec![InlineNode::Text {
+                        text: text.to_string(),
+                        attributes: None,
+                    }],
+                })
+                .collect(),
+        }
+        .to_block()
+    }
+
+    #[test]
+    fn flags_cards_whose_law_text_changed() {
+        let map = laws(&[
+            (
+                "民法-184",
+                &["因故意或過失，不法侵害他人之權利者，負損害賠償責任。"],
+            ),
+            (
+                "民法-185",
+                &["數人共同不法侵害他人之權利者，連帶負損害賠償責任。"],
+            ),
+        ]);
+        let blocks = vec![
+            card("民法", "184", &["因故意或過失，不法侵害他人之權利者。"]),
+            // 只差空白不算變動
+            card(
+                "民法",
+                "185",
+                &["數人共同不法侵害他人之權利者， 連帶負損害賠償責任。"],
+            ),
+        ];
+        let stale = find_stale_cards("n1", &blocks, &map);
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].block_index, 0);
+        assert_eq!(stale[0].law_id, "民法-184");
+        assert_eq!(
+            stale[0].current_lines,
+            vec!["因故意或過失，不法侵害他人之權利者，負損害賠償責任。".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_rewrites_card_and_records_revision() {
+        let store = SqliteStore::memory().await;
+        store
+            .add_directory(Directory {
+                id: "d1".to_string(),
+                slug: Directory::make_slug("alice", "民法"),
+                user_name: "alice".to_string(),
+                directory: "民法".to_string(),
+                public: false,
+                description: String::new(),
+                note_order: Vec::new(),
+            })
+            .await
+            .unwrap();
+        let old = vec![card(
+            "民法",
+            "184",
+            &["因故意或過失，不法侵害他人之權利者。"],
+        )];
+        store
+            .add_note(Note {
+                id: "n1".to_string(),
+                slug: Note::make_slug("alice", "民法", "侵權"),
+                content: Some(serde_json::to_value(&old).unwrap()),
+                footer: None,
+                user_name: "alice".to_string(),
+                directory: "民法".to_string(),
+                file_name: "侵權".to_string(),
+                public: false,
+            })
+            .await
+            .unwrap();
+        let current = "因故意或過失，不法侵害他人之權利者，負損害賠償責任。";
+        let map = laws(&[("民法-184", &[current])]);
+        let session = Session {
+            exp: chrono::Utc::now() + chrono::Duration::days(1),
+            user_name: "alice".to_string(),
+            nbf: chrono::Utc::now(),
+        };
+
+        refresh_law_card(
+            "n1".to_string(),
+            0,
+            session,
+            Arc::new(store.clone()),
+            None,
+            map.clone(),
+        )
+        .await
+        .unwrap();
+
+        let note = store.get_note("n1".to_string()).await.unwrap();
+        let blocks: Vec<Block> = from_value(note.content.unwrap()).unwrap();
+        let refreshed = note::LawCard::from_block(&blocks[0]).unwrap();
+        assert_eq!(refreshed.text_lines(), vec![current.to_string()]);
+        assert!(find_stale_cards("n1", &blocks, &map).is_empty());
+
+        let revisions = sqlx::query("SELECT content FROM note_revision WHERE note_id = $1")
+            .bind("n1")
+            .fetch_all(&store.connection)
+            .await
+            .unwrap();
+        assert_eq!(revisions.len(), 1);
+        let saved: String = revisions[0].get("content");
+        let saved: Vec<Block> = serde_json::from_str(&saved).unwrap();
+        assert_eq!(
+            note::LawCard::from_block(&saved[0]).unwrap().text_lines(),
+            vec!["因故意或過失，不法侵害他人之權利者。".to_string()]
+        );
+    }
+
+    #[test]
+    fn index_references_resolves_card_aliases() {
+        let link = InlineNode::LawLink {
+            text: "中華民國刑法第2條".to_string(),
+            link_type: "law".to_string(),
+            law_id: "中華民國刑法-2".to_string(),
+            attributes: None,
+        };
+        let blocks = vec![
+            card("刑法", "2", &["行為後法律有變更者"]),
+            Block::Paragraph {
+                attributes: None,
+                children: vec![link],
+            },
+        ];
+        let refs = index_references(&blocks, &LawAliases::builtin());
+        assert_eq!(
+            refs,
+            vec![note::Reference {
+                ref_type: "law".to_string(),
+                ref_id: "中華民國刑法-2".to_string(),
+            }]
+        );
+    }
+}
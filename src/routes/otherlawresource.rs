@@ -1,8 +1,10 @@
-use crate::store::Store;
+use crate::storage::Store;
+use crate::types::pagination::{with_total, ListQuery};
 use indexmap::IndexMap;
+use new_law::alias::LawAliases;
 use otherlawresource::{OldInterpretation, OtherSourceList, Precedent, Resolution};
 use percent_encoding::percent_decode_str;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 pub async fn get_newinter(store: Store) -> Result<impl warp::Reply, warp::Rejection> {
     match store.get_newinterpretations().await {
@@ -12,10 +14,27 @@ pub async fn get_newinter(store: Store) -> Result<impl warp::Reply, warp::Reject
 }
 
 pub async fn get_newinter_list(
+    query: ListQuery,
     vec: Arc<Vec<otherlawresource::NewInter>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let list: Vec<_> = vec
+    let mut items: Vec<_> = vec
         .iter()
+        .filter(|item| query.year.map_or(true, |year| item.year == year))
+        .collect();
+    // 快取的順序不一定是升冪，指定 order 時先依排序鍵排好再決定方向
+    if let Some(key) = query.sort_key(&["year", "date", "id"], "year") {
+        items.sort_by(|a, b| match key {
+            "date" => a.date.cmp(&b.date),
+            "id" => a.id.cmp(&b.id),
+            _ => (a.year, a.number).cmp(&(b.year, b.number)),
+        });
+        if query.descending() {
+            items.reverse();
+        }
+    }
+    let (items, total) = query.paginate(items);
+    let list: Vec<_> = items
+        .into_iter()
         .map(|item| {
             let name = format!("{}憲判{}", item.year, item.number);
             otherlawresource::OtherSourceList {
@@ -26,7 +45,7 @@ pub async fn get_newinter_list(
         })
         .collect();
 
-    Ok(warp::reply::json(&list))
+    Ok(with_total(&list, total))
 }
 
 pub async fn get_newinter_by_id(
@@ -48,10 +67,23 @@ pub async fn get_oldinter(store: Store) -> Result<impl warp::Reply, warp::Reject
 }
 
 pub async fn get_oldinter_list(
+    query: ListQuery,
     vec: Arc<Vec<OldInterpretation>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let list: Vec<_> = vec
-        .iter()
+    let mut items: Vec<_> = vec.iter().collect();
+    if let Some(key) = query.sort_key(&["id", "date"], "id") {
+        let number = |item: &OldInterpretation| item.id.parse::<u32>().unwrap_or(0);
+        items.sort_by(|a, b| match key {
+            "date" => (&a.date, number(a)).cmp(&(&b.date, number(b))),
+            _ => number(a).cmp(&number(b)),
+        });
+        if query.descending() {
+            items.reverse();
+        }
+    }
+    let (items, total) = query.paginate(items);
+    let list: Vec<_> = items
+        .into_iter()
         .map(|item| OtherSourceList {
             id: item.id.clone(),
             name: format!("釋字{}", item.id.clone()),
@@ -59,7 +91,7 @@ pub async fn get_oldinter_list(
         })
         .collect();
 
-    Ok(warp::reply::json(&list))
+    Ok(with_total(&list, total))
 }
 
 pub async fn get_oldinter_by_id(
@@ -74,10 +106,29 @@ pub async fn get_oldinter_by_id(
 }
 
 pub async fn get_precedent_list(
+    query: ListQuery,
     vec: Arc<Vec<Precedent>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let list: Vec<_> = vec
+    let mut items: Vec<_> = vec
         .iter()
+        .filter(|item| query.year.map_or(true, |year| item.year == year))
+        .filter(|item| query.kind.is_none() || item.case_type == query.kind)
+        .filter(|item| query.court.is_none() || item.court == query.court)
+        .collect();
+    if let Some(key) = query.sort_key(&["year", "court", "type", "id"], "year") {
+        items.sort_by(|a, b| match key {
+            "court" => (&a.court, a.year, a.num).cmp(&(&b.court, b.year, b.num)),
+            "type" => (&a.case_type, a.year, a.num).cmp(&(&b.case_type, b.year, b.num)),
+            "id" => a.id.cmp(&b.id),
+            _ => (a.year, a.num).cmp(&(b.year, b.num)),
+        });
+        if query.descending() {
+            items.reverse();
+        }
+    }
+    let (items, total) = query.paginate(items);
+    let list: Vec<_> = items
+        .into_iter()
         .map(|item| OtherSourceList {
             id: item.id.clone(),
             name: item.name.clone(),
@@ -85,7 +136,7 @@ pub async fn get_precedent_list(
         })
         .collect();
 
-    Ok(warp::reply::json(&list))
+    Ok(with_total(&list, total))
 }
 
 // GET /precedent/{id}
@@ -115,10 +166,27 @@ pub async fn get_resolutions(store: Store) -> Result<impl warp::Reply, warp::Rej
 }
 
 pub async fn get_resolution_list(
+    query: ListQuery,
     vec: Arc<Vec<Resolution>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-    let list: Vec<_> = vec
+    let mut items: Vec<_> = vec
         .iter()
+        .filter(|item| query.year.map_or(true, |year| item.year == year))
+        .filter(|item| query.kind.as_ref().map_or(true, |kind| &item.lawtype == kind))
+        .collect();
+    if let Some(key) = query.sort_key(&["year", "type", "id"], "year") {
+        items.sort_by(|a, b| match key {
+            "type" => (&a.lawtype, a.year, a.time).cmp(&(&b.lawtype, b.year, b.time)),
+            "id" => a.id.cmp(&b.id),
+            _ => (a.year, a.time).cmp(&(b.year, b.time)),
+        });
+        if query.descending() {
+            items.reverse();
+        }
+    }
+    let (items, total) = query.paginate(items);
+    let list: Vec<_> = items
+        .into_iter()
         .map(|item| OtherSourceList {
             id: item.id.clone(),
             name: item.name.clone(),
@@ -126,7 +194,7 @@ pub async fn get_resolution_list(
         })
         .collect();
 
-    Ok(warp::reply::json(&list))
+    Ok(with_total(&list, total))
 }
 
 pub async fn get_resolution_by_id(
@@ -142,6 +210,7 @@ pub async fn get_resolution_by_id(
 
 pub async fn get_all_lawname_list(
     map: Arc<IndexMap<String, new_law::NewLaws>>,
+    aliases: Arc<RwLock<LawAliases>>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let mut buffer = Vec::new();
     for name in map.keys() {
@@ -151,6 +220,16 @@ pub async fn get_all_lawname_list(
             sourcetype: "lawname".to_string(),
         })
     }
+    // 簡稱也列入搜尋清單，id 指向完整的法規名稱
+    for alias in aliases.read().unwrap().list() {
+        if map.contains_key(&alias.name) {
+            buffer.push(OtherSourceList {
+                id: alias.name,
+                name: alias.alias,
+                sourcetype: "lawalias".to_string(),
+            })
+        }
+    }
     Ok(warp::reply::json(&buffer))
 }
 
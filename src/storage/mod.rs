@@ -0,0 +1,338 @@
+use crate::types::account::Account;
+use crate::types::dictionary::{Dictionary, VocabItem, VocabItemLaw};
+use crate::types::directory::Directory;
+use crate::types::file::{File, Files};
+use crate::types::note::{Note, StaleCard};
+use crate::types::pagination::ListQuery;
+use crate::types::sync_run::SyncRun;
+use crate::types::Library::{Library, LibraryItem};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::BoxStream;
+use std::sync::Arc;
+
+pub mod sqlite;
+
+/// 資料存取的介面，PostgreSQL（store::PgStore）與 SQLite（sqlite::SqliteStore）各自實作，
+/// 由 setup.toml 的 storage 選擇
+pub type Store = Arc<dyn Storage>;
+
+#[async_trait]
+pub trait Storage: Send + Sync {
+    // 法規全文，依寫入順序
+    async fn get_new_laws(&self) -> Result<new_law::NewLaws, handle_errors::Error>;
+
+    async fn add_file(&self, file: File) -> Result<File, handle_errors::Error>;
+
+    async fn get_every_file(&self) -> Result<Files, handle_errors::Error>;
+
+    async fn get_every_folder(
+        &self,
+    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error>;
+
+    async fn get_every_note(&self) -> Result<Vec<Note>, handle_errors::Error>;
+
+    async fn get_note_page(
+        &self,
+        query: &ListQuery,
+    ) -> Result<(Vec<Note>, i64), handle_errors::Error>;
+
+    async fn get_file_user(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Files, handle_errors::Error>;
+
+    async fn get_file(&self, id: String) -> Result<File, handle_errors::Error>;
+
+    async fn update_file_name(
+        &self,
+        id: String,
+        file_name: String,
+        new_id: String,
+    ) -> Result<File, handle_errors::Error>;
+
+    async fn update_content_and_css(
+        &self,
+        id: String,
+        content: String,
+        css: String,
+        nav: String,
+    ) -> Result<File, handle_errors::Error>;
+
+    async fn delete_file(&self, id: String) -> Result<File, handle_errors::Error>;
+
+    async fn delete_directory(&self, id: String) -> Result<Directory, handle_errors::Error>;
+
+    async fn update_directory(
+        &self,
+        public: bool,
+        description: String,
+        id: String,
+    ) -> Result<Directory, handle_errors::Error>;
+
+    async fn update_note_name(
+        &self,
+        id: String,
+        file_name: String,
+        slug: String,
+    ) -> Result<Note, handle_errors::Error>;
+
+    async fn update_note_state(
+        &self,
+        id: String,
+        public: bool,
+    ) -> Result<String, handle_errors::Error>;
+
+    async fn get_note_user(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Vec<Note>, handle_errors::Error>;
+
+    async fn get_note_name_by_dir(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Vec<String>, handle_errors::Error>;
+
+    async fn get_notelist_user(
+        &self,
+    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error>;
+
+    async fn update_the_note(
+        &self,
+        content: serde_json::Value,
+        id: String,
+    ) -> Result<Note, handle_errors::Error>;
+
+    async fn get_note(&self, id: String) -> Result<Note, handle_errors::Error>;
+
+    async fn get_note_date(
+        &self,
+        id: String,
+    ) -> Result<chrono::DateTime<Utc>, handle_errors::Error>;
+
+    async fn update_note_order(
+        &self,
+        dir_id: String,
+        order_list: Vec<String>,
+    ) -> Result<String, handle_errors::Error>;
+
+    async fn update_note_date(
+        &self,
+        id: String,
+        date: chrono::DateTime<Utc>,
+    ) -> Result<String, handle_errors::Error>;
+
+    async fn delete_note(&self, id: &str) -> Result<Note, handle_errors::Error>;
+
+    async fn add_note(&self, note: Note) -> Result<Note, handle_errors::Error>;
+
+    async fn add_directory(&self, directory: Directory) -> Result<Directory, handle_errors::Error>;
+
+    async fn get_directory(&self, id: &str) -> Result<Directory, handle_errors::Error>;
+
+    async fn get_directory_by_name(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Directory, handle_errors::Error>;
+
+    // 網址中的 id 可能是 uuid，也可能是舊版的 user_name-directory，一律換成 uuid
+    async fn resolve_directory_id(&self, id: &str) -> Result<String, handle_errors::Error>;
+
+    // 同上，舊版的 id 為 user_name-directory-file_name
+    async fn resolve_note_id(&self, id: &str) -> Result<String, handle_errors::Error>;
+
+    async fn get_directory_user(
+        &self,
+        user_name: &str,
+    ) -> Result<Vec<Directory>, handle_errors::Error>;
+
+    async fn get_directory_pub(
+        &self,
+        query: &ListQuery,
+    ) -> Result<(Vec<Directory>, i64), handle_errors::Error>;
+
+    async fn add_account(&self, account: Account) -> Result<bool, handle_errors::Error>;
+
+    async fn get_account(&self, email: String) -> Result<Account, handle_errors::Error>;
+
+    async fn get_newinterpretations(
+        &self,
+    ) -> Result<Vec<otherlawresource::NewInter>, handle_errors::Error>;
+
+    async fn get_newinterpretation_by_id(
+        &self,
+        id: String,
+    ) -> Result<otherlawresource::NewInter, handle_errors::Error>;
+
+    async fn get_all_resolution(
+        &self,
+    ) -> Result<Vec<otherlawresource::Resolution>, handle_errors::Error>;
+
+    async fn get_resolution_by_id(
+        &self,
+        id: String,
+    ) -> Result<otherlawresource::Resolution, handle_errors::Error>;
+
+    async fn get_all_oldinterpretation(
+        &self,
+    ) -> Result<Vec<otherlawresource::OldInterpretation>, handle_errors::Error>;
+
+    async fn get_oldinter_by_id(
+        &self,
+        id: String,
+    ) -> Result<otherlawresource::OldInterpretation, handle_errors::Error>;
+
+    async fn get_all_precedents(
+        &self,
+    ) -> Result<Vec<otherlawresource::Precedent>, handle_errors::Error>;
+
+    async fn get_precedent_by_id(
+        &self,
+        id: String,
+    ) -> Result<otherlawresource::Precedent, handle_errors::Error>;
+
+    async fn get_historylaw(
+        &self,
+        lawid: String,
+    ) -> Result<Vec<otherlawresource::HistoryLaw>, handle_errors::Error>;
+
+    // 整部法規的沿革，lawid 形如「民法-184」
+    async fn get_historylaw_by_chapter(
+        &self,
+        chapter: &str,
+    ) -> Result<Vec<otherlawresource::HistoryLaw>, handle_errors::Error>;
+
+    async fn get_law_aliases(&self) -> Result<Vec<new_law::alias::LawAlias>, handle_errors::Error>;
+
+    async fn add_law_alias(
+        &self,
+        alias: new_law::alias::LawAlias,
+    ) -> Result<new_law::alias::LawAlias, handle_errors::Error>;
+
+    async fn delete_law_alias(&self, alias: &str) -> Result<(), handle_errors::Error>;
+
+    // 先清掉該筆記原有的引用，再整批寫入
+    async fn update_note_references(
+        &self,
+        note_id: &str,
+        refs: &[note::Reference],
+    ) -> Result<(), handle_errors::Error>;
+
+    // 只回傳公開筆記，或 user_name 本人的筆記；不帶 content
+    async fn get_notes_by_reference(
+        &self,
+        ref_type: &str,
+        ref_id: &str,
+        user_name: Option<String>,
+    ) -> Result<Vec<Note>, handle_errors::Error>;
+
+    // 每次檢查都以新的結果覆蓋該筆記的標記
+    async fn replace_stale_cards(
+        &self,
+        note_id: &str,
+        cards: &[StaleCard],
+    ) -> Result<(), handle_errors::Error>;
+
+    async fn get_stale_cards(&self, note_id: &str) -> Result<Vec<StaleCard>, handle_errors::Error>;
+
+    async fn add_note_revision(
+        &self,
+        note_id: &str,
+        content: serde_json::Value,
+        reason: &str,
+    ) -> Result<(), handle_errors::Error>;
+
+    async fn add_library(&self, library: Library) -> Result<Library, handle_errors::Error>;
+
+    async fn add_library_item(
+        &self,
+        item: LibraryItem,
+    ) -> Result<LibraryItem, handle_errors::Error>;
+
+    async fn get_library_user(&self, user_name: &str)
+        -> Result<Vec<Library>, handle_errors::Error>;
+
+    async fn get_item_by_library(
+        &self,
+        library_id: &str,
+    ) -> Result<Vec<LibraryItem>, handle_errors::Error>;
+
+    async fn delete_dictionary(&self, id: &str) -> Result<Dictionary, handle_errors::Error>;
+
+    async fn add_dictionary(
+        &self,
+        user_name: &str,
+        dictionary_name: &str,
+        id: &str,
+    ) -> Result<Dictionary, handle_errors::Error>;
+
+    async fn get_dictionary(&self, id: &str) -> Result<Dictionary, handle_errors::Error>;
+
+    async fn get_dictionary_by_user(
+        &self,
+        user_name: &str,
+    ) -> Result<Vec<Dictionary>, handle_errors::Error>;
+
+    async fn get_vocabitem_user(
+        &self,
+        user_name: &str,
+        query: &ListQuery,
+    ) -> Result<(Vec<VocabItem>, i64), handle_errors::Error>;
+
+    async fn get_vocabitem_dictionary(
+        &self,
+        dictionary: &str,
+    ) -> Result<Vec<VocabItem>, handle_errors::Error>;
+
+    async fn get_vocabitem_term(&self, term: &str) -> Result<Vec<VocabItem>, handle_errors::Error>;
+
+    async fn get_vocabitem_def(&self, def: &str) -> Result<Vec<VocabItem>, handle_errors::Error>;
+
+    async fn add_vocabitem(&self, item: VocabItem) -> Result<VocabItem, handle_errors::Error>;
+
+    async fn delete_vocabitem(&self, id: &str) -> Result<VocabItem, handle_errors::Error>;
+
+    async fn update_vocabitem(&self, item: VocabItem) -> Result<VocabItem, handle_errors::Error>;
+
+    async fn add_vocabitem_law(
+        &self,
+        item: VocabItemLaw,
+    ) -> Result<VocabItemLaw, handle_errors::Error>;
+
+    async fn get_vocabitems_by_law_id(
+        &self,
+        law_id: &str,
+    ) -> Result<Vec<VocabItem>, handle_errors::Error>;
+
+    async fn get_law_ids_by_vocabitem(
+        &self,
+        vocabitem_id: &str,
+    ) -> Result<Vec<String>, handle_errors::Error>;
+
+    async fn get_laws_by_vocabitem(
+        &self,
+        item_id: &str,
+    ) -> Result<Vec<new_law::NewLaw>, handle_errors::Error>;
+
+    async fn delete_vocabitem_law(
+        &self,
+        item_id: &str,
+        law_id: &str,
+    ) -> Result<(), handle_errors::Error>;
+
+    // ?type=laws 只看該來源，預設最新的在前
+    async fn get_sync_runs(
+        &self,
+        query: &ListQuery,
+    ) -> Result<(Vec<SyncRun>, i64), handle_errors::Error>;
+
+    // 依主鍵排序逐筆讀出，dataset 不在 otherlawresource::export::DATASETS 中時回 DatasetNotFound
+    fn export_rows(
+        &self,
+        dataset: &str,
+    ) -> BoxStream<'_, Result<serde_json::Value, handle_errors::Error>>;
+}
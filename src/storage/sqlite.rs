@@ -0,0 +1,2130 @@
+use super::Storage;
+use crate::types::account::Account;
+use crate::types::dictionary::{Dictionary, VocabItem, VocabItemLaw};
+use crate::types::directory::Directory;
+use crate::types::file::{File, Files};
+use crate::types::note::{Note, StaleCard};
+use crate::types::pagination::ListQuery;
+use crate::types::sync_run::SyncRun;
+use crate::types::Library::{Library, LibraryItem};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use new_law::provenance::Provenance;
+use otherlawresource::export::{self, Manifest, MANIFEST};
+use serde_json::Value;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::types::Json;
+use sqlx::{Column, Row};
+use std::error::Error;
+use std::path::Path;
+
+// 匯出時這些欄位還原成 JSON 陣列，與 PostgreSQL 匯出的格式相同
+const JSON_COLUMNS: [&str; 7] = [
+    "chapter",
+    "lines",
+    "maincontent",
+    "reflaws",
+    "reflawid",
+    "refinter",
+    "refprecedent",
+];
+
+/// 單一檔案的資料庫，供離線閱讀與本機開發
+#[derive(Clone)]
+pub struct SqliteStore {
+    pub connection: SqlitePool,
+}
+
+impl SqliteStore {
+    /// 檔案不存在時建立新檔，並建立缺少的資料表
+    pub async fn new(path: &str) -> Self {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let db_pool = match SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await
+        {
+            Ok(pool) => pool,
+            Err(e) => panic!("無法開啟 SQLite 檔案 {path}：{e}"),
+        };
+        Self::with_pool(db_pool).await
+    }
+
+    // 記憶體資料庫每個連線各自獨立，只開一個連線
+    #[cfg(test)]
+    pub async fn memory() -> Self {
+        use std::str::FromStr;
+        let options = SqliteConnectOptions::from_str("sqlite::memory:").unwrap();
+        let db_pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        Self::with_pool(db_pool).await
+    }
+
+    async fn with_pool(db_pool: SqlitePool) -> Self {
+        if let Err(e) = sqlx::migrate!("./migrations/sqlite").run(&db_pool).await {
+            panic!("建立 SQLite 資料表失敗：{e}");
+        }
+        SqliteStore {
+            connection: db_pool,
+        }
+    }
+
+    /// 還沒有任何法規時，匯入 otherlawresource export 指令的輸出，回傳新增的筆數
+    pub async fn seed_from_export(&self, dir: &Path) -> Result<u64, Box<dyn Error>> {
+        let count: i64 = sqlx::query("SELECT COUNT(*) FROM newlaw")
+            .fetch_one(&self.connection)
+            .await?
+            .get(0);
+        if count > 0 {
+            return Ok(0);
+        }
+        let manifest: Manifest =
+            serde_json::from_str(&std::fs::read_to_string(dir.join(MANIFEST))?)?;
+        let mut total = 0;
+        for entry in &manifest.datasets {
+            if !export::is_dataset(&entry.name) {
+                continue;
+            }
+            let jsonl = std::fs::read_to_string(dir.join(format!("{}.jsonl", entry.name)))?;
+            entry.verify(&jsonl)?;
+            // 只寫入 SQLite 資料表有的欄位
+            let columns: Vec<String> = sqlx::query(&format!(
+                "SELECT name FROM pragma_table_info('{}')",
+                entry.name
+            ))
+            .map(|row: SqliteRow| row.get("name"))
+            .fetch_all(&self.connection)
+            .await?;
+
+            let mut inserted = 0;
+            let mut tx = self.connection.begin().await?;
+            for line in jsonl.lines().filter(|line| !line.is_empty()) {
+                let Value::Object(row) = serde_json::from_str::<Value>(line)? else {
+                    continue;
+                };
+                let row: Vec<(&String, &Value)> = row
+                    .iter()
+                    .filter(|(column, _)| columns.contains(column))
+                    .collect();
+                let names: Vec<&str> = row.iter().map(|(column, _)| column.as_str()).collect();
+                let params: Vec<String> = (1..=row.len()).map(|i| format!("${i}")).collect();
+                let sql = format!(
+                    "INSERT OR IGNORE INTO {} ({}) VALUES ({})",
+                    entry.name,
+                    names.join(", "),
+                    params.join(", ")
+                );
+                let mut query = sqlx::query(&sql);
+                for (_, value) in &row {
+                    query = match value {
+                        Value::Null => query.bind(None::<String>),
+                        Value::Bool(b) => query.bind(*b),
+                        Value::Number(n) => match n.as_i64() {
+                            Some(n) => query.bind(n),
+                            None => query.bind(n.as_f64()),
+                        },
+                        Value::String(s) => query.bind(s.clone()),
+                        other => query.bind(other.to_string()),
+                    };
+                }
+                inserted += query.execute(&mut tx).await?.rows_affected();
+            }
+            tx.commit().await?;
+            println!("匯入 {} {} 筆", entry.name, inserted);
+            total += inserted;
+        }
+        Ok(total)
+    }
+}
+
+// 舊資料庫可能還沒有引用欄位，讀不到時視為沒有引用
+fn json_list(row: &SqliteRow, column: &str) -> Option<Vec<String>> {
+    row.try_get::<Option<Json<Vec<String>>>, _>(column)
+        .ok()
+        .flatten()
+        .map(|list| list.0)
+}
+
+fn to_file(row: SqliteRow) -> File {
+    File {
+        id: row.get("id"),
+        content: row.get("content"),
+        css: row.get("css"),
+        user_name: row.get("user_name"),
+        directory: row.get("directory"),
+        file_name: row.get("file_name"),
+        content_nav: row.get("content_nav"),
+    }
+}
+
+fn to_note(row: SqliteRow) -> Note {
+    Note {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        content: row
+            .get::<Option<Json<Value>>, _>("content")
+            .map(|content| content.0),
+        footer: row.get("footer"),
+        user_name: row.get("user_name"),
+        directory: row.get("directory"),
+        file_name: row.get("file_name"),
+        public: row.get("public"),
+    }
+}
+
+fn to_directory(row: SqliteRow) -> Directory {
+    Directory {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        user_name: row.get("user_name"),
+        directory: row.get("directory"),
+        public: row.get("public"),
+        description: row.get("description"),
+        note_order: json_list(&row, "note_order").unwrap_or_default(),
+    }
+}
+
+fn to_new_inter(row: SqliteRow) -> otherlawresource::NewInter {
+    otherlawresource::NewInter {
+        provenance: Provenance::from_row(&row, "source"),
+        id: row.get("id"),
+        casename: row.get("casename"),
+        casesummary: row.get("casesummary"),
+        maincontent: json_list(&row, "maincontent").unwrap_or_default(),
+        date: row.get("date"),
+        reason: row.get("reason"),
+        related_law: row.get("related_law"),
+        source: row.get("source"),
+        name: row.get("name"),
+        year: row.get("year"),
+        number: row.get("number"),
+        reflaws: json_list(&row, "reflaws").unwrap_or_default(),
+    }
+}
+
+fn to_resolution(row: SqliteRow) -> otherlawresource::Resolution {
+    otherlawresource::Resolution {
+        provenance: Provenance::from_row(&row, "source"),
+        id: row.get("id"),
+        lawtype: row.get("lawtype"),
+        related_law: row.get("related_law"),
+        name: row.get("name"),
+        content: row.get("content"),
+        source: row.get("source"),
+        year: row.get("year"),
+        time: row.get("time"),
+        reflawid: json_list(&row, "reflawid"),
+        refinter: json_list(&row, "refinter"),
+        refprecedent: json_list(&row, "refprecedent"),
+    }
+}
+
+fn to_old_inter(row: SqliteRow) -> otherlawresource::OldInterpretation {
+    otherlawresource::OldInterpretation {
+        provenance: Provenance::from_row(&row, "source"),
+        id: row.get("id"),
+        date: row.get("date"),
+        reasoning: row.get("reasoning"),
+        content: row.get("content"),
+        trouble: row.get("trouble"),
+        related_law: row.get("related_law"),
+        source: row.get("source"),
+        reflaws: json_list(&row, "reflaws"),
+        reflawid: json_list(&row, "reflawid"),
+        refinter: json_list(&row, "refinter"),
+    }
+}
+
+fn to_precedent(row: SqliteRow) -> otherlawresource::Precedent {
+    otherlawresource::Precedent {
+        provenance: Provenance::from_row(&row, "source"),
+        id: row.get("id"),
+        name: row.get("name"),
+        holding: row.get("holding"),
+        source: row.get("source"),
+        year: row.get("year"),
+        num: row.get("num"),
+        specific: row.get("specific"),
+        court: row.get("court"),
+        case_type: row.get("case_type"),
+        reflawid: json_list(&row, "reflawid"),
+        refinter: json_list(&row, "refinter"),
+        refprecedent: json_list(&row, "refprecedent"),
+    }
+}
+
+fn to_history_law(row: SqliteRow) -> otherlawresource::HistoryLaw {
+    otherlawresource::HistoryLaw {
+        provenance: Provenance::from_row(&row, "source"),
+        id: row.get("id"),
+        lawid: row.get("lawid"),
+        date: row.get("date"),
+        content: row.get("content"),
+        no: row.get("no"),
+    }
+}
+
+fn to_new_law(row: SqliteRow) -> new_law::NewLaw {
+    new_law::NewLaw {
+        id: row.get("id"),
+        href: row.get("href"),
+        chapter: json_list(&row, "chapter").unwrap_or_default(),
+        num: row.get("num"),
+        lines: row.get::<Json<Vec<new_law::Line>>, _>("lines").0,
+        provenance: Provenance::from_row(&row, "source_url"),
+    }
+}
+
+fn to_library(row: SqliteRow) -> Library {
+    Library {
+        id: row.get("id"),
+        library_name: row.get("library_name"),
+        user_name: row.get("user_name"),
+        public: row.get("public"),
+    }
+}
+
+fn to_library_item(row: SqliteRow) -> LibraryItem {
+    LibraryItem {
+        id: row.get("id"),
+        item_library: row.get("item_library"),
+        item_type: row.get("item_type"),
+        item_id: row.get("item_id"),
+        item_name: row.get("item_name"),
+        order: row.get("ordering"),
+    }
+}
+
+fn to_dictionary(row: SqliteRow) -> Dictionary {
+    Dictionary {
+        id: row.get("id"),
+        user_name: row.get("user_name"),
+        name: row.get("name"),
+    }
+}
+
+fn to_vocab_item(row: SqliteRow) -> VocabItem {
+    VocabItem {
+        id: row.get("id"),
+        user_name: row.get("user_name"),
+        term: row.get("term"),
+        definition: row.get("definition"),
+        dictionary: row.get("dictionary"),
+    }
+}
+
+// 依欄位實際存的型別轉成 JSON，JSON_COLUMNS 還原成陣列
+fn to_json(row: &SqliteRow) -> Value {
+    let mut object = serde_json::Map::new();
+    for column in row.columns() {
+        let name = column.name();
+        let value = if let Ok(n) = row.try_get::<Option<i64>, _>(name) {
+            n.map_or(Value::Null, Value::from)
+        } else if let Ok(n) = row.try_get::<Option<f64>, _>(name) {
+            n.map_or(Value::Null, Value::from)
+        } else {
+            match row.try_get::<Option<String>, _>(name) {
+                Ok(Some(s)) if JSON_COLUMNS.contains(&name) => {
+                    serde_json::from_str(&s).unwrap_or(Value::String(s))
+                }
+                Ok(Some(s)) => Value::String(s),
+                _ => Value::Null,
+            }
+        };
+        object.insert(name.to_string(), value);
+    }
+    Value::Object(object)
+}
+
+#[async_trait]
+impl Storage for SqliteStore {
+    async fn get_new_laws(&self) -> Result<new_law::NewLaws, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM newlaw ORDER BY created_at ASC")
+            .map(to_new_law)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(lines) => Ok(new_law::NewLaws { lines }),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_file(&self, file: File) -> Result<File, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO file (id, content, css, user_name, directory, file_name, content_nav)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, content, css, user_name, directory, file_name, content_nav",
+        )
+        .bind(file.id)
+        .bind(file.content)
+        .bind(file.css)
+        .bind(file.user_name)
+        .bind(file.directory)
+        .bind(file.file_name)
+        .bind(file.content_nav)
+        .map(to_file)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(file) => Ok(file),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_every_file(&self) -> Result<Files, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM file")
+            .map(to_file)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(file) => Ok(Files { vec_files: file }),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_every_folder(
+        &self,
+    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM directory")
+            .map(|row: SqliteRow| otherlawresource::OtherSourceList {
+                id: row.get("id"),
+                name: row.get("directory"),
+                sourcetype: "folder".to_string(),
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(dcs) => Ok(dcs),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_every_note(&self) -> Result<Vec<Note>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM note")
+            .map(to_note)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(note) => Ok(note),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_note_page(
+        &self,
+        query: &ListQuery,
+    ) -> Result<(Vec<Note>, i64), handle_errors::Error> {
+        // 列表不需登入，只列出公開筆記，也不回傳內容
+        let condition = "WHERE public AND ($1 IS NULL OR user_name = $1)";
+        let order_by = query.order_by(
+            &[
+                ("name", "file_name"),
+                ("author", "user_name"),
+                ("directory", "directory"),
+                ("id", "id"),
+            ],
+            "id",
+        );
+        let total: i64 = match sqlx::query(&format!("SELECT COUNT(*) FROM note {condition}"))
+            .bind(&query.author)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(row) => row.get(0),
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        // SQLite 的 LIMIT 不接受 NULL，-1 表示不限
+        match sqlx::query(&format!(
+            "SELECT * FROM note {condition} {order_by} LIMIT $2 OFFSET $3"
+        ))
+        .bind(&query.author)
+        .bind(query.limit().unwrap_or(-1))
+        .bind(query.offset())
+        .map(|row: SqliteRow| Note {
+            content: None,
+            ..to_note(row)
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(note) => Ok((note, total)),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_file_user(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Files, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM file WHERE user_name = $1 AND directory = $2")
+            .bind(user_name)
+            .bind(directory)
+            .map(to_file)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(file) => Ok(Files { vec_files: file }),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_file(&self, id: String) -> Result<File, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM file WHERE id = $1")
+            .bind(id)
+            .map(to_file)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(file) => Ok(file),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_file_name(
+        &self,
+        id: String,
+        file_name: String,
+        new_id: String,
+    ) -> Result<File, handle_errors::Error> {
+        match sqlx::query(
+            "UPDATE file
+            SET id = $1, file_name = $2
+            WHERE id = $3
+            RETURNING id, content, css, user_name, directory, file_name, content_nav",
+        )
+        .bind(new_id)
+        .bind(file_name)
+        .bind(id)
+        .map(to_file)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(file) => Ok(file),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_content_and_css(
+        &self,
+        id: String,
+        content: String,
+        css: String,
+        nav: String,
+    ) -> Result<File, handle_errors::Error> {
+        match sqlx::query(
+            "UPDATE file
+            SET content = $1, css = $2, content_nav = $4
+            WHERE id = $3
+            RETURNING id, content, css, user_name, directory, file_name, content_nav",
+        )
+        .bind(content)
+        .bind(css)
+        .bind(id)
+        .bind(nav)
+        .map(to_file)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(file) => Ok(file),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn delete_file(&self, id: String) -> Result<File, handle_errors::Error> {
+        match sqlx::query(
+            "DELETE FROM file
+            WHERE id = $1
+            RETURNING id, content, css, user_name, directory, file_name, content_nav",
+        )
+        .bind(id)
+        .map(to_file)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(file) => Ok(file),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn delete_directory(&self, id: String) -> Result<Directory, handle_errors::Error> {
+        match sqlx::query(
+            "DELETE FROM directory
+            WHERE id = $1
+            RETURNING id, slug, user_name, directory, public, description, note_order",
+        )
+        .bind(id)
+        .map(to_directory)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(directory) => Ok(directory),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_directory(
+        &self,
+        public: bool,
+        description: String,
+        id: String,
+    ) -> Result<Directory, handle_errors::Error> {
+        match sqlx::query(
+            "UPDATE directory
+            SET public = $1, description = $2
+            WHERE id = $3
+            RETURNING id, slug, user_name, directory, public, description, note_order",
+        )
+        .bind(public)
+        .bind(description)
+        .bind(id)
+        .map(to_directory)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(directory) => Ok(directory),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_note_name(
+        &self,
+        id: String,
+        file_name: String,
+        slug: String,
+    ) -> Result<Note, handle_errors::Error> {
+        match sqlx::query(
+            "UPDATE note
+            SET slug = $1, file_name = $2
+            WHERE id = $3
+            RETURNING id, slug, user_name, directory, file_name, content, footer, public",
+        )
+        .bind(slug)
+        .bind(file_name)
+        .bind(id)
+        .map(to_note)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(note) => Ok(note),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_note_state(
+        &self,
+        id: String,
+        public: bool,
+    ) -> Result<String, handle_errors::Error> {
+        match sqlx::query("UPDATE note SET public = $1 WHERE id = $2 RETURNING id")
+            .bind(public)
+            .bind(id)
+            .map(|row: SqliteRow| row.get("id"))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(id) => Ok(id),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_note_user(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Vec<Note>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM note WHERE user_name = $1 AND directory = $2")
+            .bind(user_name)
+            .bind(directory)
+            .map(to_note)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(note) => Ok(note),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_note_name_by_dir(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Vec<String>, handle_errors::Error> {
+        match sqlx::query("SELECT file_name FROM note WHERE user_name = $1 AND directory = $2")
+            .bind(user_name)
+            .bind(directory)
+            .map(|row: SqliteRow| row.get("file_name"))
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(names) => Ok(names),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_notelist_user(
+        &self,
+    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM note WHERE public = $1")
+            .bind(true)
+            .map(|row: SqliteRow| otherlawresource::OtherSourceList {
+                id: row.get("id"),
+                name: row.get("file_name"),
+                sourcetype: "note".to_string(),
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_the_note(
+        &self,
+        content: serde_json::Value,
+        id: String,
+    ) -> Result<Note, handle_errors::Error> {
+        match sqlx::query(
+            "UPDATE note
+            SET content = $1
+            WHERE id = $2
+            RETURNING id, slug, user_name, directory, file_name, content, footer, public",
+        )
+        .bind(Json(content))
+        .bind(id)
+        .map(to_note)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(note) => Ok(note),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_note(&self, id: String) -> Result<Note, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM note WHERE id = $1")
+            .bind(id)
+            .map(to_note)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(note) => Ok(note),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_note_date(
+        &self,
+        id: String,
+    ) -> Result<chrono::DateTime<Utc>, handle_errors::Error> {
+        match sqlx::query("SELECT date FROM note WHERE id = $1")
+            .bind(id)
+            .map(|row: SqliteRow| row.get("date"))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(date) => Ok(date),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_note_order(
+        &self,
+        dir_id: String,
+        order_list: Vec<String>,
+    ) -> Result<String, handle_errors::Error> {
+        match sqlx::query("UPDATE directory SET note_order = $1 WHERE id = $2 RETURNING id")
+            .bind(Json(order_list))
+            .bind(dir_id)
+            .map(|row: SqliteRow| row.get("id"))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(id) => Ok(id),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_note_date(
+        &self,
+        id: String,
+        date: chrono::DateTime<Utc>,
+    ) -> Result<String, handle_errors::Error> {
+        match sqlx::query("UPDATE note SET date = $1 WHERE id = $2 RETURNING id")
+            .bind(date)
+            .bind(id)
+            .map(|row: SqliteRow| row.get("id"))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(id) => Ok(id),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn delete_note(&self, id: &str) -> Result<Note, handle_errors::Error> {
+        match sqlx::query(
+            "DELETE FROM note
+            WHERE id = $1
+            RETURNING id, slug, user_name, directory, file_name, content, footer, public",
+        )
+        .bind(id)
+        .map(to_note)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(note) => Ok(note),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_note(&self, note: Note) -> Result<Note, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO note (id, slug, user_name, directory, file_name, content, footer, public, date, directory_id)
+            VALUES ($1, $9, $2, $3, $4, $5, $6, $7, $8,
+                (SELECT id FROM directory WHERE user_name = $2 AND directory = $3))
+            RETURNING id, slug, user_name, directory, file_name, content, footer, public",
+        )
+        .bind(note.id)
+        .bind(note.user_name)
+        .bind(note.directory)
+        .bind(note.file_name)
+        .bind(note.content.map(Json))
+        .bind(note.footer)
+        .bind(note.public)
+        .bind(Utc::now())
+        .bind(note.slug)
+        .map(to_note)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(note) => Ok(note),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_directory(&self, directory: Directory) -> Result<Directory, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO directory (id, slug, user_name, directory, public, description, note_order)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, slug, user_name, directory, public, description, note_order",
+        )
+        .bind(directory.id)
+        .bind(directory.slug)
+        .bind(directory.user_name)
+        .bind(directory.directory)
+        .bind(directory.public)
+        .bind(directory.description)
+        .bind(Json(directory.note_order))
+        .map(to_directory)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(directory) => Ok(directory),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_directory(&self, id: &str) -> Result<Directory, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM directory WHERE id = $1")
+            .bind(id)
+            .map(to_directory)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(directory) => Ok(directory),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_directory_by_name(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Directory, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM directory WHERE user_name = $1 AND directory = $2")
+            .bind(user_name)
+            .bind(directory)
+            .map(to_directory)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(directory) => Ok(directory),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn resolve_directory_id(&self, id: &str) -> Result<String, handle_errors::Error> {
+        match sqlx::query("SELECT id FROM directory WHERE id = $1 OR slug = $1")
+            .bind(id)
+            .map(|row: SqliteRow| row.get("id"))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(id) => Ok(id),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn resolve_note_id(&self, id: &str) -> Result<String, handle_errors::Error> {
+        match sqlx::query("SELECT id FROM note WHERE id = $1 OR slug = $1")
+            .bind(id)
+            .map(|row: SqliteRow| row.get("id"))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(id) => Ok(id),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_directory_user(
+        &self,
+        user_name: &str,
+    ) -> Result<Vec<Directory>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM directory WHERE user_name = $1")
+            .bind(user_name)
+            .map(to_directory)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(directory) => Ok(directory),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_directory_pub(
+        &self,
+        query: &ListQuery,
+    ) -> Result<(Vec<Directory>, i64), handle_errors::Error> {
+        let condition = "WHERE public = TRUE AND ($1 IS NULL OR user_name = $1)";
+        let order_by = query.order_by(
+            &[("name", "directory"), ("author", "user_name"), ("id", "id")],
+            "id",
+        );
+        let total: i64 = match sqlx::query(&format!("SELECT COUNT(*) FROM directory {condition}"))
+            .bind(&query.author)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(row) => row.get(0),
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        match sqlx::query(&format!(
+            "SELECT * FROM directory {condition} {order_by} LIMIT $2 OFFSET $3"
+        ))
+        .bind(&query.author)
+        .bind(query.limit().unwrap_or(-1))
+        .bind(query.offset())
+        .map(to_directory)
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(directory) => Ok((directory, total)),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_account(&self, account: Account) -> Result<bool, handle_errors::Error> {
+        match sqlx::query("INSERT INTO accounts (user_name, email, password) VALUES ($1, $2, $3)")
+            .bind(account.user_name)
+            .bind(account.email)
+            .bind(account.password)
+            .execute(&self.connection)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(handle_errors::Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    async fn get_account(&self, email: String) -> Result<Account, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM accounts WHERE email = $1")
+            .bind(email)
+            .map(|row: SqliteRow| Account {
+                user_name: row.get("user_name"),
+                email: row.get("email"),
+                password: row.get("password"),
+            })
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(account) => Ok(account),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_newinterpretations(
+        &self,
+    ) -> Result<Vec<otherlawresource::NewInter>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM newinters")
+            .map(to_new_inter)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(inters) => Ok(inters),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_newinterpretation_by_id(
+        &self,
+        id: String,
+    ) -> Result<otherlawresource::NewInter, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM newinters WHERE id = $1")
+            .bind(id)
+            .map(to_new_inter)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(res) => Ok(res),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_all_resolution(
+        &self,
+    ) -> Result<Vec<otherlawresource::Resolution>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM resolution")
+            .map(to_resolution)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(resolutions) => Ok(resolutions),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_resolution_by_id(
+        &self,
+        id: String,
+    ) -> Result<otherlawresource::Resolution, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM resolution WHERE id = $1")
+            .bind(id)
+            .map(to_resolution)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(res) => Ok(res),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_all_oldinterpretation(
+        &self,
+    ) -> Result<Vec<otherlawresource::OldInterpretation>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM oldinters")
+            .map(to_old_inter)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(result) => Ok(result),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_oldinter_by_id(
+        &self,
+        id: String,
+    ) -> Result<otherlawresource::OldInterpretation, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM oldinters WHERE id = $1")
+            .bind(id)
+            .map(to_old_inter)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(res) => Ok(res),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_all_precedents(
+        &self,
+    ) -> Result<Vec<otherlawresource::Precedent>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM precedents")
+            .map(to_precedent)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(precedents) => Ok(precedents),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_precedent_by_id(
+        &self,
+        id: String,
+    ) -> Result<otherlawresource::Precedent, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM precedents WHERE id = $1")
+            .bind(id)
+            .map(to_precedent)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(prec) => Ok(prec),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_historylaw(
+        &self,
+        lawid: String,
+    ) -> Result<Vec<otherlawresource::HistoryLaw>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM history_law WHERE lawid = $1")
+            .bind(lawid)
+            .map(to_history_law)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_historylaw_by_chapter(
+        &self,
+        chapter: &str,
+    ) -> Result<Vec<otherlawresource::HistoryLaw>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM history_law WHERE lawid LIKE $1 || '-%'")
+            .bind(chapter)
+            .map(to_history_law)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_law_aliases(&self) -> Result<Vec<new_law::alias::LawAlias>, handle_errors::Error> {
+        match sqlx::query("SELECT alias, name FROM law_alias")
+            .map(|row: SqliteRow| new_law::alias::LawAlias {
+                alias: row.get("alias"),
+                name: row.get("name"),
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_law_alias(
+        &self,
+        alias: new_law::alias::LawAlias,
+    ) -> Result<new_law::alias::LawAlias, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO law_alias (alias, name)
+            VALUES ($1, $2)
+            ON CONFLICT (alias) DO UPDATE
+            SET name = excluded.name
+            RETURNING alias, name",
+        )
+        .bind(&alias.alias)
+        .bind(&alias.name)
+        .map(|row: SqliteRow| new_law::alias::LawAlias {
+            alias: row.get("alias"),
+            name: row.get("name"),
+        })
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(alias) => Ok(alias),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn delete_law_alias(&self, alias: &str) -> Result<(), handle_errors::Error> {
+        match sqlx::query("DELETE FROM law_alias WHERE alias = $1")
+            .bind(alias)
+            .execute(&self.connection)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_note_references(
+        &self,
+        note_id: &str,
+        refs: &[note::Reference],
+    ) -> Result<(), handle_errors::Error> {
+        let mut tx = match self.connection.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        if let Err(e) = sqlx::query("DELETE FROM note_reference WHERE note_id = $1")
+            .bind(note_id)
+            .execute(&mut tx)
+            .await
+        {
+            return Err(handle_errors::Error::DatabaseQueryError(e));
+        }
+        for reference in refs {
+            if let Err(e) = sqlx::query(
+                "INSERT OR IGNORE INTO note_reference (note_id, ref_type, ref_id)
+                VALUES ($1, $2, $3)",
+            )
+            .bind(note_id)
+            .bind(&reference.ref_type)
+            .bind(&reference.ref_id)
+            .execute(&mut tx)
+            .await
+            {
+                return Err(handle_errors::Error::DatabaseQueryError(e));
+            }
+        }
+        match tx.commit().await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_notes_by_reference(
+        &self,
+        ref_type: &str,
+        ref_id: &str,
+        user_name: Option<String>,
+    ) -> Result<Vec<Note>, handle_errors::Error> {
+        match sqlx::query(
+            "SELECT note.id, note.slug, note.user_name, note.directory, note.file_name, note.footer, note.public
+            FROM note
+            JOIN note_reference ON note.id = note_reference.note_id
+            WHERE note_reference.ref_type = $1
+            AND note_reference.ref_id = $2
+            AND (note.public OR note.user_name = $3)
+            ORDER BY note.id",
+        )
+        .bind(ref_type)
+        .bind(ref_id)
+        .bind(user_name)
+        .map(|row: SqliteRow| Note {
+            id: row.get("id"),
+            slug: row.get("slug"),
+            user_name: row.get("user_name"),
+            directory: row.get("directory"),
+            file_name: row.get("file_name"),
+            footer: row.get("footer"),
+            content: None,
+            public: row.get("public"),
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(notes) => Ok(notes),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn replace_stale_cards(
+        &self,
+        note_id: &str,
+        cards: &[StaleCard],
+    ) -> Result<(), handle_errors::Error> {
+        let mut tx = match self.connection.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        if let Err(e) = sqlx::query("DELETE FROM note_stale_card WHERE note_id = $1")
+            .bind(note_id)
+            .execute(&mut tx)
+            .await
+        {
+            return Err(handle_errors::Error::DatabaseQueryError(e));
+        }
+        for card in cards {
+            if let Err(e) = sqlx::query(
+                "INSERT INTO note_stale_card (note_id, block_index, law_id, card_lines, current_lines, checked_at)
+                VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(&card.note_id)
+            .bind(card.block_index)
+            .bind(&card.law_id)
+            .bind(Json(&card.card_lines))
+            .bind(Json(&card.current_lines))
+            .bind(Utc::now())
+            .execute(&mut tx)
+            .await
+            {
+                return Err(handle_errors::Error::DatabaseQueryError(e));
+            }
+        }
+        match tx.commit().await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_stale_cards(&self, note_id: &str) -> Result<Vec<StaleCard>, handle_errors::Error> {
+        match sqlx::query(
+            "SELECT note_id, block_index, law_id, card_lines, current_lines
+            FROM note_stale_card
+            WHERE note_id = $1
+            ORDER BY block_index",
+        )
+        .bind(note_id)
+        .map(|row: SqliteRow| StaleCard {
+            note_id: row.get("note_id"),
+            block_index: row.get("block_index"),
+            law_id: row.get("law_id"),
+            card_lines: json_list(&row, "card_lines").unwrap_or_default(),
+            current_lines: json_list(&row, "current_lines").unwrap_or_default(),
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(cards) => Ok(cards),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_note_revision(
+        &self,
+        note_id: &str,
+        content: serde_json::Value,
+        reason: &str,
+    ) -> Result<(), handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO note_revision (id, note_id, content, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(note_id)
+        .bind(Json(content))
+        .bind(reason)
+        .bind(Utc::now())
+        .execute(&self.connection)
+        .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_library(&self, library: Library) -> Result<Library, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO library (id, library_name, user_name, public)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, library_name, user_name, public",
+        )
+        .bind(&library.id)
+        .bind(&library.library_name)
+        .bind(&library.user_name)
+        .bind(library.public)
+        .map(to_library)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(library) => Ok(library),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_library_item(
+        &self,
+        item: LibraryItem,
+    ) -> Result<LibraryItem, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO library_item (id, item_library, item_type, item_name, item_id, ordering)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, item_library, item_type, item_name, item_id, ordering",
+        )
+        .bind(&item.id)
+        .bind(&item.item_library)
+        .bind(&item.item_type)
+        .bind(&item.item_name)
+        .bind(item.item_id)
+        .bind(item.order)
+        .map(to_library_item)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(item) => Ok(item),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_library_user(
+        &self,
+        user_name: &str,
+    ) -> Result<Vec<Library>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM library WHERE user_name = $1")
+            .bind(user_name)
+            .map(to_library)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(libraries) => Ok(libraries),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_item_by_library(
+        &self,
+        library_id: &str,
+    ) -> Result<Vec<LibraryItem>, handle_errors::Error> {
+        match sqlx::query(
+            "SELECT * FROM library_item
+            WHERE item_library = $1
+            ORDER BY ordering DESC",
+        )
+        .bind(library_id)
+        .map(to_library_item)
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(items) => Ok(items),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn delete_dictionary(&self, id: &str) -> Result<Dictionary, handle_errors::Error> {
+        match sqlx::query("DELETE FROM dictionary WHERE id = $1 RETURNING id, user_name, name")
+            .bind(id)
+            .map(to_dictionary)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(dic) => Ok(dic),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    // 參數順序與 PgStore 相同
+    async fn add_dictionary(
+        &self,
+        user_name: &str,
+        dictionary_name: &str,
+        id: &str,
+    ) -> Result<Dictionary, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO dictionary (id, name, user_name)
+            VALUES ($1, $2, $3)
+            RETURNING id, name, user_name",
+        )
+        .bind(id)
+        .bind(user_name)
+        .bind(dictionary_name)
+        .map(to_dictionary)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(dic) => Ok(dic),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_dictionary(&self, id: &str) -> Result<Dictionary, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM dictionary WHERE id = $1")
+            .bind(id)
+            .map(to_dictionary)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(dic) => Ok(dic),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_dictionary_by_user(
+        &self,
+        user_name: &str,
+    ) -> Result<Vec<Dictionary>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM dictionary WHERE user_name = $1")
+            .bind(user_name)
+            .map(to_dictionary)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(dic) => Ok(dic),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_vocabitem_user(
+        &self,
+        user_name: &str,
+        query: &ListQuery,
+    ) -> Result<(Vec<VocabItem>, i64), handle_errors::Error> {
+        let condition = "WHERE user_name = $1 AND ($2 IS NULL OR dictionary = $2)";
+        let order_by = query.order_by(&[("term", "term"), ("id", "id")], "id");
+        let total: i64 = match sqlx::query(&format!("SELECT COUNT(*) FROM vocab_item {condition}"))
+            .bind(user_name)
+            .bind(&query.dictionary)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(row) => row.get(0),
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        match sqlx::query(&format!(
+            "SELECT * FROM vocab_item {condition} {order_by} LIMIT $3 OFFSET $4"
+        ))
+        .bind(user_name)
+        .bind(&query.dictionary)
+        .bind(query.limit().unwrap_or(-1))
+        .bind(query.offset())
+        .map(to_vocab_item)
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(items) => Ok((items, total)),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_vocabitem_dictionary(
+        &self,
+        dictionary: &str,
+    ) -> Result<Vec<VocabItem>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM vocab_item WHERE dictionary = $1")
+            .bind(dictionary)
+            .map(to_vocab_item)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(items) => Ok(items),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    // SQLite 的 LIKE 對英文字母本來就不分大小寫
+    async fn get_vocabitem_term(&self, term: &str) -> Result<Vec<VocabItem>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM vocab_item WHERE term LIKE $1")
+            .bind(term)
+            .map(to_vocab_item)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(items) => Ok(items),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_vocabitem_def(&self, def: &str) -> Result<Vec<VocabItem>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM vocab_item WHERE definition LIKE $1")
+            .bind(def)
+            .map(to_vocab_item)
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(items) => Ok(items),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_vocabitem(&self, item: VocabItem) -> Result<VocabItem, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO vocab_item (id, user_name, term, definition, dictionary)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id, user_name, term, definition, dictionary",
+        )
+        .bind(&item.id)
+        .bind(&item.user_name)
+        .bind(&item.term)
+        .bind(&item.definition)
+        .bind(&item.dictionary)
+        .map(to_vocab_item)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(inserted) => Ok(inserted),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn delete_vocabitem(&self, id: &str) -> Result<VocabItem, handle_errors::Error> {
+        match sqlx::query(
+            "DELETE FROM vocab_item
+            WHERE id = $1
+            RETURNING id, user_name, term, definition, dictionary",
+        )
+        .bind(id)
+        .map(to_vocab_item)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(item) => Ok(item),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn update_vocabitem(&self, item: VocabItem) -> Result<VocabItem, handle_errors::Error> {
+        match sqlx::query(
+            "UPDATE vocab_item
+            SET term = $1, definition = $2
+            WHERE id = $3
+            RETURNING id, user_name, term, definition, dictionary",
+        )
+        .bind(&item.term)
+        .bind(&item.definition)
+        .bind(&item.id)
+        .map(to_vocab_item)
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(updated) => Ok(updated),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_vocabitem_law(
+        &self,
+        item: VocabItemLaw,
+    ) -> Result<VocabItemLaw, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO vocabitem_law (vocabitem_id, law_id)
+            VALUES ($1, $2)
+            RETURNING vocabitem_id, law_id",
+        )
+        .bind(&item.vocabitem_id)
+        .bind(&item.law_id)
+        .map(|row: SqliteRow| VocabItemLaw {
+            vocabitem_id: row.get("vocabitem_id"),
+            law_id: row.get("law_id"),
+        })
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(item) => Ok(item),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_vocabitems_by_law_id(
+        &self,
+        law_id: &str,
+    ) -> Result<Vec<VocabItem>, handle_errors::Error> {
+        match sqlx::query(
+            "SELECT vocab_item.*
+            FROM vocab_item
+            JOIN vocabitem_law ON vocab_item.id = vocabitem_law.vocabitem_id
+            WHERE vocabitem_law.law_id = $1",
+        )
+        .bind(law_id)
+        .map(to_vocab_item)
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_law_ids_by_vocabitem(
+        &self,
+        vocabitem_id: &str,
+    ) -> Result<Vec<String>, handle_errors::Error> {
+        match sqlx::query("SELECT law_id FROM vocabitem_law WHERE vocabitem_id = $1")
+            .bind(vocabitem_id)
+            .map(|row: SqliteRow| row.get("law_id"))
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(laws) => Ok(laws),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_laws_by_vocabitem(
+        &self,
+        item_id: &str,
+    ) -> Result<Vec<new_law::NewLaw>, handle_errors::Error> {
+        match sqlx::query(
+            "SELECT newlaw.*
+            FROM newlaw
+            JOIN vocabitem_law ON newlaw.id = vocabitem_law.law_id
+            WHERE vocabitem_law.vocabitem_id = $1",
+        )
+        .bind(item_id)
+        .map(to_new_law)
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn delete_vocabitem_law(
+        &self,
+        item_id: &str,
+        law_id: &str,
+    ) -> Result<(), handle_errors::Error> {
+        match sqlx::query("DELETE FROM vocabitem_law WHERE vocabitem_id = $1 AND law_id = $2")
+            .bind(item_id)
+            .bind(law_id)
+            .execute(&self.connection)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_sync_runs(
+        &self,
+        query: &ListQuery,
+    ) -> Result<(Vec<SyncRun>, i64), handle_errors::Error> {
+        let condition = "WHERE ($1 IS NULL OR source = $1)";
+        let order_by = ListQuery {
+            order: query.order.clone().or(Some("desc".to_string())),
+            ..query.clone()
+        }
+        .order_by(&[("id", "id"), ("source", "source")], "id");
+        let total: i64 = match sqlx::query(&format!("SELECT COUNT(*) FROM sync_run {condition}"))
+            .bind(&query.kind)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(row) => row.get(0),
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        match sqlx::query(&format!(
+            "SELECT * FROM sync_run {condition} {order_by} LIMIT $2 OFFSET $3"
+        ))
+        .bind(&query.kind)
+        .bind(query.limit().unwrap_or(-1))
+        .bind(query.offset())
+        .map(|row: SqliteRow| SyncRun {
+            id: row.get("id"),
+            source: row.get("source"),
+            started_at: row.get("started_at"),
+            finished_at: row.get("finished_at"),
+            inserted: json_list(&row, "inserted").unwrap_or_default(),
+            updated: json_list(&row, "updated").unwrap_or_default(),
+            unchanged: json_list(&row, "unchanged").unwrap_or_default(),
+            failed: json_list(&row, "failed").unwrap_or_default(),
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(runs) => Ok((runs, total)),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    fn export_rows(
+        &self,
+        dataset: &str,
+    ) -> BoxStream<'_, Result<serde_json::Value, handle_errors::Error>> {
+        // 資料表名稱無法 bind，每個資料集的查詢逐一寫出
+        let query = match dataset {
+            "lawinformation" => "SELECT * FROM lawinformation ORDER BY originalid",
+            "newlaw" => "SELECT * FROM newlaw ORDER BY id",
+            "history_law" => "SELECT * FROM history_law ORDER BY id",
+            "newinters" => "SELECT * FROM newinters ORDER BY id",
+            "newinterpretations" => "SELECT * FROM newinterpretations ORDER BY id",
+            "oldinters" => "SELECT * FROM oldinters ORDER BY id",
+            "precedents" => "SELECT * FROM precedents ORDER BY id",
+            "resolution" => "SELECT * FROM resolution ORDER BY id",
+            _ => {
+                let error = handle_errors::Error::DatasetNotFound(dataset.to_string());
+                return stream::once(async { Err(error) }).boxed();
+            }
+        };
+        sqlx::query(query)
+            .map(|row: SqliteRow| to_json(&row))
+            .fetch(&self.connection)
+            .map(|row| row.map_err(handle_errors::Error::DatabaseQueryError))
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use otherlawresource::export::DatasetEntry;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    async fn memory_store() -> SqliteStore {
+        SqliteStore::memory().await
+    }
+
+    fn directory(id: &str, user_name: &str, name: &str) -> Directory {
+        Directory {
+            id: id.to_string(),
+            slug: Directory::make_slug(user_name, name),
+            user_name: user_name.to_string(),
+            directory: name.to_string(),
+            public: false,
+            description: String::new(),
+            note_order: Vec::new(),
+        }
+    }
+
+    fn note(id: &str, user_name: &str, directory: &str, file_name: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            slug: Note::make_slug(user_name, directory, file_name),
+            content: Some(json!([{ "type": "p", "children": [] }])),
+            footer: None,
+            user_name: user_name.to_string(),
+            directory: directory.to_string(),
+            file_name: file_name.to_string(),
+            public: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn notes_and_directories_round_trip() {
+        let store = memory_store().await;
+        let dir = store
+            .add_directory(directory("d1", "alice", "民法"))
+            .await
+            .unwrap();
+        assert_eq!(store.resolve_directory_id(&dir.slug).await.unwrap(), "d1");
+        store
+            .update_note_order("d1".to_string(), vec!["總則".to_string()])
+            .await
+            .unwrap();
+        let dir = store.get_directory_by_name("alice", "民法").await.unwrap();
+        assert_eq!(dir.note_order, vec!["總則".to_string()]);
+
+        let added = store
+            .add_note(note("n1", "alice", "民法", "總則"))
+            .await
+            .unwrap();
+        assert_eq!(store.resolve_note_id(&added.slug).await.unwrap(), "n1");
+        let content = json!([{ "type": "h2", "children": [] }]);
+        store
+            .update_the_note(content.clone(), "n1".to_string())
+            .await
+            .unwrap();
+        let fetched = store.get_note("n1".to_string()).await.unwrap();
+        assert_eq!(fetched.content, Some(content));
+        assert_eq!(fetched.directory, "民法");
+        assert!(fetched.public);
+
+        // 筆記隨資料夾一併刪除
+        store.delete_directory("d1".to_string()).await.unwrap();
+        assert!(store.get_note("n1".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn notes_by_reference_hide_other_users_private_notes() {
+        let store = memory_store().await;
+        store
+            .add_directory(directory("d1", "alice", "民法"))
+            .await
+            .unwrap();
+        let mut private = note("n2", "alice", "民法", "侵權");
+        private.public = false;
+        for n in [note("n1", "alice", "民法", "總則"), private] {
+            store.add_note(n).await.unwrap();
+        }
+        let refs = vec![note::Reference {
+            ref_type: "law".to_string(),
+            ref_id: "民法-184".to_string(),
+        }];
+        store.update_note_references("n1", &refs).await.unwrap();
+        store.update_note_references("n2", &refs).await.unwrap();
+
+        let ids = |notes: Vec<Note>| notes.into_iter().map(|n| n.id).collect::<Vec<_>>();
+        let anonymous = store
+            .get_notes_by_reference("law", "民法-184", None)
+            .await
+            .unwrap();
+        assert_eq!(ids(anonymous), vec!["n1"]);
+        let other = store
+            .get_notes_by_reference("law", "民法-184", Some("bob".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(ids(other), vec!["n1"]);
+        let owner = store
+            .get_notes_by_reference("law", "民法-184", Some("alice".to_string()))
+            .await
+            .unwrap();
+        assert!(owner.iter().all(|n| n.content.is_none()));
+        assert_eq!(ids(owner), vec!["n1", "n2"]);
+
+        // 刪除筆記時引用由外鍵一併刪除
+        store.delete_note("n2").await.unwrap();
+        let count: i64 = sqlx::query("SELECT COUNT(*) FROM note_reference WHERE note_id = 'n2'")
+            .fetch_one(&store.connection)
+            .await
+            .unwrap()
+            .get(0);
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn note_page_lists_public_notes_without_content() {
+        let store = memory_store().await;
+        store
+            .add_directory(directory("d1", "alice", "民法"))
+            .await
+            .unwrap();
+        let mut private = note("n2", "alice", "民法", "侵權");
+        private.public = false;
+        for n in [note("n1", "alice", "民法", "總則"), private] {
+            store.add_note(n).await.unwrap();
+        }
+        let (notes, total) = store.get_note_page(&ListQuery::default()).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(notes[0].id, "n1");
+        assert!(notes[0].content.is_none());
+    }
+
+    #[test]
+    fn slugs_of_hyphenated_names_do_not_collide() {
+        assert_eq!(Note::make_slug("alice", "民法", "總則"), "alice-民法-總則");
+        assert_ne!(
+            Note::make_slug("alice", "民法-總則", "第一章"),
+            Note::make_slug("alice", "民法", "總則-第一章")
+        );
+        assert_ne!(
+            Directory::make_slug("alice-民法", "總則"),
+            Directory::make_slug("alice", "民法-總則")
+        );
+        assert_eq!(Directory::make_slug("a%2D", "b"), "a%252D-b");
+    }
+
+    #[tokio::test]
+    async fn resolves_ids_and_slugs_with_hyphens() {
+        let store = memory_store().await;
+        store
+            .add_directory(directory("d1", "alice", "民法-總則"))
+            .await
+            .unwrap();
+        store
+            .add_directory(directory("d2", "alice-民法", "總則"))
+            .await
+            .unwrap();
+        let first = Directory::make_slug("alice", "民法-總則");
+        let second = Directory::make_slug("alice-民法", "總則");
+        assert_eq!(store.resolve_directory_id(&first).await.unwrap(), "d1");
+        assert_eq!(store.resolve_directory_id(&second).await.unwrap(), "d2");
+        assert_eq!(store.resolve_directory_id("d2").await.unwrap(), "d2");
+
+        let added = store
+            .add_note(note("n1", "alice", "民法-總則", "第1-3條"))
+            .await
+            .unwrap();
+        assert_eq!(store.resolve_note_id(&added.slug).await.unwrap(), "n1");
+        assert_eq!(store.resolve_note_id("n1").await.unwrap(), "n1");
+        assert!(store
+            .resolve_note_id("alice-民法-總則-第1-3條")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn upgrades_files_created_before_slugs() {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:").unwrap();
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        // 0002 之前的資料表，id 即為現在的 slug
+        for query in [
+            "CREATE TABLE directory (
+                id TEXT PRIMARY KEY,
+                user_name TEXT NOT NULL,
+                directory TEXT NOT NULL,
+                public BOOLEAN NOT NULL DEFAULT FALSE,
+                description TEXT NOT NULL DEFAULT '',
+                note_order TEXT NOT NULL DEFAULT '[]'
+            )",
+            "CREATE TABLE note (
+                id TEXT PRIMARY KEY,
+                user_name TEXT NOT NULL,
+                directory TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                content TEXT,
+                footer TEXT,
+                public BOOLEAN NOT NULL DEFAULT FALSE,
+                date TEXT
+            )",
+            "CREATE TABLE note_reference (
+                note_id TEXT NOT NULL,
+                ref_type TEXT NOT NULL,
+                ref_id TEXT NOT NULL,
+                PRIMARY KEY (note_id, ref_type, ref_id)
+            )",
+            "INSERT INTO directory (id, user_name, directory) VALUES ('alice-民法', 'alice', '民法')",
+            "INSERT INTO note (id, user_name, directory, file_name)
+            VALUES ('alice-民法-總則', 'alice', '民法', '總則')",
+            "INSERT INTO note_reference VALUES ('alice-民法-總則', 'law', '民法-1')",
+            // 沒有對應目錄的筆記
+            "INSERT INTO note (id, user_name, directory, file_name)
+            VALUES ('bob-刑法-總則', 'bob', '刑法', '總則')",
+            "CREATE TABLE library_item (
+                id TEXT PRIMARY KEY,
+                item_library TEXT NOT NULL,
+                item_type TEXT NOT NULL,
+                item_name TEXT NOT NULL,
+                item_id TEXT NOT NULL,
+                ordering INTEGER NOT NULL DEFAULT 1
+            )",
+            "INSERT INTO library_item (id, item_library, item_type, item_name, item_id)
+            VALUES ('i1', 'l1', 'note', '總則', 'alice-民法-總則'),
+                ('i2', 'l1', 'law', '民法第1條', '民法-1')",
+        ] {
+            sqlx::query(query).execute(&pool).await.unwrap();
+        }
+        let store = SqliteStore::with_pool(pool).await;
+
+        let id = store.resolve_note_id("alice-民法-總則").await.unwrap();
+        assert_ne!(id, "alice-民法-總則");
+        let note = store.get_note(id.clone()).await.unwrap();
+        assert_eq!(note.slug, "alice-民法-總則");
+        let refs = store
+            .get_notes_by_reference("law", "民法-1", Some("alice".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(refs.len(), 1);
+
+        // 收藏改存新的 id，其他項目不變
+        let mut items = store.get_item_by_library("l1").await.unwrap();
+        items.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(items[0].item_id, id);
+        assert_eq!(items[1].item_id, "民法-1");
+
+        // 缺少的目錄由 migration 補上
+        let bob = store.resolve_note_id("bob-刑法-總則").await.unwrap();
+        assert_eq!(store.get_note(bob).await.unwrap().directory, "刑法");
+        assert!(store.resolve_directory_id("bob-刑法").await.is_ok());
+
+        let dir_id = store.resolve_directory_id("alice-民法").await.unwrap();
+        store.delete_directory(dir_id).await.unwrap();
+        assert!(store.get_note(id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn vocab_items_round_trip() {
+        let store = memory_store().await;
+        let item = store
+            .add_vocabitem(VocabItem {
+                id: "v1".to_string(),
+                user_name: "alice".to_string(),
+                term: "善意".to_string(),
+                definition: "不知情".to_string(),
+                dictionary: "民法".to_string(),
+            })
+            .await
+            .unwrap();
+        let updated = store
+            .update_vocabitem(VocabItem {
+                definition: "不知其事".to_string(),
+                ..item
+            })
+            .await
+            .unwrap();
+        assert_eq!(updated.definition, "不知其事");
+
+        store
+            .add_vocabitem_law(VocabItemLaw {
+                vocabitem_id: "v1".to_string(),
+                law_id: "民法-948".to_string(),
+            })
+            .await
+            .unwrap();
+        let items = store.get_vocabitems_by_law_id("民法-948").await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].term, "善意");
+        assert_eq!(
+            store.get_law_ids_by_vocabitem("v1").await.unwrap(),
+            vec!["民法-948".to_string()]
+        );
+
+        let by_dictionary = |dictionary: &str| ListQuery {
+            dictionary: Some(dictionary.to_string()),
+            ..Default::default()
+        };
+        let (items, total) = store
+            .get_vocabitem_user("alice", &by_dictionary("民法"))
+            .await
+            .unwrap();
+        assert_eq!((items.len(), total), (1, 1));
+        let (items, total) = store
+            .get_vocabitem_user("alice", &by_dictionary("刑法"))
+            .await
+            .unwrap();
+        assert_eq!((items.len(), total), (0, 0));
+
+        store.delete_vocabitem("v1").await.unwrap();
+        assert!(store.get_vocabitem_term("善意").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn seeds_from_export_once() {
+        let store = memory_store().await;
+        let dir = std::env::temp_dir().join(format!("law_web_seed_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // 匯出檔中 SQLite 沒有的欄位略過
+        let laws = vec![json!({
+            "id": "民法-1",
+            "href": "https://law.example/1",
+            "chapter": ["民法", "第一編 總則"],
+            "num": "1",
+            "lines": [],
+            "extra": 1,
+        })];
+        let information = vec![json!({
+            "originalid": "B0000001",
+            "name": "民法",
+            "update_date": "2021-01-20",
+            "release_date": "1929-05-23",
+        })];
+        let mut manifest = Manifest {
+            created_at: Utc::now(),
+            datasets: Vec::new(),
+        };
+        for (name, rows) in [("newlaw", &laws), ("lawinformation", &information)] {
+            let jsonl = export::to_jsonl(rows);
+            let csv = export::to_csv(rows).unwrap();
+            std::fs::write(dir.join(format!("{name}.jsonl")), &jsonl).unwrap();
+            manifest
+                .datasets
+                .push(DatasetEntry::new(name, rows.len(), &jsonl, &csv));
+        }
+        std::fs::write(
+            dir.join(MANIFEST),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(store.seed_from_export(&dir).await.unwrap(), 2);
+        // 已有法規時不再匯入
+        assert_eq!(store.seed_from_export(&dir).await.unwrap(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let law = store.get_new_laws().await.unwrap().lines.remove(0);
+        assert_eq!(
+            law.chapter,
+            vec!["民法".to_string(), "第一編 總則".to_string()]
+        );
+        let rows: Vec<Value> = store
+            .export_rows("lawinformation")
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["name"], "民法");
+        assert!(matches!(
+            store
+                .export_rows("accounts")
+                .try_collect::<Vec<Value>>()
+                .await,
+            Err(handle_errors::Error::DatasetNotFound(_))
+        ));
+    }
+}
@@ -1,22 +1,29 @@
+use crate::storage::Storage;
 use crate::types::account::Account;
 use crate::types::dictionary::{Dictionary, VocabItem, VocabItemLaw};
 use crate::types::directory::Directory;
 use crate::types::file::{File, Files};
-use crate::types::note::Note;
+use crate::types::note::{Note, StaleCard};
+use crate::types::pagination::ListQuery;
+use crate::types::sync_run::SyncRun;
 use crate::types::Library::{Library, LibraryItem};
 use argon2::Config;
+use async_trait::async_trait;
 use chrono::Utc;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
 use log::error;
+use new_law::provenance::Provenance;
 use rand::Rng;
 use sqlx::postgres::{PgPoolOptions, PgRow};
 use sqlx::{PgPool, Row};
 
 #[derive(Clone)]
-pub struct Store {
+pub struct PgStore {
     pub connection: PgPool, //設定一個連接池
 }
 
-impl Store {
+impl PgStore {
     pub async fn new(db_url: &str) -> Self {
         let db_pool = match PgPoolOptions::new()
             .max_connections(5) // 最多可以同時連接5個
@@ -26,12 +33,149 @@ impl Store {
             Ok(pool) => pool,
             Err(e) => panic!("無法連接上池：{e}"),
         };
-        Store {
+        PgStore {
             connection: db_pool,
         }
     }
 
-    pub async fn add_file(&self, file: File) -> Result<File, handle_errors::Error> {
+    /// 執行 migrations/ 中尚未套用的版本，編譯時即嵌入執行檔
+    pub async fn run_migrations(&self) -> Result<(), sqlx::migrate::MigrateError> {
+        sqlx::migrate!("./migrations").run(&self.connection).await
+    }
+
+    pub async fn update_content(
+        &self,
+        id: String,
+        content: String,
+    ) -> Result<File, handle_errors::Error> {
+        match sqlx::query(
+            "UPDATE file
+            SET content = $1
+            WHERE id = $2
+            RETURNING id, content, css, user_name, directory, file_name, content_nav;",
+        )
+        .bind(content)
+        .bind(id)
+        .map(|row: PgRow| File {
+            id: row.get("id"),
+            content: row.get("content"),
+            css: row.get("css"),
+            user_name: row.get("user_name"),
+            directory: row.get("directory"),
+            file_name: row.get("file_name"),
+            content_nav: row.get("content_nav"),
+        })
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(file) => Ok(file),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    pub async fn get_newinterpretations_list(
+        &self,
+    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
+        match sqlx::query("SELECT id, name FROM newinters")
+            .map(|row: PgRow| {
+                let year: String = row.get("year");
+                let number: String = row.get("number");
+                let name = format!("{}憲判{}", year, number);
+                otherlawresource::OtherSourceList {
+                    id: row.get("id"),
+                    name,
+                    sourcetype: "newinterpretation".to_string(),
+                }
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    pub async fn get_resolutions_list(
+        &self,
+    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
+        match sqlx::query("SELECT id, name FROM resolution")
+            .map(|row: PgRow| otherlawresource::OtherSourceList {
+                id: row.get("id"),
+                name: row.get("name"),
+                sourcetype: "resolution".to_string(),
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    pub async fn get_oldinterpretations_list(
+        &self,
+    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
+        match sqlx::query("SELECT id FROM oldinters")
+            .map(|row: PgRow| {
+                let id: String = row.get("id");
+                let name = format!("釋字{}", id.clone());
+                otherlawresource::OtherSourceList {
+                    id: id.clone(),
+                    name: name,
+                    sourcetype: "oldinterpretation".to_string(),
+                }
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    pub async fn get_precedentlist(
+        &self,
+    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
+        match sqlx::query("SELECT id, name FROM precedents")
+            .map(|row: PgRow| otherlawresource::OtherSourceList {
+                id: row.get("id"),
+                name: row.get("name"),
+                sourcetype: "precedent".to_string(),
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for PgStore {
+    async fn get_new_laws(&self) -> Result<new_law::NewLaws, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM newlaw ORDER BY created_at ASC")
+            .map(|row: PgRow| {
+                let lines_json: serde_json::Value = row.get("lines");
+                let lines: Vec<new_law::Line> = serde_json::from_value(lines_json).unwrap();
+                new_law::NewLaw {
+                    id: row.get("id"),
+                    href: row.get("href"),
+                    chapter: row.get("chapter"),
+                    num: row.get("num"),
+                    lines,
+                    provenance: Provenance::from_row(&row, "source_url"),
+                }
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(lines) => Ok(new_law::NewLaws { lines }),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_file(&self, file: File) -> Result<File, handle_errors::Error> {
         match sqlx::query(
             "INSERT INTO file (id, content, css, user_name, directory, file_name, content_nav)
             VALUES ($1, $2, $3, $4, $5, $6, $7)
@@ -61,7 +205,7 @@ impl Store {
         }
     }
 
-    pub async fn get_every_file(&self) -> Result<Files, handle_errors::Error> {
+    async fn get_every_file(&self) -> Result<Files, handle_errors::Error> {
         match sqlx::query("SELECT * from file")
             .map(|row: PgRow| File {
                 id: row.get("id"),
@@ -80,7 +224,7 @@ impl Store {
         }
     }
 
-    pub async fn get_every_folder(
+    async fn get_every_folder(
         &self,
     ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
         match sqlx::query("SELECT * from directory")
@@ -97,10 +241,11 @@ impl Store {
         }
     }
 
-    pub async fn get_every_note(&self) -> Result<Vec<Note>, handle_errors::Error> {
+    async fn get_every_note(&self) -> Result<Vec<Note>, handle_errors::Error> {
         match sqlx::query("SELECT * from note")
             .map(|row: PgRow| Note {
                 id: row.get("id"),
+                slug: row.get("slug"),
                 content: row.get("content"),
                 footer: row.get("footer"),
                 user_name: row.get("user_name"),
@@ -116,41 +261,65 @@ impl Store {
         }
     }
 
-    pub async fn get_file_user(
+    async fn get_note_page(
         &self,
-        user_name: &str,
-        directory: &str,
-    ) -> Result<Files, handle_errors::Error> {
-        match sqlx::query(
-            "SELECT * from file
-        WHERE user_name = $1 AND directory = $2",
-        )
-        .bind(user_name)
-        .bind(directory)
-        .map(|row: PgRow| File {
+        query: &ListQuery,
+    ) -> Result<(Vec<Note>, i64), handle_errors::Error> {
+        // 列表不需登入，只列出公開筆記，也不回傳內容
+        let condition = "WHERE public AND ($1::text IS NULL OR user_name = $1)";
+        let order_by = query.order_by(
+            &[
+                ("name", "file_name"),
+                ("author", "user_name"),
+                ("directory", "directory"),
+                ("id", "id"),
+            ],
+            "id",
+        );
+        let total: i64 = match sqlx::query(&format!("SELECT COUNT(*) from note {condition}"))
+            .bind(&query.author)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(row) => row.get(0),
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        match sqlx::query(&format!(
+            "SELECT id, slug, footer, user_name, directory, file_name, public
+            FROM note {condition} {order_by} LIMIT $2 OFFSET $3"
+        ))
+        .bind(&query.author)
+        .bind(query.limit())
+        .bind(query.offset())
+        .map(|row: PgRow| Note {
             id: row.get("id"),
-            content: row.get("content"),
-            css: row.get("css"),
+            slug: row.get("slug"),
+            content: None,
+            footer: row.get("footer"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             file_name: row.get("file_name"),
-            content_nav: row.get("content_nav"),
+            public: row.get("public"),
         })
         .fetch_all(&self.connection)
         .await
         {
-            Ok(file) => Ok(Files { vec_files: file }),
+            Ok(note) => Ok((note, total)),
             Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
         }
     }
 
-    pub async fn get_file(&self, id: String) -> Result<File, handle_errors::Error> {
+    async fn get_file_user(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Files, handle_errors::Error> {
         match sqlx::query(
-            "SELECT id, content, css, user_name, directory, file_name, content_nav
-            FROM file 
-            WHERE id = $1;",
+            "SELECT * from file
+        WHERE user_name = $1 AND directory = $2",
         )
-        .bind(id)
+        .bind(user_name)
+        .bind(directory)
         .map(|row: PgRow| File {
             id: row.get("id"),
             content: row.get("content"),
@@ -160,26 +329,20 @@ impl Store {
             file_name: row.get("file_name"),
             content_nav: row.get("content_nav"),
         })
-        .fetch_one(&self.connection)
+        .fetch_all(&self.connection)
         .await
         {
-            Ok(file) => Ok(file),
+            Ok(file) => Ok(Files { vec_files: file }),
             Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
         }
     }
 
-    pub async fn update_content(
-        &self,
-        id: String,
-        content: String,
-    ) -> Result<File, handle_errors::Error> {
+    async fn get_file(&self, id: String) -> Result<File, handle_errors::Error> {
         match sqlx::query(
-            "UPDATE file
-            SET content = $1
-            WHERE id = $2
-            RETURNING id, content, css, user_name, directory, file_name, content_nav;",
+            "SELECT id, content, css, user_name, directory, file_name, content_nav
+            FROM file 
+            WHERE id = $1;",
         )
-        .bind(content)
         .bind(id)
         .map(|row: PgRow| File {
             id: row.get("id"),
@@ -198,7 +361,7 @@ impl Store {
         }
     }
 
-    pub async fn update_file_name(
+    async fn update_file_name(
         &self,
         id: String,
         file_name: String,
@@ -230,7 +393,7 @@ impl Store {
         }
     }
 
-    pub async fn update_content_and_css(
+    async fn update_content_and_css(
         &self,
         id: String,
         content: String,
@@ -264,7 +427,7 @@ impl Store {
         }
     }
 
-    pub async fn delete_file(&self, id: String) -> Result<File, handle_errors::Error> {
+    async fn delete_file(&self, id: String) -> Result<File, handle_errors::Error> {
         match sqlx::query(
             "DELETE FROM file
             Where id = $1
@@ -288,15 +451,16 @@ impl Store {
         }
     }
 
-    pub async fn delete_directory(&self, id: String) -> Result<Directory, handle_errors::Error> {
+    async fn delete_directory(&self, id: String) -> Result<Directory, handle_errors::Error> {
         match sqlx::query(
             "DELETE FROM directory
             Where id = $1
-           RETURNING id, user_name, directory, public, description note_order",
+           RETURNING id, slug, user_name, directory, public, description, note_order",
         )
         .bind(id)
         .map(|row: PgRow| Directory {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             public: row.get("public"),
@@ -311,7 +475,7 @@ impl Store {
         }
     }
 
-    pub async fn update_directory(
+    async fn update_directory(
         &self,
         public: bool,
         description: String,
@@ -321,13 +485,14 @@ impl Store {
             "UPDATE directory 
             SET public = $1, description = $2
             WHERE id = $3
-            RETURNING id, user_name, directory, public, description note_order",
+            RETURNING id, slug, user_name, directory, public, description, note_order",
         )
         .bind(public)
         .bind(description)
         .bind(id)
         .map(|row: PgRow| Directory {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             public: row.get("public"),
@@ -342,23 +507,24 @@ impl Store {
         }
     }
 
-    pub async fn update_note_name(
+    async fn update_note_name(
         &self,
         id: String,
         file_name: String,
-        new_id: String,
+        slug: String,
     ) -> Result<Note, handle_errors::Error> {
         match sqlx::query(
             "UPDATE note
-            SET id = $1, file_name = $2
+            SET slug = $1, file_name = $2
             WHERE id = $3
-             RETURNING id, user_name, directory, file_name, content, footer, public",
+             RETURNING id, slug, user_name, directory, file_name, content, footer, public",
         )
-        .bind(new_id)
+        .bind(slug)
         .bind(file_name)
         .bind(id)
         .map(|row: PgRow| Note {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             file_name: row.get("file_name"),
@@ -374,7 +540,7 @@ impl Store {
         }
     }
 
-    pub async fn update_note_state(
+    async fn update_note_state(
         &self,
         id: String,
         public: bool,
@@ -399,7 +565,7 @@ impl Store {
         }
     }
 
-    pub async fn get_note_user(
+    async fn get_note_user(
         &self,
         user_name: &str,
         directory: &str,
@@ -412,6 +578,7 @@ impl Store {
         .bind(directory)
         .map(|row: PgRow| Note {
             id: row.get("id"),
+            slug: row.get("slug"),
             content: row.get("content"),
             footer: row.get("footer"),
             user_name: row.get("user_name"),
@@ -427,7 +594,7 @@ impl Store {
         }
     }
 
-    pub async fn get_note_name_by_dir(
+    async fn get_note_name_by_dir(
         &self,
         user_name: &str,
         directory: &str,
@@ -450,7 +617,7 @@ impl Store {
         }
     }
 
-    pub async fn get_notelist_user(
+    async fn get_notelist_user(
         &self,
     ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
         match sqlx::query(
@@ -471,7 +638,7 @@ impl Store {
         }
     }
 
-    pub async fn update_the_note(
+    async fn update_the_note(
         &self,
         content: serde_json::Value,
         id: String,
@@ -480,12 +647,13 @@ impl Store {
             "UPDATE note 
             SET content = $1
             WHERE id = $2
-            RETURNING id, user_name, directory, file_name, content, footer, public",
+            RETURNING id, slug, user_name, directory, file_name, content, footer, public",
         )
         .bind(content)
         .bind(id)
         .map(|row: PgRow| Note {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             file_name: row.get("file_name"),
@@ -501,7 +669,7 @@ impl Store {
         }
     }
 
-    pub async fn get_note(&self, id: String) -> Result<Note, handle_errors::Error> {
+    async fn get_note(&self, id: String) -> Result<Note, handle_errors::Error> {
         match sqlx::query(
             "SELECT id, user_name, directory, file_name, content, footer, public
             FROM note
@@ -510,6 +678,7 @@ impl Store {
         .bind(id)
         .map(|row: PgRow| Note {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             file_name: row.get("file_name"),
@@ -525,7 +694,7 @@ impl Store {
         }
     }
 
-    pub async fn get_note_date(
+    async fn get_note_date(
         &self,
         id: String,
     ) -> Result<chrono::DateTime<Utc>, handle_errors::Error> {
@@ -547,7 +716,7 @@ impl Store {
         }
     }
 
-    pub async fn update_note_order(
+    async fn update_note_order(
         &self,
         dir_id: String,
         order_list: Vec<String>,
@@ -572,7 +741,7 @@ impl Store {
         }
     }
 
-    pub async fn update_note_date(
+    async fn update_note_date(
         &self,
         id: String,
         date: chrono::DateTime<Utc>,
@@ -597,29 +766,16 @@ impl Store {
         }
     }
 
-    pub async fn delete_folder_note(&self, folder_name: &str) -> Result<(), handle_errors::Error> {
-        match sqlx::query(
-            "DELETE FROM note
-            Where directory = $1",
-        )
-        .bind(folder_name)
-        .execute(&self.connection)
-        .await
-        {
-            Ok(_) => Ok(()),
-            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
-        }
-    }
-
-    pub async fn delete_note(&self, id: &str) -> Result<Note, handle_errors::Error> {
+    async fn delete_note(&self, id: &str) -> Result<Note, handle_errors::Error> {
         match sqlx::query(
             "DELETE FROM note
             Where id = $1
-            RETURNING id, user_name, directory, file_name, content, footer, public",
+            RETURNING id, slug, user_name, directory, file_name, content, footer, public",
         )
         .bind(id)
         .map(|row: PgRow| Note {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             file_name: row.get("file_name"),
@@ -635,11 +791,12 @@ impl Store {
         }
     }
 
-    pub async fn add_note(&self, note: Note) -> Result<Note, handle_errors::Error> {
+    async fn add_note(&self, note: Note) -> Result<Note, handle_errors::Error> {
         match sqlx::query(
-            "INSERT INTO note (id, user_name, directory, file_name, content, footer, public)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
-            RETURNING id, user_name, directory, file_name, content, footer, public",
+            "INSERT INTO note (id, slug, user_name, directory, file_name, content, footer, public, directory_id)
+            VALUES ($1, $8, $2, $3, $4, $5, $6, $7,
+                (SELECT id FROM directory WHERE user_name = $2 AND directory = $3))
+            RETURNING id, slug, user_name, directory, file_name, content, footer, public",
         )
         .bind(note.id)
         .bind(note.user_name)
@@ -648,8 +805,10 @@ impl Store {
         .bind(note.content)
         .bind(note.footer)
         .bind(note.public)
+        .bind(note.slug)
         .map(|row: PgRow| Note {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             file_name: row.get("file_name"),
@@ -665,16 +824,17 @@ impl Store {
         }
     }
 
-    pub async fn add_directory(
+    async fn add_directory(
         &self,
         directory: Directory,
     ) -> Result<Directory, handle_errors::Error> {
         match sqlx::query(
-            "INSERT INTO directory (id, user_name, directory, public, description, note_order)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, user_name, directory, public, description, note_order",
+            "INSERT INTO directory (id, slug, user_name, directory, public, description, note_order)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, slug, user_name, directory, public, description, note_order",
         )
         .bind(directory.id)
+        .bind(directory.slug)
         .bind(directory.user_name)
         .bind(directory.directory)
         .bind(directory.public)
@@ -682,6 +842,7 @@ impl Store {
             .bind(directory.note_order)
         .map(|row: PgRow| Directory {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             public: row.get("public"),
@@ -696,7 +857,7 @@ impl Store {
         }
     }
 
-    pub async fn get_directory(self, id: &str) -> Result<Directory, handle_errors::Error> {
+    async fn get_directory(&self, id: &str) -> Result<Directory, handle_errors::Error> {
         match sqlx::query(
             "SELECT * from directory
         WHERE id = $1",
@@ -704,6 +865,7 @@ impl Store {
         .bind(id)
         .map(|row: PgRow| Directory {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             public: row.get("public"),
@@ -718,8 +880,57 @@ impl Store {
         }
     }
 
-    pub async fn get_directory_user(
-        self,
+    async fn get_directory_by_name(
+        &self,
+        user_name: &str,
+        directory: &str,
+    ) -> Result<Directory, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM directory WHERE user_name = $1 AND directory = $2")
+            .bind(user_name)
+            .bind(directory)
+            .map(|row: PgRow| Directory {
+                id: row.get("id"),
+                slug: row.get("slug"),
+                user_name: row.get("user_name"),
+                directory: row.get("directory"),
+                public: row.get("public"),
+                description: row.get("description"),
+                note_order: row.get("note_order"),
+            })
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(directory) => Ok(directory),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn resolve_directory_id(&self, id: &str) -> Result<String, handle_errors::Error> {
+        match sqlx::query("SELECT id FROM directory WHERE id = $1 OR slug = $1")
+            .bind(id)
+            .map(|row: PgRow| row.get("id"))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(id) => Ok(id),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn resolve_note_id(&self, id: &str) -> Result<String, handle_errors::Error> {
+        match sqlx::query("SELECT id FROM note WHERE id = $1 OR slug = $1")
+            .bind(id)
+            .map(|row: PgRow| row.get("id"))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(id) => Ok(id),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_directory_user(
+        &self,
         user_name: &str,
     ) -> Result<Vec<Directory>, handle_errors::Error> {
         match sqlx::query(
@@ -729,6 +940,7 @@ impl Store {
         .bind(user_name)
         .map(|row: PgRow| Directory {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             public: row.get("public"),
@@ -743,13 +955,32 @@ impl Store {
         }
     }
 
-    pub async fn get_directory_pub(self) -> Result<Vec<Directory>, handle_errors::Error> {
-        match sqlx::query(
-            "SELECT * from directory
-        WHERE public = true",
-        )
+    async fn get_directory_pub(
+        &self,
+        query: &ListQuery,
+    ) -> Result<(Vec<Directory>, i64), handle_errors::Error> {
+        let condition = "WHERE public = true AND ($1::text IS NULL OR user_name = $1)";
+        let order_by = query.order_by(
+            &[("name", "directory"), ("author", "user_name"), ("id", "id")],
+            "id",
+        );
+        let total: i64 = match sqlx::query(&format!("SELECT COUNT(*) from directory {condition}"))
+            .bind(&query.author)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(row) => row.get(0),
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        match sqlx::query(&format!(
+            "SELECT * from directory {condition} {order_by} LIMIT $2 OFFSET $3"
+        ))
+        .bind(&query.author)
+        .bind(query.limit())
+        .bind(query.offset())
         .map(|row: PgRow| Directory {
             id: row.get("id"),
+            slug: row.get("slug"),
             user_name: row.get("user_name"),
             directory: row.get("directory"),
             public: row.get("public"),
@@ -759,12 +990,12 @@ impl Store {
         .fetch_all(&self.connection)
         .await
         {
-            Ok(directory) => Ok(directory),
+            Ok(directory) => Ok((directory, total)),
             Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
         }
     }
 
-    pub async fn add_account(self, account: Account) -> Result<bool, handle_errors::Error> {
+    async fn add_account(&self, account: Account) -> Result<bool, handle_errors::Error> {
         match sqlx::query(
             "INSERT INTO accounts (user_name, email, password)
             VALUES ($1, $2, $3)",
@@ -794,7 +1025,7 @@ impl Store {
         }
     }
 
-    pub async fn get_account(self, email: String) -> Result<Account, handle_errors::Error> {
+    async fn get_account(&self, email: String) -> Result<Account, handle_errors::Error> {
         match sqlx::query("SELECT * from accounts WHERE email = $1")
             .bind(email)
             .map(|row: PgRow| Account {
@@ -810,11 +1041,12 @@ impl Store {
         }
     }
 
-    pub async fn get_newinterpretations(
-        self,
+    async fn get_newinterpretations(
+        &self,
     ) -> Result<Vec<otherlawresource::NewInter>, handle_errors::Error> {
         match sqlx::query("SELECT * from newinters")
             .map(|row: PgRow| otherlawresource::NewInter {
+                provenance: Provenance::from_row(&row, "source"),
                 id: row.get("id"),
                 casename: row.get("casename"),
                 casesummary: row.get("casesummary"),
@@ -836,35 +1068,14 @@ impl Store {
         }
     }
 
-    pub async fn get_newinterpretations_list(
-        &self,
-    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
-        match sqlx::query("SELECT id, name FROM newinters")
-            .map(|row: PgRow| {
-                let year: String = row.get("year");
-                let number: String = row.get("number");
-                let name = format!("{}憲判{}", year, number);
-                otherlawresource::OtherSourceList {
-                    id: row.get("id"),
-                    name,
-                    sourcetype: "newinterpretation".to_string(),
-                }
-            })
-            .fetch_all(&self.connection)
-            .await
-        {
-            Ok(list) => Ok(list),
-            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
-        }
-    }
-
-    pub async fn get_newinterpretation_by_id(
+    async fn get_newinterpretation_by_id(
         &self,
         id: String,
     ) -> Result<otherlawresource::NewInter, handle_errors::Error> {
         match sqlx::query("SELECT * FROM newinters WHERE id = $1")
             .bind(id)
             .map(|row: PgRow| otherlawresource::NewInter {
+                provenance: Provenance::from_row(&row, "source"),
                 id: row.get("id"),
                 casename: row.get("casename"),
                 casesummary: row.get("casesummary"),
@@ -886,11 +1097,12 @@ impl Store {
         }
     }
 
-    pub async fn get_all_resolution(
-        self,
+    async fn get_all_resolution(
+        &self,
     ) -> Result<Vec<otherlawresource::Resolution>, handle_errors::Error> {
         match sqlx::query("SELECT * FROM resolution")
             .map(|row: PgRow| otherlawresource::Resolution {
+                provenance: Provenance::from_row(&row, "source"),
                 id: row.get("id"),
                 lawtype: row.get("lawtype"),
                 related_law: row.get("related_law"),
@@ -899,6 +1111,9 @@ impl Store {
                 source: row.get("source"),
                 year: row.get("year"),
                 time: row.get("time"),
+                reflawid: row.try_get("reflawid").unwrap_or_default(),
+                refinter: row.try_get("refinter").unwrap_or_default(),
+                refprecedent: row.try_get("refprecedent").unwrap_or_default(),
             })
             .fetch_all(&self.connection)
             .await
@@ -908,30 +1123,14 @@ impl Store {
         }
     }
 
-    pub async fn get_resolutions_list(
-        &self,
-    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
-        match sqlx::query("SELECT id, name FROM resolution")
-            .map(|row: PgRow| otherlawresource::OtherSourceList {
-                id: row.get("id"),
-                name: row.get("name"),
-                sourcetype: "resolution".to_string(),
-            })
-            .fetch_all(&self.connection)
-            .await
-        {
-            Ok(list) => Ok(list),
-            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
-        }
-    }
-
-    pub async fn get_resolution_by_id(
+    async fn get_resolution_by_id(
         &self,
         id: String,
     ) -> Result<otherlawresource::Resolution, handle_errors::Error> {
         match sqlx::query("SELECT * FROM resolution WHERE id = $1")
             .bind(id)
             .map(|row: PgRow| otherlawresource::Resolution {
+                provenance: Provenance::from_row(&row, "source"),
                 id: row.get("id"),
                 lawtype: row.get("lawtype"),
                 related_law: row.get("related_law"),
@@ -940,6 +1139,9 @@ impl Store {
                 source: row.get("source"),
                 year: row.get("year"),
                 time: row.get("time"),
+                reflawid: row.try_get("reflawid").unwrap_or_default(),
+                refinter: row.try_get("refinter").unwrap_or_default(),
+                refprecedent: row.try_get("refprecedent").unwrap_or_default(),
             })
             .fetch_one(&self.connection)
             .await
@@ -949,11 +1151,12 @@ impl Store {
         }
     }
 
-    pub async fn get_all_oldinterpretation(
+    async fn get_all_oldinterpretation(
         &self,
     ) -> Result<Vec<otherlawresource::OldInterpretation>, handle_errors::Error> {
         match sqlx::query("SELECT * FROM oldinters")
             .map(|row: PgRow| otherlawresource::OldInterpretation {
+                provenance: Provenance::from_row(&row, "source"),
                 id: row.get("id"),
                 date: row.get("date"),
                 reasoning: row.get("reasoning"),
@@ -973,34 +1176,14 @@ impl Store {
         }
     }
 
-    pub async fn get_oldinterpretations_list(
-        &self,
-    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
-        match sqlx::query("SELECT id FROM oldinters")
-            .map(|row: PgRow| {
-                let id: String = row.get("id");
-                let name = format!("釋字{}", id.clone());
-                otherlawresource::OtherSourceList {
-                    id: id.clone(),
-                    name: name,
-                    sourcetype: "oldinterpretation".to_string(),
-                }
-            })
-            .fetch_all(&self.connection)
-            .await
-        {
-            Ok(list) => Ok(list),
-            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
-        }
-    }
-
-    pub async fn get_oldinter_by_id(
+    async fn get_oldinter_by_id(
         &self,
         id: String,
     ) -> Result<otherlawresource::OldInterpretation, handle_errors::Error> {
         match sqlx::query("SELECT * FROM oldinters WHERE id = $1")
             .bind(id)
             .map(|row: PgRow| otherlawresource::OldInterpretation {
+                provenance: Provenance::from_row(&row, "source"),
                 id: row.get("id"),
                 date: row.get("date"),
                 reasoning: row.get("reasoning"),
@@ -1020,11 +1203,12 @@ impl Store {
         }
     }
 
-    pub async fn get_all_precedents(
+    async fn get_all_precedents(
         &self,
     ) -> Result<Vec<otherlawresource::Precedent>, handle_errors::Error> {
         match sqlx::query("SELECT * FROM precedents")
             .map(|row: PgRow| otherlawresource::Precedent {
+                provenance: Provenance::from_row(&row, "source"),
                 id: row.get("id"),
                 name: row.get("name"),
                 holding: row.get("holding"),
@@ -1032,6 +1216,11 @@ impl Store {
                 year: row.get("year"),
                 num: row.get("num"),
                 specific: row.get("specific"),
+                court: row.get("court"),
+                case_type: row.get("case_type"),
+                reflawid: row.try_get("reflawid").unwrap_or_default(),
+                refinter: row.try_get("refinter").unwrap_or_default(),
+                refprecedent: row.try_get("refprecedent").unwrap_or_default(),
             })
             .fetch_all(&self.connection)
             .await
@@ -1041,30 +1230,14 @@ impl Store {
         }
     }
 
-    pub async fn get_precedentlist(
-        &self,
-    ) -> Result<Vec<otherlawresource::OtherSourceList>, handle_errors::Error> {
-        match sqlx::query("SELECT id, name FROM precedents")
-            .map(|row: PgRow| otherlawresource::OtherSourceList {
-                id: row.get("id"),
-                name: row.get("name"),
-                sourcetype: "precedent".to_string(),
-            })
-            .fetch_all(&self.connection)
-            .await
-        {
-            Ok(list) => Ok(list),
-            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
-        }
-    }
-
-    pub async fn get_precedent_by_id(
+    async fn get_precedent_by_id(
         &self,
         id: String,
     ) -> Result<otherlawresource::Precedent, handle_errors::Error> {
         match sqlx::query("SELECT * FROM precedents WHERE id = $1")
             .bind(id)
             .map(|row: PgRow| otherlawresource::Precedent {
+                provenance: Provenance::from_row(&row, "source"),
                 id: row.get("id"),
                 name: row.get("name"),
                 holding: row.get("holding"),
@@ -1072,6 +1245,11 @@ impl Store {
                 year: row.get("year"),
                 num: row.get("num"),
                 specific: row.get("specific"),
+                court: row.get("court"),
+                case_type: row.get("case_type"),
+                reflawid: row.try_get("reflawid").unwrap_or_default(),
+                refinter: row.try_get("refinter").unwrap_or_default(),
+                refprecedent: row.try_get("refprecedent").unwrap_or_default(),
             })
             .fetch_one(&self.connection)
             .await
@@ -1081,13 +1259,14 @@ impl Store {
         }
     }
 
-    pub async fn get_historylaw(
+    async fn get_historylaw(
         &self,
         lawid: String,
     ) -> Result<Vec<otherlawresource::HistoryLaw>, handle_errors::Error> {
         match sqlx::query("SELECT * FROM history_law WHERE lawid  = $1")
             .bind(lawid)
             .map(|row: PgRow| otherlawresource::HistoryLaw {
+                provenance: Provenance::from_row(&row, "source"),
                 id: row.get("id"),
                 lawid: row.get("lawid"),
                 date: row.get("date"),
@@ -1102,7 +1281,241 @@ impl Store {
         }
     }
 
-    pub async fn add_library(&self, library: Library) -> Result<Library, handle_errors::Error> {
+    // 整部法規的沿革，lawid 形如「民法-184」
+    async fn get_historylaw_by_chapter(
+        &self,
+        chapter: &str,
+    ) -> Result<Vec<otherlawresource::HistoryLaw>, handle_errors::Error> {
+        match sqlx::query("SELECT * FROM history_law WHERE lawid LIKE $1 || '-%'")
+            .bind(chapter)
+            .map(|row: PgRow| otherlawresource::HistoryLaw {
+                provenance: Provenance::from_row(&row, "source"),
+                id: row.get("id"),
+                lawid: row.get("lawid"),
+                date: row.get("date"),
+                content: row.get("content"),
+                no: row.get("no"),
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_law_aliases(
+        &self,
+    ) -> Result<Vec<new_law::alias::LawAlias>, handle_errors::Error> {
+        match new_law::alias::get_law_aliases(&self.connection).await {
+            Ok(list) => Ok(list),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_law_alias(
+        &self,
+        alias: new_law::alias::LawAlias,
+    ) -> Result<new_law::alias::LawAlias, handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO law_alias (alias, name)
+            VALUES ($1, $2)
+            ON CONFLICT (alias) DO UPDATE
+            SET name = EXCLUDED.name
+            RETURNING alias, name",
+        )
+        .bind(&alias.alias)
+        .bind(&alias.name)
+        .map(|row: PgRow| new_law::alias::LawAlias {
+            alias: row.get("alias"),
+            name: row.get("name"),
+        })
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(alias) => Ok(alias),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn delete_law_alias(&self, alias: &str) -> Result<(), handle_errors::Error> {
+        match sqlx::query("DELETE FROM law_alias WHERE alias = $1")
+            .bind(alias)
+            .execute(&self.connection)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    // 先清掉該筆記原有的引用，再整批寫入
+    async fn update_note_references(
+        &self,
+        note_id: &str,
+        refs: &[note::Reference],
+    ) -> Result<(), handle_errors::Error> {
+        let ref_types: Vec<String> = refs.iter().map(|r| r.ref_type.clone()).collect();
+        let ref_ids: Vec<String> = refs.iter().map(|r| r.ref_id.clone()).collect();
+
+        let mut tx = match self.connection.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        if let Err(e) = sqlx::query("DELETE FROM note_reference WHERE note_id = $1")
+            .bind(note_id)
+            .execute(&mut tx)
+            .await
+        {
+            return Err(handle_errors::Error::DatabaseQueryError(e));
+        }
+        if let Err(e) = sqlx::query(
+            "INSERT INTO note_reference (note_id, ref_type, ref_id)
+            SELECT $1, ref_type, ref_id
+            FROM UNNEST($2::text[], $3::text[]) AS r(ref_type, ref_id)
+            ON CONFLICT DO NOTHING",
+        )
+        .bind(note_id)
+        .bind(ref_types)
+        .bind(ref_ids)
+        .execute(&mut tx)
+        .await
+        {
+            return Err(handle_errors::Error::DatabaseQueryError(e));
+        }
+        match tx.commit().await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    // 只回傳公開筆記，或 user_name 本人的筆記；不帶 content
+    async fn get_notes_by_reference(
+        &self,
+        ref_type: &str,
+        ref_id: &str,
+        user_name: Option<String>,
+    ) -> Result<Vec<Note>, handle_errors::Error> {
+        match sqlx::query(
+            "SELECT note.id, note.slug, note.user_name, note.directory, note.file_name, note.footer, note.public
+            FROM note
+            JOIN note_reference ON note.id = note_reference.note_id
+            WHERE note_reference.ref_type = $1
+            AND note_reference.ref_id = $2
+            AND (note.public OR note.user_name = $3)
+            ORDER BY note.id",
+        )
+        .bind(ref_type)
+        .bind(ref_id)
+        .bind(user_name)
+        .map(|row: PgRow| Note {
+            id: row.get("id"),
+            slug: row.get("slug"),
+            user_name: row.get("user_name"),
+            directory: row.get("directory"),
+            file_name: row.get("file_name"),
+            footer: row.get("footer"),
+            content: None,
+            public: row.get("public"),
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(notes) => Ok(notes),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    // 每次檢查都以新的結果覆蓋該筆記的標記
+    async fn replace_stale_cards(
+        &self,
+        note_id: &str,
+        cards: &[StaleCard],
+    ) -> Result<(), handle_errors::Error> {
+        let mut tx = match self.connection.begin().await {
+            Ok(tx) => tx,
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        if let Err(e) = sqlx::query("DELETE FROM note_stale_card WHERE note_id = $1")
+            .bind(note_id)
+            .execute(&mut tx)
+            .await
+        {
+            return Err(handle_errors::Error::DatabaseQueryError(e));
+        }
+        for card in cards {
+            if let Err(e) = sqlx::query(
+                "INSERT INTO note_stale_card (note_id, block_index, law_id, card_lines, current_lines, checked_at)
+                VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(&card.note_id)
+            .bind(card.block_index)
+            .bind(&card.law_id)
+            .bind(serde_json::to_value(&card.card_lines).unwrap())
+            .bind(serde_json::to_value(&card.current_lines).unwrap())
+            .bind(Utc::now())
+            .execute(&mut tx)
+            .await
+            {
+                return Err(handle_errors::Error::DatabaseQueryError(e));
+            }
+        }
+        match tx.commit().await {
+            Ok(_) => Ok(()),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn get_stale_cards(
+        &self,
+        note_id: &str,
+    ) -> Result<Vec<StaleCard>, handle_errors::Error> {
+        match sqlx::query(
+            "SELECT note_id, block_index, law_id, card_lines, current_lines
+            FROM note_stale_card
+            WHERE note_id = $1
+            ORDER BY block_index",
+        )
+        .bind(note_id)
+        .map(|row: PgRow| StaleCard {
+            note_id: row.get("note_id"),
+            block_index: row.get("block_index"),
+            law_id: row.get("law_id"),
+            card_lines: serde_json::from_value(row.get("card_lines")).unwrap_or_default(),
+            current_lines: serde_json::from_value(row.get("current_lines")).unwrap_or_default(),
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(cards) => Ok(cards),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_note_revision(
+        &self,
+        note_id: &str,
+        content: serde_json::Value,
+        reason: &str,
+    ) -> Result<(), handle_errors::Error> {
+        match sqlx::query(
+            "INSERT INTO note_revision (id, note_id, content, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(note_id)
+        .bind(content)
+        .bind(reason)
+        .bind(Utc::now())
+        .execute(&self.connection)
+        .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    async fn add_library(&self, library: Library) -> Result<Library, handle_errors::Error> {
         match sqlx::query(
             "INSERT INTO library (id, library_name, user_name, public)
          VALUES ($1, $2, $3, $4)
@@ -1126,7 +1539,7 @@ impl Store {
         }
     }
 
-    pub async fn add_library_item(
+    async fn add_library_item(
         &self,
         item: LibraryItem,
     ) -> Result<LibraryItem, handle_errors::Error> {
@@ -1157,8 +1570,8 @@ impl Store {
         }
     }
 
-    pub async fn get_library_user(
-        self,
+    async fn get_library_user(
+        &self,
         user_name: &str,
     ) -> Result<Vec<Library>, handle_errors::Error> {
         match sqlx::query(
@@ -1180,8 +1593,8 @@ impl Store {
         }
     }
 
-    pub async fn get_item_by_library(
-        self,
+    async fn get_item_by_library(
+        &self,
         library_id: &str,
     ) -> Result<Vec<LibraryItem>, handle_errors::Error> {
         match sqlx::query(
@@ -1206,7 +1619,7 @@ impl Store {
         }
     }
 
-    pub async fn delete_dictionary(&self, id: &str) -> Result<Dictionary, handle_errors::Error> {
+    async fn delete_dictionary(&self, id: &str) -> Result<Dictionary, handle_errors::Error> {
         match sqlx::query(
             "DELETE FROM dictionary
             Where id = $1
@@ -1226,7 +1639,7 @@ impl Store {
         }
     }
 
-    pub async fn add_dictionary(
+    async fn add_dictionary(
         &self,
         user_name: &str,
         dictionary_name: &str,
@@ -1253,7 +1666,7 @@ impl Store {
         }
     }
 
-    pub async fn get_dictionary(&self, id: &str) -> Result<Dictionary, handle_errors::Error> {
+    async fn get_dictionary(&self, id: &str) -> Result<Dictionary, handle_errors::Error> {
         match sqlx::query(
             "SELECT * from dictionary
         WHERE id = $1",
@@ -1272,7 +1685,7 @@ impl Store {
         }
     }
 
-    pub async fn get_dictionary_by_user(
+    async fn get_dictionary_by_user(
         &self,
         user_name: &str,
     ) -> Result<Vec<Dictionary>, handle_errors::Error> {
@@ -1294,15 +1707,29 @@ impl Store {
         }
     }
 
-    pub async fn get_vocabitem_user(
+    async fn get_vocabitem_user(
         &self,
         user_name: &str,
-    ) -> Result<Vec<VocabItem>, handle_errors::Error> {
-        match sqlx::query(
-            "SELECT * from vocab_item
-        WHERE user_name = $1",
-        )
+        query: &ListQuery,
+    ) -> Result<(Vec<VocabItem>, i64), handle_errors::Error> {
+        let condition = "WHERE user_name = $1 AND ($2::text IS NULL OR dictionary = $2)";
+        let order_by = query.order_by(&[("term", "term"), ("id", "id")], "id");
+        let total: i64 = match sqlx::query(&format!("SELECT COUNT(*) from vocab_item {condition}"))
+            .bind(user_name)
+            .bind(&query.dictionary)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(row) => row.get(0),
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        match sqlx::query(&format!(
+            "SELECT * from vocab_item {condition} {order_by} LIMIT $3 OFFSET $4"
+        ))
         .bind(user_name)
+        .bind(&query.dictionary)
+        .bind(query.limit())
+        .bind(query.offset())
         .map(|row: PgRow| VocabItem {
             id: row.get("id"),
             user_name: row.get("user_name"),
@@ -1313,12 +1740,12 @@ impl Store {
         .fetch_all(&self.connection)
         .await
         {
-            Ok(items) => Ok(items),
+            Ok(items) => Ok((items, total)),
             Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
         }
     }
 
-    pub async fn get_vocabitem_dictionary(
+    async fn get_vocabitem_dictionary(
         &self,
         dictionary: &str,
     ) -> Result<Vec<VocabItem>, handle_errors::Error> {
@@ -1342,7 +1769,7 @@ impl Store {
         }
     }
 
-    pub async fn get_vocabitem_term(
+    async fn get_vocabitem_term(
         &self,
         term: &str,
     ) -> Result<Vec<VocabItem>, handle_errors::Error> {
@@ -1363,7 +1790,7 @@ impl Store {
         }
     }
 
-    pub async fn get_vocabitem_def(
+    async fn get_vocabitem_def(
         &self,
         def: &str,
     ) -> Result<Vec<VocabItem>, handle_errors::Error> {
@@ -1384,7 +1811,7 @@ impl Store {
         }
     }
 
-    pub async fn add_vocabitem(&self, item: VocabItem) -> Result<VocabItem, handle_errors::Error> {
+    async fn add_vocabitem(&self, item: VocabItem) -> Result<VocabItem, handle_errors::Error> {
         match sqlx::query(
             "INSERT INTO vocab_item (id, user_name, term, definition, dictionary)
          VALUES ($1, $2, $3, $4, $5)
@@ -1410,7 +1837,7 @@ impl Store {
         }
     }
 
-    pub async fn delete_vocabitem(&self, id: &str) -> Result<VocabItem, handle_errors::Error> {
+    async fn delete_vocabitem(&self, id: &str) -> Result<VocabItem, handle_errors::Error> {
         match sqlx::query(
             "DELETE FROM vocab_item
          WHERE id = $1
@@ -1432,7 +1859,7 @@ impl Store {
         }
     }
 
-    pub async fn update_vocabitem(
+    async fn update_vocabitem(
         &self,
         item: VocabItem,
     ) -> Result<VocabItem, handle_errors::Error> {
@@ -1461,7 +1888,7 @@ impl Store {
         }
     }
 
-    pub async fn add_vocabitem_law(
+    async fn add_vocabitem_law(
         &self,
         item: VocabItemLaw,
     ) -> Result<VocabItemLaw, handle_errors::Error> {
@@ -1484,7 +1911,7 @@ impl Store {
         }
     }
 
-    pub async fn get_vocabitems_by_law_id(
+    async fn get_vocabitems_by_law_id(
         &self,
         law_id: &str,
     ) -> Result<Vec<VocabItem>, handle_errors::Error> {
@@ -1510,7 +1937,7 @@ impl Store {
         }
     }
 
-    pub async fn get_law_ids_by_vocabitem(
+    async fn get_law_ids_by_vocabitem(
         &self,
         vocabitem_id: &str,
     ) -> Result<Vec<String>, handle_errors::Error> {
@@ -1525,7 +1952,7 @@ impl Store {
         }
     }
 
-    pub async fn get_laws_by_vocabitem(
+    async fn get_laws_by_vocabitem(
         &self,
         item_id: &str,
     ) -> Result<Vec<new_law::NewLaw>, handle_errors::Error> {
@@ -1547,6 +1974,7 @@ WHERE vocabitem_law.vocabitem_id = $1
                 chapter: row.get("chapter"),
                 num: row.get("num"),
                 lines,
+                provenance: Provenance::from_row(&row, "source_url"),
             }
         })
         .fetch_all(&self.connection)
@@ -1557,7 +1985,7 @@ WHERE vocabitem_law.vocabitem_id = $1
         }
     }
 
-    pub async fn delete_vocabitem_law(
+    async fn delete_vocabitem_law(
         &self,
         item_id: &str,
         law_id: &str,
@@ -1576,4 +2004,60 @@ WHERE vocabitem_law.vocabitem_id = $1
             Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
         }
     }
+
+    // ?type=laws 只看該來源，預設最新的在前
+    async fn get_sync_runs(
+        &self,
+        query: &ListQuery,
+    ) -> Result<(Vec<SyncRun>, i64), handle_errors::Error> {
+        let condition = "WHERE ($1::text IS NULL OR source = $1)";
+        let order_by = ListQuery {
+            order: query.order.clone().or(Some("desc".to_string())),
+            ..query.clone()
+        }
+        .order_by(&[("id", "id"), ("source", "source")], "id");
+        let total: i64 = match sqlx::query(&format!("SELECT COUNT(*) from sync_run {condition}"))
+            .bind(&query.kind)
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(row) => row.get(0),
+            Err(e) => return Err(handle_errors::Error::DatabaseQueryError(e)),
+        };
+        match sqlx::query(&format!(
+            "SELECT * from sync_run {condition} {order_by} LIMIT $2 OFFSET $3"
+        ))
+        .bind(&query.kind)
+        .bind(query.limit())
+        .bind(query.offset())
+        .map(|row: PgRow| SyncRun {
+            id: row.get("id"),
+            source: row.get("source"),
+            started_at: row.get("started_at"),
+            finished_at: row.get("finished_at"),
+            inserted: row.get("inserted"),
+            updated: row.get("updated"),
+            unchanged: row.get("unchanged"),
+            failed: row.get("failed"),
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(runs) => Ok((runs, total)),
+            Err(e) => Err(handle_errors::Error::DatabaseQueryError(e)),
+        }
+    }
+
+    fn export_rows(
+        &self,
+        dataset: &str,
+    ) -> BoxStream<'_, Result<serde_json::Value, handle_errors::Error>> {
+        if !otherlawresource::export::is_dataset(dataset) {
+            let error = handle_errors::Error::DatasetNotFound(dataset.to_string());
+            return stream::once(async { Err(error) }).boxed();
+        }
+        otherlawresource::export::stream_rows(&self.connection, dataset)
+            .map(|row| row.map_err(handle_errors::Error::DatabaseQueryError))
+            .boxed()
+    }
 }
@@ -2,10 +2,23 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Directory {
-    pub id: String, //(user_name + directory),
+    pub id: String, // uuid
+    #[serde(default)]
+    pub slug: String, // user_name-directory，改用 uuid 之前的目錄保留舊版的 id
     pub user_name: String,
     pub directory: String,
     pub public: bool,
     pub description: String,
     pub note_order: Vec<String>,
 }
+
+impl Directory {
+    pub fn make_slug(user_name: &str, directory: &str) -> String {
+        format!("{}-{}", slug_part(user_name), slug_part(directory))
+    }
+}
+
+/// slug 的各部分以「-」連接，名稱中的「%」與「-」先編碼，名稱含「-」時也不會和別的 slug 相同
+pub fn slug_part(part: &str) -> String {
+    part.replace('%', "%25").replace('-', "%2D")
+}
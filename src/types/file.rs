@@ -15,3 +15,23 @@ pub struct File {
 pub struct Files {
     pub vec_files: Vec<File>,
 }
+
+/// 檔案列表用，不含內容與 CSS
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FileEntry {
+    pub id: String,
+    pub user_name: String,
+    pub directory: String,
+    pub file_name: String,
+}
+
+impl From<&File> for FileEntry {
+    fn from(file: &File) -> Self {
+        FileEntry {
+            id: file.id.clone(),
+            user_name: file.user_name.clone(),
+            directory: file.directory.clone(),
+            file_name: file.file_name.clone(),
+        }
+    }
+}
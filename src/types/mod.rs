@@ -5,3 +5,5 @@ pub(crate) mod directory;
 pub mod file;
 mod newinterpretation;
 pub mod note;
+pub mod pagination;
+pub mod sync_run;
@@ -1,8 +1,11 @@
+use crate::types::directory::slug_part;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Note {
-    pub id: String,
+    pub id: String, // uuid
+    #[serde(default)]
+    pub slug: String, // user_name-directory-file_name，改用 uuid 之前的筆記保留舊版的 id
     pub content: Option<serde_json::Value>,
     pub footer: Option<String>,
     pub user_name: String,
@@ -10,3 +13,24 @@ pub struct Note {
     pub file_name: String,
     pub public: bool,
 }
+
+impl Note {
+    pub fn make_slug(user_name: &str, directory: &str, file_name: &str) -> String {
+        format!(
+            "{}-{}-{}",
+            slug_part(user_name),
+            slug_part(directory),
+            slug_part(file_name)
+        )
+    }
+}
+
+// 筆記中內容已和現行條文不同的 law 卡片
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct StaleCard {
+    pub note_id: String,
+    pub block_index: i32,
+    pub law_id: String,
+    pub card_lines: Vec<String>,
+    pub current_lines: Vec<String>,
+}
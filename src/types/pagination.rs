@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_LIMIT: i64 = 50;
+pub const MAX_LIMIT: i64 = 200;
+
+/// 列表共用的查詢參數，例如 `?page=2&limit=50&sort=year&order=desc&year=110&author=xxx&type=民事&court=最高法院&dictionary=xxx`
+/// 沒給 page 與 limit 時維持舊行為回傳全部，總數放在 X-Total-Count header
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct ListQuery {
+    pub page: Option<i64>, // 從 1 開始
+    pub limit: Option<i64>,
+    pub sort: Option<String>,
+    pub order: Option<String>, // asc 或 desc
+    pub year: Option<i16>,
+    pub author: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub court: Option<String>,      // 目前只有判例使用
+    pub dictionary: Option<String>, // 目前只有單字列表使用，值為字典 id
+}
+
+impl ListQuery {
+    pub fn limit(&self) -> Option<i64> {
+        match (self.page, self.limit) {
+            (None, None) => None,
+            (_, limit) => Some(limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)),
+        }
+    }
+
+    pub fn offset(&self) -> i64 {
+        match self.limit() {
+            Some(limit) => (self.page.unwrap_or(1).max(1) - 1).saturating_mul(limit),
+            None => 0,
+        }
+    }
+
+    pub fn descending(&self) -> bool {
+        self.order.as_deref() == Some("desc")
+    }
+
+    /// 排序鍵只接受 allowed 中的名稱，再換成 SQL 欄位，使用者輸入不會直接進到 SQL
+    pub fn order_by(&self, allowed: &[(&str, &'static str)], default: &'static str) -> String {
+        let column = self
+            .sort
+            .as_deref()
+            .and_then(|key| allowed.iter().find(|(name, _)| *name == key))
+            .map(|(_, column)| *column)
+            .unwrap_or(default);
+        let direction = if self.descending() { "DESC" } else { "ASC" };
+        format!("ORDER BY {column} {direction}")
+    }
+
+    /// 記憶體中的列表用的排序鍵，與 order_by 一樣只接受 allowed 中的名稱，其餘用 default
+    /// 沒有指定 sort 與 order 時回傳 None，維持快取原本的順序
+    pub fn sort_key<'a>(&self, allowed: &[&'a str], default: &'a str) -> Option<&'a str> {
+        if self.sort.is_none() && self.order.is_none() {
+            return None;
+        }
+        let key = self
+            .sort
+            .as_deref()
+            .and_then(|key| allowed.iter().find(|name| **name == key))
+            .copied()
+            .unwrap_or(default);
+        Some(key)
+    }
+
+    /// 記憶體中的列表已篩選、排序好後再切頁，回傳該頁與總數
+    pub fn paginate<T>(&self, items: Vec<T>) -> (Vec<T>, i64) {
+        let total = items.len() as i64;
+        let page = match self.limit() {
+            Some(limit) => items
+                .into_iter()
+                .skip(self.offset() as usize)
+                .take(limit as usize)
+                .collect(),
+            None => items,
+        };
+        (page, total)
+    }
+}
+
+/// 回傳該頁的 JSON 陣列，總數放在 header，舊的用戶端不受影響
+pub fn with_total<T: Serialize>(items: &T, total: i64) -> impl warp::Reply {
+    warp::reply::with_header(warp::reply::json(items), "X-Total-Count", total.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::Reply;
+
+    fn query(page: Option<i64>, limit: Option<i64>) -> ListQuery {
+        ListQuery {
+            page,
+            limit,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn paginate_returns_everything_without_page_or_limit() {
+        let (items, total) = query(None, None).paginate((1..=5).collect::<Vec<_>>());
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+        assert_eq!(total, 5);
+    }
+
+    #[test]
+    fn paginate_slices_the_requested_page() {
+        let (items, total) = query(Some(2), Some(2)).paginate((1..=5).collect::<Vec<_>>());
+        assert_eq!(items, vec![3, 4]);
+        assert_eq!(total, 5);
+
+        let (items, _) = query(Some(4), Some(2)).paginate((1..=5).collect::<Vec<_>>());
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn limit_is_defaulted_and_clamped() {
+        assert_eq!(query(Some(1), None).limit(), Some(DEFAULT_LIMIT));
+        assert_eq!(query(None, Some(0)).limit(), Some(1));
+        assert_eq!(query(None, Some(10_000)).limit(), Some(MAX_LIMIT));
+        // page 小於 1 視為第一頁
+        assert_eq!(query(Some(0), Some(10)).offset(), 0);
+        assert_eq!(query(Some(3), Some(10)).offset(), 20);
+    }
+
+    #[test]
+    fn order_by_only_accepts_allowed_keys() {
+        let allowed = [("name", "file_name"), ("id", "id")];
+        let q = ListQuery {
+            sort: Some("name".to_string()),
+            order: Some("desc".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(q.order_by(&allowed, "id"), "ORDER BY file_name DESC");
+        let q = ListQuery {
+            sort: Some("id; DROP TABLE note".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(q.order_by(&allowed, "id"), "ORDER BY id ASC");
+    }
+
+    #[test]
+    fn sort_key_keeps_cache_order_unless_asked() {
+        let allowed = ["year", "court"];
+        assert_eq!(ListQuery::default().sort_key(&allowed, "year"), None);
+        let q = ListQuery {
+            sort: Some("court".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(q.sort_key(&allowed, "year"), Some("court"));
+        let q = ListQuery {
+            sort: Some("unknown".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(q.sort_key(&allowed, "year"), Some("year"));
+        let q = ListQuery {
+            order: Some("desc".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(q.sort_key(&allowed, "year"), Some("year"));
+    }
+
+    #[test]
+    fn with_total_sets_the_count_header() {
+        let response = with_total(&vec![1, 2], 42).into_response();
+        assert_eq!(response.headers()["X-Total-Count"], "42");
+    }
+}